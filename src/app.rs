@@ -1,106 +1,278 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::rc::Rc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use yew::prelude::*;
 use crate::*;
-use web_sys::HtmlTextAreaElement;
+use crate::chart::{view_chart, Chart};
+use crate::export::{download_diagnostic, download_tab, TabExport};
+use crate::graph::{Graph, GraphView};
+use crate::grid_canvas::{GridCanvas, GridRenderer};
+use crate::grid_diff::{diff_tab, GridDiff};
+use crate::inputs::DayInputs;
+use crate::parameters::{Parameter, Parameters};
+use crate::playback::{Playback, MAX_FRAMES_PER_SECOND};
+use crate::search::{count_matches, view_line, Search, SearchMode};
+use crate::table::{Table, TableView};
+use crate::tree::{Tree, TreeView};
+use crate::timing::{format_duration, Stopwatch, Timings};
+use crate::worker::{PuzzleRequest, PuzzleResponse, PuzzleWorker, WorkerRequest, WorkerResponse};
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
 
 pub struct App {
     days: Vec<Day>,
     day_index: usize,
     title_text: String,
-    input: String,
+    inputs: DayInputs,
     silver_output: String,
     gold_output: String,
     diagnostic: Diagnostic,
     tab_index: usize,
     up_to_date: bool,
+    worker: Option<PuzzleWorker>,
+    running: Option<Part>,
+    timings: Option<Timings>,
+    input_ref: NodeRef,
+    /// The lazy tab the worker is building
+    rendering: Option<usize>,
+    playback: Playback,
+    grid_renderer: GridRenderer,
+    search: Search,
+    /// `search` compiled once it changes, `None` if there is nothing to search for
+    search_matcher: Result<Option<Regex>, String>,
+    /// How many matches the lines of each tab have, counted when the search or the tab changes
+    search_matches: Vec<usize>,
+    grid_diff: GridDiff,
+    /// The comparison of the two grid tabs in `grid_diff`, once both are built
+    diff: Option<Result<Rc<Tab>, String>>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DayOutput {
     pub silver_output: String,
     pub gold_output: String,
     pub diagnostic: Diagnostic,
 }
 
-pub type DayFunction = fn(&str) -> DayOutput;
+impl DayOutput {
+    /// The output of a run that failed before the day could answer, e.g. because it panicked.
+    pub fn error(message: String) -> Self {
+        DayOutput {
+            silver_output: "Error".to_string(),
+            gold_output: "Error".to_string(),
+            diagnostic: Diagnostic::simple("Puzzle run failed".to_string()).with_entries(vec![DiagnosticEntry::error(message)]),
+        }
+    }
+}
+
+pub type DayFunction = fn(&str, Part, &Parameters, &mut Stopwatch) -> DayOutput;
+
+/// Which part of a puzzle to compute. Days skip the work for the part that isn't requested.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Part {
+    Silver,
+    Gold,
+    Both,
+}
+
+impl Part {
+    pub fn silver(self) -> bool {
+        matches!(self, Part::Silver | Part::Both)
+    }
+    pub fn gold(self) -> bool {
+        matches!(self, Part::Gold | Part::Both)
+    }
+}
+
+/// An input with known answers, usually an example from the puzzle text. Parts without a known answer are None.
+/// `parameters` overrides the defaults of the day, for examples that are smaller than the real input.
+#[derive(Clone, Copy, Debug)]
+pub struct Example {
+    pub name: &'static str,
+    pub input: &'static str,
+    pub silver: Option<&'static str>,
+    pub gold: Option<&'static str>,
+    pub parameters: &'static [(&'static str, i64)],
+}
+
+impl Example {
+    /// The parts that have a known answer, if any.
+    pub fn part(&self) -> Option<Part> {
+        match (self.silver, self.gold) {
+            (Some(_), Some(_)) => Some(Part::Both),
+            (Some(_), None) => Some(Part::Silver),
+            (None, Some(_)) => Some(Part::Gold),
+            (None, None) => None,
+        }
+    }
+    pub fn parameters(&self, day: &Day) -> Parameters {
+        Parameters::with_overrides(day.parameters, self.parameters.iter().copied())
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Day {
-    pub text: String,
+    pub number: u32,
+    pub title: &'static str,
     pub puzzle: DayFunction,
-    pub index: usize,
+    pub examples: &'static [Example],
+    pub parameters: &'static [Parameter],
+}
+
+impl Day {
+    /// Panics if the parameters are declared wrong, so a broken day fails as soon as the days are listed.
+    pub fn new(number: u32, title: &'static str, puzzle: DayFunction, examples: &'static [Example], parameters: &'static [Parameter]) -> Self {
+        if let Err(error) = Parameter::check_declared(parameters) {
+            panic!("Day {}: {}", number, error);
+        }
+        Day {
+            number,
+            title,
+            puzzle,
+            examples,
+            parameters,
+        }
+    }
+    pub fn text(&self) -> String {
+        format!("Day {}: {}", self.number, self.title)
+    }
+    /// Runs the puzzle. A panic becomes an error output, where it can be caught.
+    pub fn run(&self, input: &str, part: Part, parameters: &Parameters) -> (DayOutput, Timings) {
+        let (output, timings) = self.try_run(input, part, parameters);
+        (output.unwrap_or_else(DayOutput::error), timings)
+    }
+    /// Runs the puzzle, returning a panic as its message instead of as output.
+    pub fn try_run(&self, input: &str, part: Part, parameters: &Parameters) -> (Result<DayOutput, String>, Timings) {
+        let mut stopwatch = Stopwatch::start();
+        let output = panics::catch(|| (self.puzzle)(input, part, parameters, &mut stopwatch))
+            .map_err(|panic| panics::day_panicked(self.number, &panic));
+        (output, stopwatch.finish())
+    }
+    /// The stored example with this input, ignoring surrounding whitespace.
+    pub fn find_example(&self, input: &str) -> Option<&Example> {
+        self.examples.iter().find(|example| example.input.trim() == input.trim())
+    }
+    pub fn default_parameters(&self) -> Parameters {
+        Parameters::new(self.parameters)
+    }
 }
 
 #[derive(Clone, Debug)]
 pub enum AppMessage {
     NewText(String),
     SetDay(usize),
-    Run,
+    SelectSlot(usize),
+    AddSlot,
+    RenameSlot(String),
+    DeleteSlot,
+    SetParameter(&'static str, String),
+    UseExampleParameters(&'static [(&'static str, i64)]),
+    ResetParameters,
+    Highlight(Span),
+    Run(Part),
+    Finished(PuzzleResponse),
+    Cancel,
     TabClicked(usize),
+    TabRendered(usize, Tab),
     TabPrevious,
     TabNext,
     TabLast,
+    PlaybackSelect(usize),
+    PlaybackToggle,
+    PlaybackTick,
+    PlaybackSeek(usize),
+    PlaybackSpeed(u32),
+    SetGridRenderer(GridRenderer),
+    SearchQuery(String),
+    SetSearchMode(SearchMode),
+    SearchOnlyMatching(bool),
+    DiffBefore(Option<usize>),
+    DiffAfter(Option<usize>),
+    DownloadTab(TabExport),
+    DownloadAll,
 }
 
-const LOCAL_STORAGE_INPUT: &'static str = "INPUT";
-const LOCAL_STORAGE_INDEX: &'static str = "INDEX";
-
-fn get_days() -> Vec<Day> {
-    let mut index = 0;
-    let mut days = Vec::new();
-    // days.push(add_day(day1::puzzle, &mut index));
-    // days.push(add_day(day2::puzzle, &mut index));
-    // days.push(add_day(day3::puzzle, &mut index));
-    // days.push(add_day(day4::puzzle, &mut index));
-    // days.push(add_day(day5::puzzle, &mut index));
-    // days.push(add_day(day6::puzzle, &mut index));
-    // days.push(add_day(day7::puzzle, &mut index));
-    // days.push(add_day(day8::puzzle, &mut index));
-    // days.push(add_day(day9::puzzle, &mut index));
-    // days.push(add_day(day10::puzzle, &mut index));
-    // days.push(add_day(day11::puzzle, &mut index));
-    // days.push(add_day(day12::puzzle, &mut index));
-    // days.push(add_day(day13::puzzle, &mut index));
-    // days.push(add_day(day14::puzzle, &mut index));
-    // days.push(add_day(day15::puzzle, &mut index));
-    // days.push(add_day(day16::puzzle, &mut index));
-    // days.push(add_day(day17::puzzle, &mut index));
-    // days.push(add_day(day18::puzzle, &mut index));
-    // days.push(add_day(day19::puzzle, &mut index));
-    // days.push(add_day(day20::puzzle, &mut index));
-    // days.push(add_day(day21::puzzle, &mut index));
-    // days.push(add_day(day22::puzzle, &mut index));
-    // days.push(add_day(day23::puzzle, &mut index));
-    days.push(add_day(day24::puzzle, &mut index));
-    // days.push(add_day(day25::puzzle, &mut index));
-    days
-}
-
-fn add_day(function: DayFunction, index: &mut usize) -> Day {
-    let this_index = *index;
-    *index += 1;
-    Day {
-        text: "Terminus".to_string(),
-        puzzle: function,
-        index: this_index,
-    }
-}
-
-#[derive(Clone, Debug, Default)]
+const LOCAL_STORAGE_INDEX: &str = "INDEX";
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Diagnostic {
     message: String,
-    tabs: Vec<Tab>,
+    entries: Vec<DiagnosticEntry>,
+    tabs: Vec<DiagnosticTab>,
+    series: Vec<TabSeries>,
 }
 
-#[derive(Clone, Debug)]
+/// Tabs that show consecutive states of the same grid, which the app can play back as an animation.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TabSeries {
+    pub title: String,
+    pub tabs: Vec<usize>,
+}
+
+/// A tab of a diagnostic, either with its content or with a way to build it when it is opened.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum DiagnosticTab {
+    Ready(Rc<Tab>),
+    Lazy(LazyTab),
+}
+
+/// A tab that only builds its content when it is opened, for days that would otherwise render hundreds of grids up front.
+/// The render function stays where the puzzle ran, only the title travels to the app.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LazyTab {
+    title: String,
+    #[serde(skip)]
+    render: Option<Rc<dyn Fn() -> Tab>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Where in the input something was found. Line and column start at 0, the column counts characters.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+}
+
+/// One problem or remark a day found, optionally pointing at the part of the input it is about.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DiagnosticEntry {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Option<Span>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Tab {
     pub title: String,
+    /// Lines shown above the content
     pub strings: Vec<String>,
-    pub grid: Vec<Vec<GridCell>>,
+    pub content: TabContent,
 }
 
-#[derive(Clone, Debug)]
+/// What a tab shows below its strings. A tab has one kind of content, each has its own view.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum TabContent {
+    /// Nothing but the strings
+    Strings,
+    Grid(Vec<Vec<GridCell>>),
+    Chart(Chart),
+    Graph(Graph),
+    Table(Table),
+    Tree(Tree),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GridCell {
     // pub icon: IconId,
     pub text: String,
+    #[serde(with = "classes_as_string")]
     pub class: Classes,
     pub title: String,
 }
@@ -109,41 +281,560 @@ impl Diagnostic {
     pub fn simple(message: String) -> Self {
         Diagnostic {
             message,
+            entries: vec![],
             tabs: vec![],
+            series: vec![],
         }
     }
     pub fn with_tabs(tabs: Vec<Tab>, message: String) -> Self {
         Diagnostic {
             message,
-            tabs,
+            entries: vec![],
+            tabs: tabs.into_iter().map(|tab| DiagnosticTab::Ready(Rc::new(tab))).collect(),
+            series: vec![],
+        }
+    }
+    pub fn with_entries(mut self, entries: Vec<DiagnosticEntry>) -> Self {
+        self.entries = entries;
+        self
+    }
+    /// Adds tabs after the ones that are already there.
+    pub fn with_lazy_tabs(mut self, tabs: Vec<LazyTab>) -> Self {
+        self.tabs.extend(tabs.into_iter().map(DiagnosticTab::Lazy));
+        self
+    }
+    /// Marks tabs that are already there as a series, in the order they are given. Empty series are left out.
+    pub fn with_series(mut self, title: String, tabs: Vec<usize>) -> Self {
+        let tabs = tabs.into_iter().filter(|index| *index < self.tabs.len()).collect::<Vec<_>>();
+        if !tabs.is_empty() {
+            self.series.push(TabSeries {
+                title,
+                tabs,
+            });
+        }
+        self
+    }
+    /// Adds lazy tabs after the ones that are already there and plays them back as one series.
+    pub fn with_lazy_series(self, title: String, tabs: Vec<LazyTab>) -> Self {
+        let start = self.tabs.len();
+        let end = start + tabs.len();
+        self.with_lazy_tabs(tabs).with_series(title, (start..end).collect())
+    }
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+    pub fn series(&self) -> &[TabSeries] {
+        &self.series
+    }
+    pub fn entries(&self) -> &[DiagnosticEntry] {
+        &self.entries
+    }
+    pub fn tab_count(&self) -> usize {
+        self.tabs.len()
+    }
+    pub fn tab_titles(&self) -> impl Iterator<Item=&str> {
+        self.tabs.iter().map(|tab| match tab {
+            DiagnosticTab::Ready(tab) => tab.title.as_str(),
+            DiagnosticTab::Lazy(tab) => tab.title.as_str(),
+        })
+    }
+    /// Whether a tab shows a grid. Lazy tabs are only known to once they are built, unless they are frames of a series.
+    pub fn has_grid(&self, index: usize) -> bool {
+        match self.tabs.get(index) {
+            Some(DiagnosticTab::Ready(tab)) => !tab.grid().is_empty(),
+            Some(DiagnosticTab::Lazy(_)) => self.series.iter().any(|series| series.tabs.contains(&index)),
+            None => false,
+        }
+    }
+    /// The content of a tab, if it has been built already.
+    pub fn tab(&self, index: usize) -> Option<&Rc<Tab>> {
+        match self.tabs.get(index) {
+            Some(DiagnosticTab::Ready(tab)) => Some(tab),
+            _ => None,
+        }
+    }
+    /// Builds the content of a tab. None if there is no such tab, or its render function stayed behind in the worker.
+    pub fn render_tab(&self, index: usize) -> Option<Tab> {
+        match self.tabs.get(index)? {
+            DiagnosticTab::Ready(tab) => Some(tab.as_ref().clone()),
+            DiagnosticTab::Lazy(LazyTab { title, render }) => render.as_ref().map(|render| Tab {
+                title: title.clone(),
+                ..render()
+            }),
+        }
+    }
+    /// Stores the content of a lazy tab once it has been rendered. The tab keeps its title.
+    pub fn set_tab(&mut self, index: usize, tab: Tab) {
+        let title = self.tab_titles().nth(index).map(str::to_string);
+        if let Some(title) = title {
+            self.tabs[index] = DiagnosticTab::Ready(Rc::new(Tab {
+                title,
+                ..tab
+            }));
+        }
+    }
+    /// Lays out the graphs of the tabs that are built already, see `Graph::lay_out`.
+    pub fn lay_out_graphs(&mut self) {
+        for tab in self.tabs.iter_mut() {
+            if let DiagnosticTab::Ready(tab) = tab {
+                if matches!(tab.content, TabContent::Graph(_)) {
+                    Rc::make_mut(tab).lay_out_graph();
+                }
+            }
         }
     }
 }
 
+impl LazyTab {
+    pub fn new<F: Fn() -> Tab + 'static>(title: String, render: F) -> Self {
+        LazyTab {
+            title,
+            render: Some(Rc::new(render)),
+        }
+    }
+}
+
+impl Debug for LazyTab {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyTab").field("title", &self.title).finish_non_exhaustive()
+    }
+}
+
+impl DiagnosticEntry {
+    pub fn info(message: String) -> Self {
+        Self::new(Severity::Info, message)
+    }
+    pub fn warning(message: String) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+    pub fn error(message: String) -> Self {
+        Self::new(Severity::Error, message)
+    }
+    fn new(severity: Severity, message: String) -> Self {
+        DiagnosticEntry {
+            severity,
+            message,
+            span: None,
+        }
+    }
+    pub fn at(mut self, line: usize, column: usize, length: usize) -> Self {
+        self.span = Some(Span {
+            line,
+            column,
+            length,
+        });
+        self
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Info => f.write_str("info"),
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line + 1, self.column + 1)
+    }
+}
+
+impl Display for DiagnosticEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)?;
+        if let Some(span) = &self.span {
+            write!(f, " ({})", span)?;
+        }
+        Ok(())
+    }
+}
+
+impl Tab {
+    /// Renders the tab the way it would look in the diagnostics panel, one line per string, grid row, chart point, graph edge, table row or tree node.
+    pub fn to_plain_text(&self) -> String {
+        let content = match &self.content {
+            TabContent::Strings => vec![],
+            TabContent::Grid(grid) => grid.iter().map(|row| {
+                row.iter().map(|cell| cell.text.as_str()).collect::<String>()
+            }).collect(),
+            TabContent::Chart(chart) => chart.to_plain_text(),
+            TabContent::Graph(graph) => graph.to_plain_text(),
+            TabContent::Table(table) => table.to_plain_text(),
+            TabContent::Tree(tree) => tree.to_plain_text(),
+        };
+        self.strings.iter().cloned().chain(content).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Lays out the graph of a graph tab, see `Graph::lay_out`.
+    pub fn lay_out_graph(&mut self) {
+        if let TabContent::Graph(graph) = &mut self.content {
+            graph.lay_out();
+        }
+    }
+
+    /// The rows of a grid tab, empty for other tabs.
+    pub fn grid(&self) -> &[Vec<GridCell>] {
+        match &self.content {
+            TabContent::Grid(grid) => grid,
+            _ => &[],
+        }
+    }
+}
+
+/// Classes travel to and from the puzzle worker as their space separated string.
+mod classes_as_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use yew::Classes;
+
+    pub fn serialize<S: Serializer>(classes: &Classes, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&classes.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Classes, D::Error> {
+        String::deserialize(deserializer).map(Classes::from)
+    }
+}
+
 impl App {
-    fn run(&mut self) {
+    fn run(&mut self, ctx: &Context<Self>, part: Part) {
+        let Some(day) = self.days.get(self.day_index) else {
+            self.silver_output = "Day not found".to_string();
+            self.gold_output = "Day not found".to_string();
+            return;
+        };
+        if self.worker.is_none() {
+            match PuzzleWorker::spawn(ctx.link().callback(|response| match response {
+                WorkerResponse::Finished(response) => AppMessage::Finished(response),
+                WorkerResponse::TabRendered(index, tab) => AppMessage::TabRendered(index, tab),
+            })) {
+                Ok(worker) => self.worker = Some(worker),
+                Err(error) => {
+                    self.set_diagnostic(Diagnostic::simple(error));
+                    return;
+                }
+            }
+        }
+        let request = PuzzleRequest {
+            day_number: day.number,
+            input: self.inputs.current().input.clone(),
+            part,
+            parameters: self.parameters(day),
+        };
+        if let Some(worker) = &self.worker {
+            match worker.send(&WorkerRequest::Run(request)) {
+                Ok(()) => self.running = Some(part),
+                Err(error) => self.set_diagnostic(Diagnostic::simple(error)),
+            }
+        }
+    }
+    fn finish(&mut self, response: PuzzleResponse) {
+        if response.crashed {
+            // The next run starts a fresh worker, this one is broken
+            self.worker = None;
+            if let (None, Some(index)) = (self.running, self.rendering) {
+                // It crashed while building a tab rather than running the puzzle
+                self.set_tab(index, Tab {
+                    title: String::new(),
+                    strings: response.output.diagnostic.entries().iter().map(|entry| entry.to_string()).collect(),
+                    content: TabContent::Strings,
+                });
+                self.rendering = None;
+                self.refresh_diff();
+                return;
+            }
+        }
+        if let Some(part) = self.running.take() {
+            let output = response.output;
+            if part.silver() {
+                self.silver_output = output.silver_output;
+            }
+            if part.gold() {
+                self.gold_output = output.gold_output;
+            }
+            self.set_diagnostic(output.diagnostic);
+            self.timings = Some(response.timings);
+            self.up_to_date = true;
+            self.rendering = None;
+            self.playback.reset();
+            self.grid_diff = GridDiff::default();
+            self.diff = None;
+            self.request_tab();
+        }
+    }
+    /// Selects the tab of the current playback frame.
+    fn show_frame(&mut self) {
+        if let Some(index) = self.playback.tab(self.diagnostic.series()) {
+            self.tab_index = index;
+            self.request_tab();
+        }
+    }
+    /// Asks the worker for the content of the selected tab, then for the tabs picked for a grid diff, if they are lazy and haven't been built yet.
+    fn request_tab(&mut self) {
+        let wanted = [Some(self.tab_index), self.grid_diff.before, self.grid_diff.after];
+        let Some(index) = wanted.into_iter().flatten().find(|index| *index < self.diagnostic.tab_count() && self.diagnostic.tab(*index).is_none()) else {
+            return;
+        };
+        if self.rendering == Some(index) {
+            return;
+        }
+        let sent = self.worker.as_ref().map(|worker| worker.send(&WorkerRequest::RenderTab(index)));
+        match sent {
+            Some(Ok(())) => self.rendering = Some(index),
+            Some(Err(error)) => self.set_diagnostic(Diagnostic::simple(error)),
+            None => {
+                // The render functions went down with the worker
+                self.set_tab(index, Tab {
+                    title: String::new(),
+                    strings: vec!["This tab isn't available anymore, run the puzzle again".to_string()],
+                    content: TabContent::Strings,
+                });
+                // The tabs picked for a diff may still be waiting
+                self.request_tab();
+            }
+        }
+    }
+    /// Shows another diagnostic, with the search matches counted in its tabs.
+    fn set_diagnostic(&mut self, diagnostic: Diagnostic) {
+        self.diagnostic = diagnostic;
+        self.count_search_matches();
+    }
+    /// Stores a lazy tab once it has been built, with its search matches counted.
+    fn set_tab(&mut self, index: usize, tab: Tab) {
+        self.diagnostic.set_tab(index, tab);
+        let matches = self.tab_search_matches(index);
+        if let Some(count) = self.search_matches.get_mut(index) {
+            *count = matches;
+        }
+    }
+    /// Compiles the search and counts its matches in every tab.
+    fn refresh_search(&mut self) {
+        self.search_matcher = self.search.matcher();
+        self.count_search_matches();
+    }
+    fn count_search_matches(&mut self) {
+        self.search_matches = (0..self.diagnostic.tab_count()).map(|index| self.tab_search_matches(index)).collect();
+    }
+    /// Zero for tabs that haven't been built yet.
+    fn tab_search_matches(&self, index: usize) -> usize {
+        match (&self.search_matcher, self.diagnostic.tab(index)) {
+            (Ok(Some(matcher)), Some(tab)) => count_matches(matcher, &tab.strings),
+            _ => 0,
+        }
+    }
+    /// Compares the tabs picked for a grid diff, if both are built.
+    fn refresh_diff(&mut self) {
+        self.diff = self.grid_diff.tabs().and_then(|(before, after)| {
+            Some(diff_tab(self.diagnostic.tab(before)?, self.diagnostic.tab(after)?).map(Rc::new))
+        });
+    }
+    /// Terminates the worker, the next run starts a fresh one.
+    fn cancel(&mut self) {
+        self.worker = None;
+        self.rendering = None;
+        self.playback.reset();
+        if self.running.take().is_some() {
+            self.set_diagnostic(Diagnostic::simple("Puzzle run cancelled".to_string()));
+        }
+    }
+    /// The defaults of the day with the values stored in the selected input on top.
+    fn parameters(&self, day: &Day) -> Parameters {
+        let overrides = self.inputs.current().parameters.iter().map(|(name, value)| (name.as_str(), *value));
+        Parameters::with_overrides(day.parameters, overrides)
+    }
+    fn set_parameter(&mut self, name: &str, text: &str) {
+        let Some(day) = self.days.get(self.day_index) else {
+            return;
+        };
+        let result = text.trim().parse::<i64>()
+            .map_err(|_| format!("'{}' is not a whole number", text.trim()))
+            .and_then(|value| self.parameters(day).set(name, value).map(|_| value));
+        match result {
+            Ok(value) => {
+                self.inputs.set_parameter(name, value);
+                self.save_inputs();
+                self.refresh();
+            }
+            Err(error) => self.set_diagnostic(Diagnostic::simple(error)),
+        }
+    }
+    /// Selects the line of the span in the input box and scrolls it into view.
+    fn highlight(&self, span: Span) {
+        let Some(textarea) = self.input_ref.cast::<HtmlTextAreaElement>() else {
+            return;
+        };
+        let input = &self.inputs.current().input;
+        // Selection offsets count UTF-16 units, like everything else in javascript
+        let start = input.split('\n').take(span.line).map(|line| line.encode_utf16().count() + 1).sum::<usize>();
+        let end = start + input.split('\n').nth(span.line).map(|line| line.encode_utf16().count()).unwrap_or(0);
+        let _ = textarea.focus();
+        let _ = textarea.set_selection_range(start as u32, end as u32);
+        let lines = input.split('\n').count().max(1);
+        textarea.set_scroll_top(textarea.scroll_height() * span.line as i32 / lines as i32);
+    }
+    fn save_inputs(&self) {
         if let Some(day) = self.days.get(self.day_index) {
-            let output = (day.puzzle)(&self.input);
-            self.silver_output = output.silver_output;
-            self.gold_output = output.gold_output;
-            self.diagnostic = output.diagnostic;
-        } else {
-            self.silver_output = format!("Day not found");
-            self.gold_output = format!("Day not found");
+            self.inputs.save(day.number);
         }
-        self.up_to_date = true;
     }
+    /// Drops the output of the previous run. Only a running puzzle costs the worker, an idle one keeps serving the next run.
     fn refresh(&mut self) {
+        if self.running.is_some() {
+            self.cancel();
+        } else {
+            // A tab still being built belongs to the old diagnostic, the new one has no lazy tabs to put it in
+            self.rendering = None;
+            self.playback.reset();
+        }
         let (title_text, diagnostic) = Self::get_refresh_values(&self.days, self.day_index);
         self.title_text = title_text;
-        self.diagnostic = diagnostic;
+        self.set_diagnostic(diagnostic);
         self.silver_output = String::new();
         self.gold_output = String::new();
+        self.timings = None;
         self.up_to_date = false;
     }
-    fn get_refresh_values(days: &Vec<Day>, day_index: usize) -> (String, Diagnostic) {
+    /// Play/pause, scrubber, frame counter and speed for the tab series of the diagnostic.
+    fn view_playback(&self, ctx: &Context<Self>) -> Html {
+        let all_series = self.diagnostic.series();
+        let Some(series) = all_series.get(self.playback.series()) else {
+            return html! {};
+        };
+        let frames = series.tabs.len();
+        html! {
+            <div class="flex flex-row flex-wrap items-center gap-2 p-1 border border-gray-400 rounded-md">
+                {for all_series.iter().enumerate().map(|(index, series)| {
+                    html! {
+                        <button onclick={ctx.link().callback(move |_| AppMessage::PlaybackSelect(index))} class={merge("p-1 border border-gray-400 rounded-md", &if self.playback.series() == index {classes!("bg-slate-700")} else {classes!("")})}>
+                            {&series.title}
+                        </button>
+                    }
+                })}
+                <button onclick={ctx.link().callback(|_| AppMessage::PlaybackToggle)} class="w-16 p-1 border border-gray-400 rounded-md">
+                    {if self.playback.is_playing() {"Pause"} else {"Play"}}
+                </button>
+                <input type="range"
+                    min="0"
+                    max={frames.saturating_sub(1).to_string()}
+                    value={self.playback.frame().to_string()}
+                    oninput={ctx.link().batch_callback(|event: InputEvent| {
+                        event.target_dyn_into::<HtmlInputElement>()
+                            .and_then(|input| input.value().parse::<usize>().ok())
+                            .map(AppMessage::PlaybackSeek)
+                    })}
+                    class="grow"
+                />
+                <div class="w-32">{format!("Frame {} / {}", self.playback.frame() + 1, frames)}</div>
+                <label class="flex flex-row items-center gap-1">
+                    {"Speed"}
+                    <input type="range"
+                        min="1"
+                        max={MAX_FRAMES_PER_SECOND.to_string()}
+                        value={self.playback.frames_per_second().to_string()}
+                        oninput={ctx.link().batch_callback(|event: InputEvent| {
+                            event.target_dyn_into::<HtmlInputElement>()
+                                .and_then(|input| input.value().parse::<u32>().ok())
+                                .map(AppMessage::PlaybackSpeed)
+                        })}
+                    />
+                    <span class="w-16">{format!("{} fps", self.playback.frames_per_second())}</span>
+                </label>
+            </div>
+        }
+    }
+    /// What the tab shows below its strings.
+    fn view_content(&self, tab: &Rc<Tab>) -> Html {
+        match &tab.content {
+            TabContent::Strings => html! {},
+            TabContent::Grid(_) => self.view_grid(tab),
+            TabContent::Chart(chart) => view_chart(chart),
+            TabContent::Graph(_) => html! { <GraphView tab={tab.clone()} /> },
+            TabContent::Table(_) => html! { <TableView tab={tab.clone()} /> },
+            TabContent::Tree(_) => html! { <TreeView tab={tab.clone()} /> },
+        }
+    }
+    /// The grid of a tab, on a canvas or as one element per cell depending on the renderer.
+    fn view_grid(&self, tab: &Rc<Tab>) -> Html {
+        if tab.grid().is_empty() {
+            html! {}
+        } else if self.grid_renderer.uses_canvas(tab) {
+            html! {
+                <GridCanvas tab={tab.clone()} />
+            }
+        } else {
+            html! {
+                {for tab.grid().iter().map(|row|{
+                    html! {
+                        <div class="flex flex-row">
+                    {for row.iter().map(|cell|{
+                        html! {
+                            // <Icon icon_id={cell.icon} width={"2em".to_string()} height={"2em".to_string()} />
+                            <div class={merge("w-4 h-4", &cell.class)} title={cell.title.clone()}>
+                                {&cell.text}
+                            </div>
+                        }
+                    })}
+                        </div>
+                    }
+                })}
+            }
+        }
+    }
+    fn view_grid_diff(&self, ctx: &Context<Self>) -> Html {
+        let grid_tabs = self.diagnostic.tab_titles().enumerate().filter(|(index, _)| self.diagnostic.has_grid(*index)).collect::<Vec<_>>();
+        if grid_tabs.is_empty() {
+            return html! {};
+        }
+        let select = |selected: Option<usize>, message: fn(Option<usize>) -> AppMessage| html! {
+            <select class="p-1 rounded-md bg-gray-700" onchange={ctx.link().callback(move |event: Event| {
+                message(event.target_unchecked_into::<HtmlSelectElement>().value().parse().ok())
+            })}>
+                <option value="" selected={selected.is_none()}>{"None"}</option>
+                {for grid_tabs.iter().map(|(index, title)| html! {
+                    <option value={index.to_string()} selected={selected == Some(*index)}>{*title}</option>
+                })}
+            </select>
+        };
+        html! {
+            <div class="flex flex-row flex-wrap items-center gap-1">
+                <div>{"Compare grids:"}</div>
+                {select(self.grid_diff.before, AppMessage::DiffBefore)}
+                <div>{"→"}</div>
+                {select(self.grid_diff.after, AppMessage::DiffAfter)}
+            </div>
+        }
+    }
+    fn view_search(&self, ctx: &Context<Self>, error: Option<&str>, matches: usize) -> Html {
+        html! {
+            <div class="flex flex-row flex-wrap items-center gap-2 mb-2">
+                <input type="search" placeholder="Search lines" value={self.search.query.clone()} class="p-1 rounded-md bg-gray-700"
+                    oninput={ctx.link().callback(|event: InputEvent| AppMessage::SearchQuery(event.target_unchecked_into::<HtmlInputElement>().value()))} />
+                {for [(SearchMode::Substring, "Text"), (SearchMode::Regex, "Regex")].into_iter().map(|(mode, name)| {
+                    html! {
+                        <button onclick={ctx.link().callback(move |_| AppMessage::SetSearchMode(mode))} class={merge("p-1 border border-gray-400 rounded-md", &if self.search.mode == mode {classes!("bg-slate-700")} else {classes!("")})}>
+                            {name}
+                        </button>
+                    }
+                })}
+                <label class="flex flex-row items-center gap-1">
+                    <input type="checkbox" checked={self.search.only_matching}
+                        onchange={ctx.link().callback(|event: Event| AppMessage::SearchOnlyMatching(event.target_unchecked_into::<HtmlInputElement>().checked()))} />
+                    {"Only matching lines"}
+                </label>
+                {match error {
+                    Some(error) => html! { <div class="text-red-400 whitespace-pre">{error}</div> },
+                    None if self.search.query.is_empty() => html! {},
+                    None => html! { <div class="text-gray-400">{format!("{} matches", matches)}</div> },
+                }}
+            </div>
+        }
+    }
+    fn get_refresh_values(days: &[Day], day_index: usize) -> (String, Diagnostic) {
         if let Some(day) = days.get(day_index) {
-            (day.text.clone(), Diagnostic::simple(format!("Puzzle not yet run")))
+            (day.text(), Diagnostic::simple("Puzzle not yet run".to_string()))
         } else {
             ("No day with that index found".to_string(), Default::default())
         }
@@ -153,7 +844,7 @@ impl App {
 pub fn class_string(text: &'static str) -> Classes {
     let mut split = text.split(" ");
     if let Some(first) = split.next() {
-        split.into_iter().fold(classes!(first), |mut class, substring| {
+        split.fold(classes!(first), |mut class, substring| {
             class.extend(classes!(substring));
             class
         })
@@ -168,6 +859,32 @@ pub fn merge(base: &'static str, additional: &Classes) -> Classes {
     base
 }
 
+fn severity_class(severity: Severity) -> Classes {
+    match severity {
+        Severity::Info => class_string("bg-slate-600"),
+        Severity::Warning => class_string("bg-yellow-700"),
+        Severity::Error => class_string("bg-red-700"),
+    }
+}
+
+/// Green when an output matches the known answer of the example in the input box, red with the answer when it doesn't.
+fn answer_badge(example: Option<&Example>, expected: fn(&Example) -> Option<&'static str>, actual: &str) -> Html {
+    match example.and_then(|example| expected(example).map(|answer| (example.name, answer))) {
+        Some((name, answer)) if !actual.is_empty() => {
+            if answer == actual {
+                html! {
+                    <span class="px-2 rounded-md bg-green-700">{format!("Matches {}", name)}</span>
+                }
+            } else {
+                html! {
+                    <span class="px-2 rounded-md bg-red-700">{format!("{} expects {}", name, answer)}</span>
+                }
+            }
+        }
+        _ => html! {},
+    }
+}
+
 impl Component for App {
     type Message = AppMessage;
     type Properties = ();
@@ -175,42 +892,65 @@ impl Component for App {
     fn create(_ctx: &Context<Self>) -> Self {
         let days = get_days();
         let local_storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
-        let input = local_storage.get_item(LOCAL_STORAGE_INPUT).unwrap().unwrap_or(String::new());
         let index: usize = local_storage.get_item(LOCAL_STORAGE_INDEX).unwrap().map(|index| index.parse::<usize>().ok()).unwrap_or(None).unwrap_or(0);
         if let Some(day) = days.get(index) {
-            let day_text = day.text.clone();
+            let day_text = day.text();
+            let inputs = DayInputs::load(day.number);
             Self {
                 days,
                 day_index: index,
                 title_text: day_text,
-                input,
+                inputs,
                 silver_output: String::new(),
                 gold_output: String::new(),
-                diagnostic: Diagnostic::simple(format!("Puzzle not yet run")),
+                diagnostic: Diagnostic::simple("Puzzle not yet run".to_string()),
                 tab_index: 0,
                 up_to_date: false,
+                worker: None,
+                running: None,
+                timings: None,
+                input_ref: NodeRef::default(),
+                rendering: None,
+                playback: Playback::default(),
+                grid_renderer: GridRenderer::default(),
+                search: Search::default(),
+                search_matcher: Ok(None),
+                search_matches: vec![],
+                grid_diff: GridDiff::default(),
+                diff: None,
             }
         } else {
             Self {
                 days,
                 day_index: index,
                 title_text: "No initial day with that index found".to_string(),
-                input,
+                inputs: DayInputs::default(),
                 silver_output: String::new(),
                 gold_output: String::new(),
                 diagnostic: Default::default(),
                 tab_index: 0,
                 up_to_date: false,
+                worker: None,
+                running: None,
+                timings: None,
+                input_ref: NodeRef::default(),
+                rendering: None,
+                playback: Playback::default(),
+                grid_renderer: GridRenderer::default(),
+                search: Search::default(),
+                search_matcher: Ok(None),
+                search_matches: vec![],
+                grid_diff: GridDiff::default(),
+                diff: None,
             }
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             AppMessage::NewText(contents) => {
-                self.input = contents;
-                let local_storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
-                let _ = local_storage.set_item(LOCAL_STORAGE_INPUT, &self.input);
+                self.inputs.set_input(contents);
+                self.save_inputs();
                 self.refresh();
                 true
             }
@@ -218,47 +958,211 @@ impl Component for App {
                 self.day_index = index;
                 let local_storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
                 let _ = local_storage.set_item(LOCAL_STORAGE_INDEX, &format!("{}", self.day_index));
+                self.inputs = self.days.get(index).map(|day| DayInputs::load(day.number)).unwrap_or_default();
+                self.refresh();
+                true
+            }
+            AppMessage::SelectSlot(index) => {
+                self.inputs.select(index);
+                self.save_inputs();
+                self.refresh();
+                true
+            }
+            AppMessage::AddSlot => {
+                self.inputs.add();
+                self.save_inputs();
+                self.refresh();
+                true
+            }
+            AppMessage::RenameSlot(name) => {
+                self.inputs.rename(name);
+                self.save_inputs();
+                true
+            }
+            AppMessage::DeleteSlot => {
+                self.inputs.remove();
+                self.save_inputs();
                 self.refresh();
                 true
             }
-            AppMessage::Run => {
-                self.run();
+            AppMessage::SetParameter(name, text) => {
+                self.set_parameter(name, &text);
+                true
+            }
+            AppMessage::UseExampleParameters(parameters) => {
+                self.inputs.set_parameters(parameters.iter().map(|(name, value)| (name.to_string(), *value)).collect());
+                self.save_inputs();
+                self.refresh();
+                true
+            }
+            AppMessage::ResetParameters => {
+                self.inputs.set_parameters(Vec::new());
+                self.save_inputs();
+                self.refresh();
+                true
+            }
+            AppMessage::Highlight(span) => {
+                self.highlight(span);
+                false
+            }
+            AppMessage::Run(part) => {
+                self.run(ctx, part);
+                true
+            }
+            AppMessage::Finished(response) => {
+                self.finish(response);
+                true
+            }
+            AppMessage::Cancel => {
+                self.cancel();
                 true
             }
             AppMessage::TabClicked(index) => {
+                self.playback.pause();
                 self.tab_index = index;
+                self.request_tab();
+                true
+            }
+            AppMessage::TabRendered(index, tab) => {
+                if self.rendering == Some(index) {
+                    self.rendering = None;
+                }
+                if self.diagnostic.tab(index).is_none() {
+                    self.set_tab(index, tab);
+                    if self.grid_diff.before == Some(index) || self.grid_diff.after == Some(index) {
+                        self.refresh_diff();
+                    }
+                }
+                self.request_tab();
                 true
             }
             AppMessage::TabPrevious => {
+                self.playback.pause();
                 if self.tab_index > 0 {
                     self.tab_index -= 1;
                 }
+                self.request_tab();
                 true
             }
             AppMessage::TabNext => {
+                self.playback.pause();
                 self.tab_index += 1;
+                self.request_tab();
                 true
             }
             AppMessage::TabLast => {
-                if self.diagnostic.tabs.len() >= 1 {
-                    self.tab_index = self.diagnostic.tabs.len() - 1;
+                self.playback.pause();
+                if self.diagnostic.tab_count() > 0 {
+                    self.tab_index = self.diagnostic.tab_count() - 1;
+                }
+                self.request_tab();
+                true
+            }
+            AppMessage::PlaybackSelect(series) => {
+                self.playback.select(series);
+                self.show_frame();
+                true
+            }
+            AppMessage::PlaybackToggle => {
+                if self.playback.is_playing() {
+                    self.playback.pause();
+                } else {
+                    self.playback.play(self.diagnostic.series(), ctx.link());
+                    self.show_frame();
                 }
                 true
             }
+            AppMessage::PlaybackTick => {
+                // Lazy frames are waited for, so playback never gets ahead of the worker
+                let ready = self.diagnostic.tab(self.tab_index).is_some();
+                if ready && self.playback.advance(self.diagnostic.series()) {
+                    self.show_frame();
+                }
+                true
+            }
+            AppMessage::PlaybackSeek(frame) => {
+                self.playback.seek(frame);
+                self.show_frame();
+                true
+            }
+            AppMessage::PlaybackSpeed(frames_per_second) => {
+                self.playback.set_speed(frames_per_second, self.diagnostic.series(), ctx.link());
+                true
+            }
+            AppMessage::SetGridRenderer(grid_renderer) => {
+                self.grid_renderer = grid_renderer;
+                true
+            }
+            AppMessage::SearchQuery(query) => {
+                self.search.query = query;
+                self.refresh_search();
+                true
+            }
+            AppMessage::SetSearchMode(mode) => {
+                self.search.mode = mode;
+                self.refresh_search();
+                true
+            }
+            AppMessage::SearchOnlyMatching(only_matching) => {
+                self.search.only_matching = only_matching;
+                true
+            }
+            AppMessage::DiffBefore(index) => {
+                self.grid_diff.before = index;
+                self.request_tab();
+                self.refresh_diff();
+                true
+            }
+            AppMessage::DiffAfter(index) => {
+                self.grid_diff.after = index;
+                self.request_tab();
+                self.refresh_diff();
+                true
+            }
+            AppMessage::DownloadTab(export) => {
+                let day_number = self.days.get(self.day_index).map_or(0, |day| day.number);
+                if let Some(tab) = self.diagnostic.tab(self.tab_index) {
+                    if let Err(error) = download_tab(tab, export, day_number) {
+                        log::error!("Failed to download tab {}: {}", tab.title, error);
+                    }
+                }
+                false
+            }
+            AppMessage::DownloadAll => {
+                let day_number = self.days.get(self.day_index).map_or(0, |day| day.number);
+                if let Err(error) = download_diagnostic(&self.diagnostic, day_number) {
+                    log::error!("Failed to download the diagnostic: {}", error);
+                }
+                false
+            }
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        let day = self.days.get(self.day_index);
+        let parameters = day.map(|day| self.parameters(day)).unwrap_or_default();
+        let stored_example = day.and_then(|day| day.find_example(&self.inputs.current().input).map(|example| (day, example)));
+        // The known answers only hold for the parameters the example was written for
+        let example = stored_example
+            .filter(|(day, example)| self.up_to_date && example.parameters(day) == parameters)
+            .map(|(_, example)| example);
+        let example_parameters = stored_example
+            .filter(|(day, example)| example.parameters(day) != parameters)
+            .map(|(_, example)| (example.name, example.parameters));
+        // An invalid regex searches for nothing, the search bar shows why
+        let (matcher, search_error) = match &self.search_matcher {
+            Ok(matcher) => (matcher.as_ref(), None),
+            Err(error) => (None, Some(error.as_str())),
+        };
         html! {
         <div class="flex flex-row text-gray-100 border-gray-400 m-2 mx-3">
             <div class="flex flex-col gap-2 m-2">
-                { for self.days.iter().map(|day| {
-                    let index = day.index;
+                { for self.days.iter().enumerate().map(|(index, day)| {
                     html! {
                         <button onclick={ctx.link().callback(move |_| {
                             AppMessage::SetDay(index)
                         })} class="p-2 border rounded">
-                            {&day.text}
+                            {day.text()}
                         </button>
                     }
                 })}
@@ -270,51 +1174,152 @@ impl Component for App {
                 <div class="p-2 border-b border-gray-400 rounded">
                     {"Input"}
                 </div>
-                <textarea onchange={ctx.link().batch_callback(move |event: Event| {
-                        if let Some(input) = event.target_dyn_into::<HtmlTextAreaElement>() {
-                            Some(AppMessage::NewText(input.value()))
-                        } else {
-                            None
+                <div class="flex flex-row flex-wrap items-center">
+                    {for self.inputs.slots().iter().enumerate().map(|(index, slot)| {
+                        html! {
+                            <button onclick={ctx.link().callback(move |_| AppMessage::SelectSlot(index))} class={merge("p-1 m-1 border border-gray-400 rounded-md", &if self.inputs.selected() == index {classes!("bg-slate-700")} else {classes!("")})}>
+                                {&slot.name}
+                            </button>
                         }
+                    })}
+                    <button onclick={ctx.link().callback(move |_| AppMessage::AddSlot)} class="p-1 m-1 border border-gray-400 rounded-md">
+                        {"New input"}
+                    </button>
+                    <input onchange={ctx.link().batch_callback(move |event: Event| {
+                            event.target_dyn_into::<HtmlInputElement>().map(|input| AppMessage::RenameSlot(input.value()))
+                        })}
+                        value={self.inputs.current().name.clone()}
+                        title="Rename this input"
+                        class="p-1 m-1 rounded-md bg-gray-700"
+                    />
+                    <button onclick={ctx.link().callback(move |_| AppMessage::DeleteSlot)} class="p-1 m-1 border border-gray-400 rounded-md">
+                        {"Delete input"}
+                    </button>
+                </div>
+                <textarea onchange={ctx.link().batch_callback(move |event: Event| {
+                        event.target_dyn_into::<HtmlTextAreaElement>().map(|input| AppMessage::NewText(input.value()))
                    })}
-                    value={self.input.clone()}
+                    value={self.inputs.current().input.clone()}
+                    ref={self.input_ref.clone()}
                     class="rounded-md h-72 bg-gray-700"
                 >
                 </textarea>
-                <button onclick={
-                    ctx.link().callback(move |_| AppMessage::Run)
-                } class="p-2 m-2 border border-gray-400 rounded-md text-lg">
-                    { if self.up_to_date {
-                        "Run"
-                    } else {
-                        "** Run **"
-                    } }
-                </button>
-                <div class="p-2 border-b border-gray-400 rounded">
-                    {"Output for first part"}
+                if day.is_some_and(|day| !day.parameters.is_empty()) {
+                    <div class="p-2 border-b border-gray-400 rounded">
+                        {"Parameters"}
+                    </div>
+                    <div class="flex flex-row flex-wrap items-center">
+                        {for day.map(|day| day.parameters).unwrap_or_default().iter().map(|parameter| {
+                            let name = parameter.name;
+                            html! {
+                                <label title={parameter.description} class="flex flex-row items-center gap-1 m-1">
+                                    {name}
+                                    <input type="number"
+                                        min={parameter.min.to_string()}
+                                        max={parameter.max.to_string()}
+                                        value={parameters.get::<i64>(name).to_string()}
+                                        onchange={ctx.link().batch_callback(move |event: Event| {
+                                            event.target_dyn_into::<HtmlInputElement>().map(|input| AppMessage::SetParameter(name, input.value()))
+                                        })}
+                                        class="w-32 p-1 rounded-md bg-gray-700"
+                                    />
+                                </label>
+                            }
+                        })}
+                        if let Some((name, example_parameters)) = example_parameters {
+                            <button onclick={ctx.link().callback(move |_| AppMessage::UseExampleParameters(example_parameters))} class="p-1 m-1 border border-gray-400 rounded-md">
+                                {format!("Use parameters of {}", name)}
+                            </button>
+                        }
+                        <button onclick={ctx.link().callback(move |_| AppMessage::ResetParameters)} class="p-1 m-1 border border-gray-400 rounded-md">
+                            {"Reset parameters"}
+                        </button>
+                    </div>
+                }
+                <div class="flex flex-row">
+                    { for [("Run silver", Part::Silver), ("Run gold", Part::Gold), ("Run both", Part::Both)].into_iter().map(|(text, part)| {
+                        html! {
+                            <button onclick={
+                                ctx.link().callback(move |_| AppMessage::Run(part))
+                            } disabled={self.running.is_some()} class="grow p-2 m-2 border border-gray-400 rounded-md text-lg disabled:text-gray-500">
+                                { if self.up_to_date {
+                                    text.to_string()
+                                } else {
+                                    format!("** {} **", text)
+                                } }
+                            </button>
+                        }
+                    })}
+                </div>
+                if self.running.is_some() {
+                    <div class="flex flex-row items-center gap-2">
+                        <div class="w-6 h-6 m-2 border-4 border-gray-400 border-t-transparent rounded-full animate-spin"></div>
+                        <div class="grow">{"Running..."}</div>
+                        <button onclick={
+                            ctx.link().callback(move |_| AppMessage::Cancel)
+                        } class="p-2 m-2 border border-gray-400 rounded-md">
+                            {"Cancel"}
+                        </button>
+                    </div>
+                }
+                <div class="flex flex-row gap-2 p-2 border-b border-gray-400 rounded">
+                    <div class="grow">{"Output for first part"}</div>
+                    {answer_badge(example, |example| example.silver, &self.silver_output)}
+                    <div class="text-gray-400">{self.timings.as_ref().map(|timings| format_duration(timings.silver)).unwrap_or_default()}</div>
                 </div>
                 <div class="p-2 border border-gray-400 rounded">
                     {&self.silver_output}
                 </div>
-                <div class="p-2 border-b border-gray-400 rounded">
-                    {"Output for second part"}
+                <div class="flex flex-row gap-2 p-2 border-b border-gray-400 rounded">
+                    <div class="grow">{"Output for second part"}</div>
+                    {answer_badge(example, |example| example.gold, &self.gold_output)}
+                    <div class="text-gray-400">{self.timings.as_ref().map(|timings| format_duration(timings.gold)).unwrap_or_default()}</div>
                 </div>
                 <div class="p-2 border border-gray-400 rounded">
                     {&self.gold_output}
                 </div>
+                if let Some(timings) = &self.timings {
+                    <div class="p-2 text-gray-400">
+                        {format!("Parsed in {}, {} in total", format_duration(timings.parse), format_duration(timings.total))}
+                    </div>
+                }
                 <div class="p-2 border-b border-gray-400 rounded">
                     {"Diagnostic field"}
                 </div>
                 <div class="p-2 border border-gray-400 rounded">
                     {&self.diagnostic.message}
                 </div>
+                if !self.diagnostic.entries.is_empty() {
+                    <div class="flex flex-col p-2 border border-gray-400 rounded max-h-72 overflow-y-auto">
+                        {for self.diagnostic.entries.iter().map(|entry| {
+                            html! {
+                                <div class="flex flex-row items-center gap-2">
+                                    <span class={merge("px-2 rounded-md", &severity_class(entry.severity))}>{entry.severity.to_string()}</span>
+                                    <span class="grow">{&entry.message}</span>
+                                    if let Some(span) = entry.span {
+                                        <button onclick={ctx.link().callback(move |_| AppMessage::Highlight(span))} class="text-gray-400 underline">
+                                            {span.to_string()}
+                                        </button>
+                                    }
+                                </div>
+                            }
+                        })}
+                    </div>
+                }
             </div>
             <div class="flex flex-col gap-2 m-2">
+                {self.view_playback(ctx)}
                 <div class="flex flex-row">
-                    {for self.diagnostic.tabs.iter().enumerate().map(|(index, tab)|{
+                    {for self.diagnostic.tab_titles().enumerate().map(|(index, title)|{
+                        let matches = self.search_matches.get(index).copied().unwrap_or(0);
                         html!{
                     <button onclick={ctx.link().callback(move |_| AppMessage::TabClicked(index))} class={merge("p-1 m-1 border border-gray-400 rounded-md", &if self.tab_index == index {classes!("bg-slate-700")} else {classes!("")})}>
-                            {&tab.title}
+                            {title}
+                            {if matches > 0 {
+                                html! { <span class="ml-1 px-1 rounded-md bg-yellow-300 text-gray-900">{matches}</span> }
+                            } else {
+                                html! {}
+                            }}
                     </button>
                         }
                     })}
@@ -335,41 +1340,80 @@ impl Component for App {
                     } class="p-1 m-1 border border-gray-400 rounded-md">
                         {"Last tab"}
                     </button>
+                    <button onclick={
+                        ctx.link().callback(move |_| AppMessage::DownloadAll)
+                    } class="p-1 m-1 border border-gray-400 rounded-md">
+                        {"Download all"}
+                    </button>
                 </div>
+                <div class="flex flex-row items-center gap-1">
+                    <div>{"Grids:"}</div>
+                    {for GridRenderer::ALL.into_iter().map(|grid_renderer| {
+                        html! {
+                            <button onclick={ctx.link().callback(move |_| AppMessage::SetGridRenderer(grid_renderer))} class={merge("p-1 border border-gray-400 rounded-md", &if self.grid_renderer == grid_renderer {classes!("bg-slate-700")} else {classes!("")})}>
+                                {grid_renderer.name()}
+                            </button>
+                        }
+                    })}
+                </div>
+                {self.view_grid_diff(ctx)}
                 <div class="p-4 flex flex-col border border-gray-400">
-                {if let Some(tab) = self.diagnostic.tabs.get(self.tab_index) {
+                {if let Some(tab) = self.diagnostic.tab(self.tab_index) {
                     html! {
                         <>
                         <div class="mb-2 border-b border-gray-400">{"Tab name: "}{&tab.title}</div>
-                    {for tab.strings.iter().map(|string|{
-                        html!{
-                            <div>
-                                {string}
+                    {match TabExport::for_tab(tab) {
+                        exports if exports.is_empty() => html! {},
+                        exports => html! {
+                            <div class="flex flex-row flex-wrap items-center gap-1 mb-2">
+                                <div>{"Download:"}</div>
+                                {for exports.into_iter().map(|export| html! {
+                                    <button onclick={ctx.link().callback(move |_| AppMessage::DownloadTab(export))} class="p-1 border border-gray-400 rounded-md">
+                                        {export.name()}
+                                    </button>
+                                })}
                             </div>
-                        }
-                    })}
-                    {for tab.grid.iter().map(|row|{
-                        html! {
-                            <div class="flex flex-row">
-                        {for row.iter().map(|cell|{
-                            html! {
-                                // <Icon icon_id={cell.icon} width={"2em".to_string()} height={"2em".to_string()} />
-                                <div class={merge("w-4 h-4", &cell.class)} title={cell.title.clone()}>
-                                    {&cell.text}
-                                </div>
-                            }
-                        })}
-                            </div>
-                        }
-                    })}
+                        },
+                    }}
+                    {if tab.strings.is_empty() {
+                        html! {}
+                    } else {
+                        let matches = self.search_matches.get(self.tab_index).copied().unwrap_or(0);
+                        self.view_search(ctx, search_error, matches)
+                    }}
+                    {for tab.strings.iter().filter_map(|string| view_line(string, matcher, self.search.only_matching))}
+                    {self.view_content(tab)}
                         </>
                     }
+                } else if self.tab_index < self.diagnostic.tab_count() {
+                    html! {
+                        <div class="flex flex-row items-center gap-2">
+                            <div class="w-6 h-6 m-2 border-4 border-gray-400 border-t-transparent rounded-full animate-spin"></div>
+                            <div>{"Rendering tab..."}</div>
+                        </div>
+                    }
                 } else {
                     html! {
                         <div class="p-2 font-mono">{"No tab info"}</div>
                     }
                 }}
                 </div>
+                {match &self.diff {
+                    Some(Ok(diff)) => html! {
+                        <div class="p-4 flex flex-col border border-gray-400">
+                            <div class="mb-2 border-b border-gray-400">{"Grid diff: "}{&diff.title}</div>
+                            {for diff.strings.iter().map(|string| html! { <div>{string}</div> })}
+                            {self.view_grid(diff)}
+                        </div>
+                    },
+                    Some(Err(error)) => html! {
+                        <div class="p-4 border border-gray-400 text-red-400">{error}</div>
+                    },
+                    None if self.grid_diff.tabs().is_some() => html! {
+                        <div class="p-4 border border-gray-400">{"Rendering tabs to compare..."}</div>
+                    },
+                    None => html! {},
+                }}
             </div>
         </div>
         }
@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+use crate::app::{Day, Part};
+use crate::timing::{format_duration, Timings};
+
+const DEFAULT_RUNS: usize = 10;
+const DEFAULT_INPUTS: &str = "inputs";
+
+struct BenchArguments {
+    runs: usize,
+    inputs: PathBuf,
+    days: Vec<u32>,
+    examples: bool,
+}
+
+/// Runs every stored input of every selected day `runs` times and prints min/median/max per phase.
+/// Stored inputs run with the defaults of the day, examples with their own parameters on top.
+pub fn run<I>(days: &[Day], args: I, usage: &str) -> Result<(), String>
+    where I: Iterator<Item=String> {
+    let arguments = parse_arguments(args, usage)?;
+    println!("{:<5}{:<24}{:<8}{:>12}{:>12}{:>12}", "Day", "Input", "Phase", "Min", "Median", "Max");
+    let mut benchmarked = 0;
+    for day in days.iter().filter(|day| arguments.days.is_empty() || arguments.days.contains(&day.number)) {
+        for path in stored_inputs(&arguments.inputs, day.number)? {
+            let input = std::fs::read_to_string(&path).map_err(|error| format!("Couldn't read '{}': {}", path.display(), error))?;
+            let parameters = day.default_parameters();
+            let timings = (0..arguments.runs).map(|_| day.run(&input, Part::Both, &parameters).1).collect::<Vec<_>>();
+            let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            print_rows(day.number, &name, &timings);
+            benchmarked += 1;
+        }
+        if arguments.examples {
+            for example in day.examples {
+                let parameters = example.parameters(day);
+                let timings = (0..arguments.runs).map(|_| day.run(example.input, Part::Both, &parameters).1).collect::<Vec<_>>();
+                print_rows(day.number, example.name, &timings);
+                benchmarked += 1;
+            }
+        }
+    }
+    if benchmarked == 0 {
+        println!("No stored inputs found in '{}'", arguments.inputs.display());
+    }
+    Ok(())
+}
+
+fn parse_arguments<I>(mut args: I, usage: &str) -> Result<BenchArguments, String>
+    where I: Iterator<Item=String> {
+    let mut runs = DEFAULT_RUNS;
+    let mut inputs = PathBuf::from(DEFAULT_INPUTS);
+    let mut days = Vec::new();
+    let mut examples = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" => {
+                let value = args.next().ok_or_else(|| "Expected a number after --runs".to_string())?;
+                runs = value.parse::<usize>().ok().filter(|runs| *runs > 0)
+                    .ok_or_else(|| format!("'{}' is not a positive number of runs", value))?;
+            }
+            "--inputs" => {
+                inputs = PathBuf::from(args.next().ok_or_else(|| "Expected a directory after --inputs".to_string())?);
+            }
+            "--examples" => examples = true,
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown bench option '{}'\n\n{}", flag, usage));
+            }
+            day => {
+                days.push(day.parse::<u32>().map_err(|_| format!("'{}' is not a day number", day))?);
+            }
+        }
+    }
+    Ok(BenchArguments {
+        runs,
+        inputs,
+        days,
+        examples,
+    })
+}
+
+/// Finds `dayN.txt` and `dayN-<name>.txt` in the inputs directory, sorted by name.
+fn stored_inputs(directory: &Path, day_number: u32) -> Result<Vec<PathBuf>, String> {
+    if !directory.is_dir() {
+        return Ok(Vec::new());
+    }
+    let exact = format!("day{}.txt", day_number);
+    let prefix = format!("day{}-", day_number);
+    let entries = std::fs::read_dir(directory).map_err(|error| format!("Couldn't read '{}': {}", directory.display(), error))?;
+    let mut paths = entries.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name().and_then(|name| name.to_str()).is_some_and(|name| {
+                name == exact || (name.starts_with(&prefix) && name.ends_with(".txt"))
+            })
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    Ok(paths)
+}
+
+fn print_rows(day_number: u32, input_name: &str, timings: &[Timings]) {
+    let phase_names = Timings::default().phases().map(|(phase, _)| phase);
+    for (index, phase) in phase_names.iter().enumerate() {
+        let mut samples = timings.iter().map(|timings| timings.phases()[index].1).collect::<Vec<_>>();
+        samples.sort_by(f64::total_cmp);
+        let (day, input) = if index == 0 {
+            (day_number.to_string(), input_name)
+        } else {
+            (String::new(), "")
+        };
+        println!(
+            "{:<5}{:<24}{:<8}{:>12}{:>12}{:>12}",
+            day,
+            input,
+            phase,
+            format_duration(samples[0]),
+            format_duration(median(&samples)),
+            format_duration(samples[samples.len() - 1]),
+        );
+    }
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let middle = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    } else {
+        sorted[middle]
+    }
+}
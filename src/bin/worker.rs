@@ -0,0 +1,10 @@
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    advent_of_code_2024::worker::register(advent_of_code_2024::get_days());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    eprintln!("The puzzle worker only runs in the browser, use the main binary to run puzzles natively");
+    std::process::exit(1);
+}
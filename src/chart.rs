@@ -0,0 +1,307 @@
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+use crate::export::csv_line;
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 320.0;
+const MARGIN_LEFT: f64 = 64.0;
+const MARGIN_RIGHT: f64 = 16.0;
+const MARGIN_TOP: f64 = 12.0;
+const MARGIN_BOTTOM: f64 = 44.0;
+const TICKS: usize = 5;
+pub const SERIES_COLOURS: &[&str] = &["#60a5fa", "#f87171", "#4ade80", "#facc15", "#c084fc", "#fb923c"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ChartKind {
+    /// Points joined by lines, for values over time
+    Line,
+    /// A bar per point, series side by side
+    Bar,
+    /// Bars that cover `bucket_width` from their x, built by `Chart::histogram`
+    Histogram { bucket_width: f64 },
+}
+
+/// Labelled numeric data for a chart tab.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Chart {
+    pub kind: ChartKind,
+    pub x_label: String,
+    pub y_label: String,
+    pub series: Vec<ChartSeries>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChartSeries {
+    pub label: String,
+    /// (x, y), sorted by x
+    pub points: Vec<(f64, f64)>,
+}
+
+impl ChartSeries {
+    /// Sorts the points by x, so days can pass them straight from a map.
+    pub fn new(label: String, mut points: Vec<(f64, f64)>) -> Self {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ChartSeries {
+            label,
+            points,
+        }
+    }
+}
+
+impl Chart {
+    pub fn line(x_label: String, y_label: String, series: Vec<ChartSeries>) -> Self {
+        Chart {
+            kind: ChartKind::Line,
+            x_label,
+            y_label,
+            series,
+        }
+    }
+
+    pub fn bar(x_label: String, y_label: String, series: Vec<ChartSeries>) -> Self {
+        Chart {
+            kind: ChartKind::Bar,
+            x_label,
+            y_label,
+            series,
+        }
+    }
+
+    /// Counts the values into `buckets` equally wide buckets between the smallest and largest value.
+    pub fn histogram(x_label: String, values: &[f64], buckets: usize) -> Self {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let buckets = buckets.max(1);
+        let bucket_width = if values.is_empty() || max == min {
+            1.0
+        } else {
+            (max - min) / buckets as f64
+        };
+        let mut counts = vec![0.0; buckets];
+        for value in values {
+            let bucket = (((value - min) / bucket_width) as usize).min(buckets - 1);
+            counts[bucket] += 1.0;
+        }
+        let points = if values.is_empty() {
+            vec![]
+        } else {
+            counts.into_iter().enumerate().map(|(bucket, count)| (min + bucket as f64 * bucket_width, count)).collect()
+        };
+        Chart {
+            kind: ChartKind::Histogram { bucket_width },
+            x_label,
+            y_label: "Count".to_string(),
+            series: vec![ChartSeries::new(String::new(), points)],
+        }
+    }
+
+    /// One line per point, for the command line.
+    pub fn to_plain_text(&self) -> Vec<String> {
+        let mut lines = vec![format!("{} / {}", self.x_label, self.y_label)];
+        for series in self.series.iter() {
+            if !series.label.is_empty() {
+                lines.push(format!("{}:", series.label));
+            }
+            lines.extend(series.points.iter().map(|(x, y)| format!("{}: {}", format_number(*x), format_number(*y))));
+        }
+        lines
+    }
+
+    /// A header line and a line per point with its series, x and y.
+    pub fn to_csv(&self) -> String {
+        let header = csv_line(["Series", &self.x_label, &self.y_label]);
+        let points = self.series.iter().flat_map(|series| {
+            series.points.iter().map(|(x, y)| csv_line([series.label.as_str(), &x.to_string(), &y.to_string()]))
+        });
+        [header].into_iter().chain(points).map(|line| line + "\n").collect()
+    }
+
+    /// The x range covered by the points, including the width of bars.
+    fn x_range(&self) -> (f64, f64) {
+        let xs = self.series.iter().flat_map(|series| series.points.iter().map(|(x, _)| *x));
+        let (min, max) = xs.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), x| (min.min(x), max.max(x)));
+        if min > max {
+            return (0.0, 1.0);
+        }
+        match self.kind {
+            ChartKind::Line if min == max => (min - 1.0, max + 1.0),
+            ChartKind::Line => (min, max),
+            ChartKind::Bar => {
+                let width = self.bar_slot();
+                (min - width / 2.0, max + width / 2.0)
+            }
+            ChartKind::Histogram { bucket_width } => (min, max + bucket_width),
+        }
+    }
+
+    /// The y range, always including 0 so bars start at the axis.
+    fn y_range(&self) -> (f64, f64) {
+        let ys = self.series.iter().flat_map(|series| series.points.iter().map(|(_, y)| *y));
+        let (min, max) = ys.fold((0.0f64, 0.0f64), |(min, max), y| (min.min(y), max.max(y)));
+        if min == max {
+            (min, min + 1.0)
+        } else {
+            (min, max)
+        }
+    }
+
+    /// The x distance reserved for the bars at one x, the smallest gap between two x values.
+    fn bar_slot(&self) -> f64 {
+        let mut xs = self.series.iter().flat_map(|series| series.points.iter().map(|(x, _)| *x)).collect::<Vec<_>>();
+        xs.sort_by(f64::total_cmp);
+        xs.dedup();
+        let smallest_gap = xs.windows(2).map(|pair| pair[1] - pair[0]).fold(f64::INFINITY, f64::min);
+        if smallest_gap.is_finite() {
+            smallest_gap
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Draws the chart as SVG. Hovering a point or bar shows its values.
+pub fn view_chart(chart: &Chart) -> Html {
+    let (x_min, x_max) = chart.x_range();
+    let (y_min, y_max) = chart.y_range();
+    let x_ticks = nice_ticks(x_min, x_max);
+    let y_ticks = nice_ticks(y_min, y_max);
+    let (x_min, x_max) = (x_min.min(x_ticks[0]), x_max.max(*x_ticks.last().unwrap_or(&x_max)));
+    let (y_min, y_max) = (y_min.min(y_ticks[0]), y_max.max(*y_ticks.last().unwrap_or(&y_max)));
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let to_x = move |x: f64| MARGIN_LEFT + (x - x_min) / (x_max - x_min) * plot_width;
+    let to_y = move |y: f64| MARGIN_TOP + (y_max - y) / (y_max - y_min) * plot_height;
+    let series_count = chart.series.len().max(1) as f64;
+    let bar_slot = chart.bar_slot();
+
+    let series = chart.series.iter().enumerate().map(|(index, series)| {
+        let colour = SERIES_COLOURS[index % SERIES_COLOURS.len()];
+        let hover = |x: f64, y: f64| if series.label.is_empty() {
+            format!("{}: {}", format_number(x), format_number(y))
+        } else {
+            format!("{}, {}: {}", series.label, format_number(x), format_number(y))
+        };
+        match chart.kind {
+            ChartKind::Line => {
+                let points = series.points.iter().map(|(x, y)| format!("{:.1},{:.1}", to_x(*x), to_y(*y))).collect::<Vec<_>>().join(" ");
+                html! {
+                    <g>
+                        <polyline points={points} fill="none" stroke={colour} stroke-width="1.5" />
+                        {for series.points.iter().map(|(x, y)| html! {
+                            <circle cx={to_x(*x).to_string()} cy={to_y(*y).to_string()} r="3" fill={colour} class="opacity-0 hover:opacity-100">
+                                <title>{hover(*x, *y)}</title>
+                            </circle>
+                        })}
+                    </g>
+                }
+            }
+            ChartKind::Bar | ChartKind::Histogram { .. } => {
+                let (width, offset) = match chart.kind {
+                    ChartKind::Histogram { bucket_width } => (bucket_width, 0.0),
+                    _ => {
+                        let width = bar_slot * 0.8 / series_count;
+                        (width, -bar_slot * 0.4 + index as f64 * width)
+                    }
+                };
+                html! {
+                    <g>
+                        {for series.points.iter().map(|(x, y)| {
+                            let left = to_x(x + offset);
+                            let right = to_x(x + offset + width);
+                            let top = to_y(y.max(0.0));
+                            let bottom = to_y(y.min(0.0));
+                            html! {
+                                <rect x={left.to_string()} y={top.to_string()} width={(right - left).max(1.0).to_string()} height={(bottom - top).to_string()}
+                                    fill={colour} stroke="#1f2937" stroke-width="0.5" class="hover:opacity-75">
+                                    <title>{hover(*x, *y)}</title>
+                                </rect>
+                            }
+                        })}
+                    </g>
+                }
+            }
+        }
+    });
+
+    html! {
+        <div class="flex flex-col gap-1">
+            <svg width={WIDTH.to_string()} height={HEIGHT.to_string()} class="text-gray-400">
+                {for y_ticks.iter().map(|tick| html! {
+                    <g>
+                        <line x1={MARGIN_LEFT.to_string()} x2={(WIDTH - MARGIN_RIGHT).to_string()} y1={to_y(*tick).to_string()} y2={to_y(*tick).to_string()} stroke="#374151" />
+                        <text x={(MARGIN_LEFT - 6.0).to_string()} y={to_y(*tick).to_string()} text-anchor="end" dominant-baseline="middle" font-size="11" fill="currentColor">{format_number(*tick)}</text>
+                    </g>
+                })}
+                {for x_ticks.iter().map(|tick| html! {
+                    <text x={to_x(*tick).to_string()} y={(HEIGHT - MARGIN_BOTTOM + 16.0).to_string()} text-anchor="middle" font-size="11" fill="currentColor">{format_number(*tick)}</text>
+                })}
+                {for series}
+                <line x1={MARGIN_LEFT.to_string()} x2={MARGIN_LEFT.to_string()} y1={MARGIN_TOP.to_string()} y2={(HEIGHT - MARGIN_BOTTOM).to_string()} stroke="currentColor" />
+                <line x1={MARGIN_LEFT.to_string()} x2={(WIDTH - MARGIN_RIGHT).to_string()} y1={to_y(0.0f64.clamp(y_min, y_max)).to_string()} y2={to_y(0.0f64.clamp(y_min, y_max)).to_string()} stroke="currentColor" />
+                <text x={(MARGIN_LEFT + plot_width / 2.0).to_string()} y={(HEIGHT - 8.0).to_string()} text-anchor="middle" font-size="12" fill="currentColor">{&chart.x_label}</text>
+                <text transform={format!("translate(14 {}) rotate(-90)", MARGIN_TOP + plot_height / 2.0)} text-anchor="middle" font-size="12" fill="currentColor">{&chart.y_label}</text>
+            </svg>
+            <div class="flex flex-row gap-3">
+                {for chart.series.iter().enumerate().filter(|(_, series)| !series.label.is_empty()).map(|(index, series)| html! {
+                    <div class="flex flex-row items-center gap-1">
+                        <div class="w-3 h-3" style={format!("background-color: {}", SERIES_COLOURS[index % SERIES_COLOURS.len()])}></div>
+                        <div>{&series.label}</div>
+                    </div>
+                })}
+            </div>
+        </div>
+    }
+}
+
+/// About `TICKS` round values spanning `min` to `max`, using steps of 1, 2 or 5 times a power of ten.
+fn nice_ticks(min: f64, max: f64) -> Vec<f64> {
+    let rough_step = (max - min) / TICKS as f64;
+    let magnitude = 10f64.powf(rough_step.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0].into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= rough_step)
+        .unwrap_or(10.0 * magnitude);
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+    (first..=last).map(|tick| tick as f64 * step).collect()
+}
+
+fn format_number(number: f64) -> String {
+    if number.fract() == 0.0 && number.abs() < 1.0e15 {
+        format!("{}", number as i64)
+    } else {
+        let formatted = format!("{:.2}", number);
+        formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn histogram_points(values: &[f64], buckets: usize) -> (ChartKind, Vec<(f64, f64)>) {
+        let chart = Chart::histogram("Value".to_string(), values, buckets);
+        (chart.kind, chart.series[0].points.clone())
+    }
+
+    #[test]
+    fn histogram_buckets_values() {
+        assert_eq!(histogram_points(&[], 3), (ChartKind::Histogram { bucket_width: 1.0 }, vec![]));
+        assert_eq!(histogram_points(&[5.0, 5.0, 5.0], 3), (ChartKind::Histogram { bucket_width: 1.0 }, vec![(5.0, 3.0), (6.0, 0.0), (7.0, 0.0)]));
+        // A value on a bucket edge goes in the bucket above it, and the largest value in the last bucket
+        assert_eq!(histogram_points(&[0.0, 1.0, 2.0, 3.0, 4.0], 2), (ChartKind::Histogram { bucket_width: 2.0 }, vec![(0.0, 2.0), (2.0, 3.0)]));
+        assert_eq!(histogram_points(&[1.0, 2.0], 0), (ChartKind::Histogram { bucket_width: 1.0 }, vec![(1.0, 2.0)]));
+    }
+
+    #[test]
+    fn nice_ticks_use_round_steps() {
+        assert_eq!(nice_ticks(0.0, 10.0), vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_eq!(nice_ticks(-7.0, 23.0), vec![-10.0, 0.0, 10.0, 20.0, 30.0]);
+        assert_eq!(nice_ticks(0.0, 1.0e6), vec![0.0, 2.0e5, 4.0e5, 6.0e5, 8.0e5, 1.0e6]);
+        let small = nice_ticks(0.0, 0.3);
+        assert_eq!(small.len(), 4);
+        for (tick, expected) in small.into_iter().zip([0.0, 0.1, 0.2, 0.3]) {
+            assert!((tick - expected).abs() < 1.0e-9, "{} isn't {}", tick, expected);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::io::Read;
+use crate::app::{Day, DayOutput, Part, Severity};
+use crate::bench;
+use crate::timing::{format_duration, Timings};
+
+const USAGE: &str = "Usage: advent-of-code-2024 <day> [input file, or - for stdin] [options]
+       advent-of-code-2024 bench [bench options] [day...]
+
+Options:
+    --silver          Only run the first part
+    --gold            Only run the second part
+    --diagnostic      Print the diagnostic message and entries
+    --list-tabs       Print the index and title of every diagnostic tab
+    --tab <tab>       Print a diagnostic tab as plain text, by index or title. Can be repeated
+    --time            Print how long parsing, each part and the whole run took
+    --param <n>=<v>   Set a puzzle parameter, see --list-params. Can be repeated
+    --list-params     Print the parameters of the day with their defaults
+    --help            Print this message
+
+Bench options:
+    --runs <n>        How many times to run each input, 10 by default
+    --inputs <dir>    Where the stored inputs are, `inputs` by default.
+                      Every dayN.txt or dayN-<name>.txt in it is benchmarked,
+                      with the default parameters of the day
+    --examples        Also benchmark the examples of each day, with their own parameters";
+
+struct Arguments {
+    day: u32,
+    input_path: Option<String>,
+    part: Part,
+    diagnostic: bool,
+    list_tabs: bool,
+    tabs: Vec<String>,
+    time: bool,
+    parameters: Vec<(String, i64)>,
+    list_parameters: bool,
+}
+
+pub fn run(days: &[Day]) -> Result<(), String> {
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("bench") {
+        args.next();
+        return bench::run(days, args, USAGE);
+    }
+    let arguments = match parse_arguments(args)? {
+        Some(arguments) => arguments,
+        None => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+    };
+    let day = days.iter().find(|day| day.number == arguments.day)
+        .ok_or_else(|| format!("No day {} registered", arguments.day))?;
+    if arguments.list_parameters {
+        print_parameters(day);
+        return Ok(());
+    }
+    let mut parameters = day.default_parameters();
+    for (name, value) in arguments.parameters.iter() {
+        parameters.set(name, *value)?;
+    }
+    let input = read_input(arguments.input_path.as_deref())?;
+
+    // A panic fails the command, so scripts can tell a crash from an answer
+    let (output, timings) = day.try_run(&input, arguments.part, &parameters);
+    print_output(day, &output?, &timings, &arguments)
+}
+
+fn parse_arguments<I>(mut args: I) -> Result<Option<Arguments>, String>
+    where I: Iterator<Item=String> {
+    let mut day = None;
+    let mut input_path = None;
+    let mut silver = false;
+    let mut gold = false;
+    let mut diagnostic = false;
+    let mut list_tabs = false;
+    let mut tabs = Vec::new();
+    let mut time = false;
+    let mut parameters = Vec::new();
+    let mut list_parameters = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(None),
+            "--silver" => silver = true,
+            "--gold" => gold = true,
+            "--diagnostic" => diagnostic = true,
+            "--list-tabs" => list_tabs = true,
+            "--time" => time = true,
+            "--list-params" => list_parameters = true,
+            "--param" => {
+                let assignment = args.next().ok_or_else(|| "Expected name=value after --param".to_string())?;
+                parameters.push(parse_parameter(&assignment)?);
+            }
+            "--tab" => {
+                tabs.push(args.next().ok_or_else(|| "Expected a tab index or title after --tab".to_string())?);
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option '{}'\n\n{}", flag, USAGE));
+            }
+            positional => {
+                if day.is_none() {
+                    day = Some(positional.parse::<u32>().map_err(|_| format!("'{}' is not a day number", positional))?);
+                } else if input_path.is_none() {
+                    input_path = Some(positional.to_string());
+                } else {
+                    return Err(format!("Unexpected argument '{}'\n\n{}", positional, USAGE));
+                }
+            }
+        }
+    }
+    let day = day.ok_or_else(|| USAGE.to_string())?;
+    let part = match (silver, gold) {
+        (true, false) => Part::Silver,
+        (false, true) => Part::Gold,
+        _ => Part::Both,
+    };
+    Ok(Some(Arguments {
+        day,
+        input_path: input_path.filter(|path| path != "-"),
+        part,
+        diagnostic,
+        list_tabs,
+        tabs,
+        time,
+        parameters,
+        list_parameters,
+    }))
+}
+
+fn parse_parameter(assignment: &str) -> Result<(String, i64), String> {
+    let (name, value) = assignment.split_once('=')
+        .ok_or_else(|| format!("'{}' is not of the form name=value", assignment))?;
+    let value = value.trim().parse::<i64>().map_err(|_| format!("'{}' is not a whole number", value))?;
+    Ok((name.trim().to_string(), value))
+}
+
+fn print_parameters(day: &Day) {
+    println!("{}", day.text());
+    if day.parameters.is_empty() {
+        println!("No parameters");
+    }
+    for parameter in day.parameters.iter() {
+        println!("{}={} ({} to {}): {}", parameter.name, parameter.default, parameter.min, parameter.max, parameter.description);
+    }
+}
+
+fn read_input(path: Option<&str>) -> Result<String, String> {
+    match path {
+        Some(path) => {
+            std::fs::read_to_string(path).map_err(|error| format!("Couldn't read '{}': {}", path, error))
+        }
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input).map_err(|error| format!("Couldn't read stdin: {}", error))?;
+            Ok(input)
+        }
+    }
+}
+
+fn print_output(day: &Day, output: &DayOutput, timings: &Timings, arguments: &Arguments) -> Result<(), String> {
+    println!("{}", day.text());
+    if arguments.part.silver() {
+        println!("Silver: {}", output.silver_output);
+    }
+    if arguments.part.gold() {
+        println!("Gold: {}", output.gold_output);
+    }
+    if arguments.time {
+        println!();
+        println!("Parse: {}", format_duration(timings.parse));
+        if arguments.part.silver() {
+            println!("Silver: {}", format_duration(timings.silver));
+        }
+        if arguments.part.gold() {
+            println!("Gold: {}", format_duration(timings.gold));
+        }
+        println!("Total: {}", format_duration(timings.total));
+    }
+
+    let diagnostic = &output.diagnostic;
+    if arguments.diagnostic {
+        println!();
+        println!("Diagnostic: {}", diagnostic.message());
+        for entry in diagnostic.entries() {
+            println!("{}", entry);
+        }
+    } else {
+        for entry in diagnostic.entries().iter().filter(|entry| entry.severity == Severity::Error) {
+            eprintln!("{}", entry);
+        }
+    }
+    if arguments.list_tabs {
+        println!();
+        for (index, title) in diagnostic.tab_titles().enumerate() {
+            println!("{}: {}", index, title);
+        }
+    }
+    for selector in arguments.tabs.iter() {
+        let tab = selector.parse::<usize>().ok()
+            .filter(|index| *index < diagnostic.tab_count())
+            .or_else(|| diagnostic.tab_titles().position(|title| title == selector))
+            .and_then(|index| diagnostic.render_tab(index))
+            .ok_or_else(|| format!("No tab '{}' in the output of day {}", selector, day.number))?;
+        println!();
+        println!("Tab: {}", tab.title);
+        println!("{}", tab.to_plain_text());
+    }
+    Ok(())
+}
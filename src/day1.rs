@@ -1,8 +1,24 @@
 use std::iter::zip;
 use regex::{Regex};
-use crate::app::{DayOutput, Diagnostic};
+use crate::app::{DayOutput, Diagnostic, Example, Part};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub const TITLE: &str = "Historian Hysteria";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day1.txt"),
+        silver: Some("11"),
+        gold: Some("31"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let re = Regex::new(r"^([0-9]*)   ([0-9]*)$").unwrap();
     let pairs = input.split("\n")
         .filter_map(|line| {
@@ -35,21 +51,28 @@ pub fn puzzle(input: &str) -> DayOutput {
     }
     left_list.sort();
     right_list.sort();
+    stopwatch.lap(Phase::Parse);
 
     let mut sum_of_distances = 0;
-    for (left, right) in zip(left_list.iter(), right_list.iter()) {
-        if left > right {
-            sum_of_distances += left - right;
-        } else {
-            sum_of_distances += right - left;
+    if part.silver() {
+        for (left, right) in zip(left_list.iter(), right_list.iter()) {
+            if left > right {
+                sum_of_distances += left - right;
+            } else {
+                sum_of_distances += right - left;
+            }
         }
     }
+    stopwatch.lap(Phase::Silver);
 
     let mut similarity_score = 0;
-    for left in left_list.iter() {
-        let occurrences = right_list.iter().filter(|right| **right == *left).count() as u64;
-        similarity_score += left * occurrences;
+    if part.gold() {
+        for left in left_list.iter() {
+            let occurrences = right_list.iter().filter(|right| **right == *left).count() as u64;
+            similarity_score += left * occurrences;
+        }
     }
+    stopwatch.lap(Phase::Gold);
 
     DayOutput {
         silver_output: format!("{}", sum_of_distances),
@@ -1,39 +1,77 @@
 use std::collections::HashSet;
-use crate::app::{DayOutput, Diagnostic, Tab};
+use crate::app::{DayOutput, Diagnostic, DiagnosticEntry, Example, Part, Tab, TabContent};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 use crate::grid::{Coord, Grid};
 
-pub fn puzzle(input: &str) -> DayOutput {
-    let input_grid = Grid::from_filtered(input, |character| {
+pub const TITLE: &str = "Hoof It";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day10.txt"),
+        silver: Some("36"),
+        gold: Some("81"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
+    let mut entries = Vec::new();
+    let input_grid = Grid::parse_filtered(input, |character| {
         character.to_digit(10)
+    }).unwrap_or_else(|error| {
+        entries.push(DiagnosticEntry::error(error));
+        Grid::default()
     });
+    stopwatch.lap(Phase::Parse);
 
     let grid = input_grid.clone();
+    let mut trails = None;
     let mut sum = 0;
+    if part.silver() {
+        let found = find_trails(&grid);
+        sum = found.iter().map(|(_, complete_trails, _)| complete_trails.len()).sum();
+        trails = Some(found);
+    }
+    stopwatch.lap(Phase::Silver);
+
     let mut sum_rating = 0;
-    let mut tuples = Vec::new();
-    for coord in grid.get_all_coords().into_iter() {
-        let (complete_trails, rating) = continue_trail(&grid, 0, coord);
-        sum += complete_trails.len();
-        sum_rating += rating;
-        if !complete_trails.is_empty() {
-            tuples.push(format!("{}: {}, {:?}", coord, rating, complete_trails))
-        }
+    if part.gold() {
+        // The ratings come out of the same pass, so gold only walks the trails when silver didn't
+        let found = trails.get_or_insert_with(|| find_trails(&grid));
+        sum_rating = found.iter().map(|(_, _, rating)| rating).sum();
     }
+    stopwatch.lap(Phase::Gold);
+
+    let tuples = trails.unwrap_or_default().into_iter().map(|(coord, complete_trails, rating)| {
+        format!("{}: {}, {:?}", coord, rating, complete_trails)
+    }).collect();
 
     let tabs = vec![
         Tab {
             title: "Tab".to_string(),
             strings: tuples,
-            grid: input_grid.to_tab_grid(),
+            content: TabContent::Grid(input_grid.to_tab_grid()),
         },
     ];
     DayOutput {
         silver_output: format!("{}", sum),
         gold_output: format!("{}", sum_rating),
-        diagnostic: Diagnostic::with_tabs(tabs, format!("")),
+        diagnostic: Diagnostic::with_tabs(tabs, String::new()).with_entries(entries),
     }
 }
 
+/// The trail heads with the trail endings they reach and their rating.
+fn find_trails(grid: &Grid<u32>) -> Vec<(Coord, HashSet<Coord>, u32)> {
+    grid.get_all_coords().into_iter().filter_map(|coord| {
+        let (complete_trails, rating) = continue_trail(grid, 0, coord);
+        (!complete_trails.is_empty()).then_some((coord, complete_trails, rating))
+    }).collect()
+}
+
 pub fn continue_trail(grid: &Grid<u32>, expected_height: u32, current_coord: Coord) -> (HashSet<Coord>, u32) {
     if let Some(current_height) = grid.get(current_coord) {
         if *current_height == expected_height {
@@ -42,9 +80,9 @@ pub fn continue_trail(grid: &Grid<u32>, expected_height: u32, current_coord: Coo
             } else {
                 let mut trail_endings = HashSet::new();
                 let mut rating = 0;
-                for dir in Coord::get_orthagonal_dirs() {
-                    let (current_trail_endings, current_rating) = continue_trail(&grid, expected_height + 1, current_coord.add(&dir));
-                    trail_endings.extend(current_trail_endings.into_iter());
+                for (next_coord, _) in grid.orthogonal_neighbours(current_coord) {
+                    let (current_trail_endings, current_rating) = continue_trail(grid, expected_height + 1, next_coord);
+                    trail_endings.extend(current_trail_endings);
                     rating += current_rating;
                 }
                 (trail_endings, rating)
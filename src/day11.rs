@@ -1,35 +1,80 @@
 use std::collections::HashMap;
-use crate::app::{DayOutput, Diagnostic, Tab};
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab, TabContent};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub const TITLE: &str = "Plutonian Pebbles";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day11.txt"),
+        silver: Some("55312"),
+        gold: Some("65601038650482"),
+        parameters: &[],
+    },
+];
+
+const SILVER_BLINKS: &str = "silver_blinks";
+const GOLD_BLINKS: &str = "gold_blinks";
+
+pub const PARAMETERS: &[Parameter] = &[
+    Parameter {
+        name: SILVER_BLINKS,
+        description: "How often the stones blink for the first part",
+        default: 25,
+        min: 0,
+        max: 150,
+    },
+    Parameter {
+        name: GOLD_BLINKS,
+        description: "How often the stones blink for the second part",
+        default: 75,
+        min: 0,
+        max: 150,
+    },
+];
+
+pub fn puzzle(input: &str, part: Part, parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let mut parse_errors = Vec::new();
-    let input_values = input.split(" ").into_iter().filter_map(|text| {
+    let input_values = input.split(" ").filter_map(|text| {
         let a = text.trim().parse::<u128>();
         parse_errors.push(format!("{:?}", a));
         a.ok()
     }).collect::<Vec<u128>>();
+    stopwatch.lap(Phase::Parse);
 
     let mut evolutions_silver = vec![];
     let mut evolutions_gold = vec![];
 
-    let (sum_silver, errors) = apply_evolutions(&input_values, 25, &mut evolutions_silver);
-    let (sum_gold, errors_gold  ) = apply_evolutions(&input_values, 75, &mut evolutions_gold);
+    let (sum_silver, errors) = if part.silver() {
+        apply_evolutions(&input_values, parameters.get(SILVER_BLINKS), &mut evolutions_silver)
+    } else {
+        (0, Vec::new())
+    };
+    stopwatch.lap(Phase::Silver);
+    let (sum_gold, errors_gold) = if part.gold() {
+        apply_evolutions(&input_values, parameters.get(GOLD_BLINKS), &mut evolutions_gold)
+    } else {
+        (0, Vec::new())
+    };
+    stopwatch.lap(Phase::Gold);
 
     let mut tabs = Vec::new();
     tabs.push(Tab { // Apparently 165674 is wrong
         title: "Silver".to_string(),
         strings: evolutions_silver,
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Gold".to_string(),
         strings: evolutions_gold,
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Parse steps".to_string(),
         strings: parse_errors,
-        grid: vec![],
+        content: TabContent::Strings,
     });
 
     DayOutput {
@@ -70,7 +115,7 @@ fn apply_evolutions(input_values: &Vec<u128>, num_evolutions: usize, evolutions:
 
         evolutions.push(format!("{:?}", values));
     }
-    let sum =values.into_iter().map(|(_value, num)|num).sum();
+    let sum =values.into_values().sum();
     (sum, errors)
 }
 
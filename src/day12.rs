@@ -1,12 +1,60 @@
 use std::collections::HashSet;
-use std::ops::Deref;
-use crate::app::{DayOutput, Diagnostic, Tab};
-use crate::grid::{Coord, Grid};
+use crate::app::{DayOutput, Diagnostic, DiagnosticEntry, Example, Part, Tab, TabContent};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
+use crate::grid::{Coord, Direction, Grid};
 
-pub fn puzzle(input: &str) -> DayOutput {
-    let input_grid = Grid::from(input, |character| {
+pub const TITLE: &str = "Garden Groups";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day12.txt"),
+        silver: Some("140"),
+        gold: Some("80"),
+        parameters: &[],
+    },
+    Example {
+        name: "Nested regions",
+        input: include_str!("../res/examples/day12-2.txt"),
+        silver: Some("772"),
+        gold: Some("436"),
+        parameters: &[],
+    },
+    Example {
+        name: "Larger example",
+        input: include_str!("../res/examples/day12-3.txt"),
+        silver: Some("1930"),
+        gold: Some("1206"),
+        parameters: &[],
+    },
+    Example {
+        name: "E-shaped region",
+        input: include_str!("../res/examples/day12-4.txt"),
+        silver: None,
+        gold: Some("236"),
+        parameters: &[],
+    },
+    Example {
+        name: "Diagonal regions",
+        input: include_str!("../res/examples/day12-5.txt"),
+        silver: None,
+        gold: Some("368"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
+    let mut entries = Vec::new();
+    let input_grid = Grid::parse(input, |character| {
         character
+    }).unwrap_or_else(|error| {
+        entries.push(DiagnosticEntry::error(error));
+        Grid::default()
     });
+    stopwatch.lap(Phase::Parse);
     let mut tabs = vec![];
 
     let grid = input_grid.clone();
@@ -24,8 +72,14 @@ pub fn puzzle(input: &str) -> DayOutput {
                 let fence_segment_strings = fence_segments.iter().map(|(first, second)| {
                     format!("{}: {}, {}", character, first, second)
                 }).collect::<Vec<_>>();
-                let sides = combine_fences(fence_segments);
+                stopwatch.lap(Phase::Silver);
+                let sides = if part.gold() {
+                    combine_fences(fence_segments)
+                } else {
+                    HashSet::new()
+                };
                 sum_gold += area * sides.len() as u64;
+                stopwatch.lap(Phase::Gold);
                 diagnostic_strings.push(format!("Area with {} starting at {} had area {} and boundary {}, sides {}", *character, coord, area, boundary, sides.len()));
                 let strings = fence_segment_strings.into_iter().chain(sides.into_iter().map(|(first, second)| {
                     format!("Side {}, {}", first, second)
@@ -33,7 +87,7 @@ pub fn puzzle(input: &str) -> DayOutput {
                 tabs.push(Tab {
                     title: format!("Sides {}", tabs.len()),
                     strings,
-                    grid: vec![],
+                    content: TabContent::Strings,
                 })
             }
         }
@@ -42,13 +96,13 @@ pub fn puzzle(input: &str) -> DayOutput {
     tabs.insert(0, Tab {
         title: "Input grid".to_string(),
         strings: diagnostic_strings,
-        grid: input_grid.to_tab_grid(),
+        content: TabContent::Grid(input_grid.to_tab_grid()),
     },
     );
     DayOutput {
         silver_output: format!("{}", sum_silver),
         gold_output: format!("{}", sum_gold),
-        diagnostic: Diagnostic::with_tabs(tabs, format!("")),
+        diagnostic: Diagnostic::with_tabs(tabs, String::new()).with_entries(entries),
     }
 }
 
@@ -59,11 +113,12 @@ fn traverse(grid: &Grid<char>, used_coords: &mut HashSet<Coord>, coord: Coord, p
                 used_coords.insert(coord);
                 let mut area = 1;
                 let mut boundary = HashSet::new();
-                for dir in Coord::get_orthagonal_dirs().into_iter() {
-                    let adjacent_coord = coord.add(&dir);
+                // Cells outside the grid are needed too, they get a fence
+                for direction in Direction::ORTHOGONAL {
+                    let adjacent_coord = coord + direction.offset();
                     let (new_area, new_boundary) = traverse(grid, used_coords, adjacent_coord, Some(coord), area_char);
                     area += new_area;
-                    boundary.extend(new_boundary.into_iter());
+                    boundary.extend(new_boundary);
                 }
                 (area, boundary)
             } else {
@@ -79,11 +134,11 @@ fn traverse(grid: &Grid<char>, used_coords: &mut HashSet<Coord>, coord: Coord, p
 
 fn get_fence(coord: Coord, previous_coord: Option<Coord>) -> HashSet<(Coord, Coord)> {
     if let Some(previous_coord) = previous_coord {
-        if previous_coord.0.0 == coord.0.0 {
-            let x = previous_coord.0.0;
-            let y = std::cmp::max(previous_coord.deref().1, coord.deref().1);
+        if previous_coord.x() == coord.x() {
+            let x = previous_coord.x();
+            let y = std::cmp::max(previous_coord.y(), coord.y());
             // Make inside and outside edges different so independent segments that meet in a cross don't combine
-            if previous_coord.deref().1 < coord.deref().1 {
+            if previous_coord.y() < coord.y() {
                 HashSet::from([(
                     Coord::new(x, y),
                     Coord::new(x + 1, y)
@@ -95,9 +150,9 @@ fn get_fence(coord: Coord, previous_coord: Option<Coord>) -> HashSet<(Coord, Coo
                 )])
             }
         } else {
-            let x = std::cmp::max(previous_coord.deref().0, coord.deref().0);
-            let y = previous_coord.0.1;
-            if previous_coord.deref().0 < coord.deref().0 {
+            let x = std::cmp::max(previous_coord.x(), coord.x());
+            let y = previous_coord.y();
+            if previous_coord.x() < coord.x() {
                 HashSet::from([(
                     Coord::new(x, y),
                     Coord::new(x, y + 1)
@@ -120,15 +175,9 @@ fn combine_fences(input: HashSet<(Coord, Coord)>) -> HashSet<(Coord, Coord)> {
     for (mut new_start, mut new_end) in input.into_iter() {
         for (current_start, current_end, current_delete) in output.iter_mut() {
             if !*current_delete {
-                if new_start.0.0 == new_end.0.0 && new_start.0.0 == current_start.0.0 && new_start.0.0 == current_end.0.0 {
-                    if *current_start == new_end {
-                        new_end = *current_end;
-                        *current_delete = true;
-                    } else if *current_end == new_start {
-                        new_start = *current_start;
-                        *current_delete = true;
-                    }
-                } else if new_start.0.1 == new_end.0.1 && new_start.0.1 == current_start.0.1 && new_start.0.1 == current_end.0.1 {
+                let vertical = new_start.x() == new_end.x() && new_start.x() == current_start.x() && new_start.x() == current_end.x();
+                let horizontal = new_start.y() == new_end.y() && new_start.y() == current_start.y() && new_start.y() == current_end.y();
+                if vertical || horizontal {
                     if *current_start == new_end {
                         new_end = *current_end;
                         *current_delete = true;
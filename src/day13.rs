@@ -1,104 +1,148 @@
 use regex::{Regex};
-use crate::app::{DayOutput, Diagnostic, Tab};
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab, TabContent};
+use crate::grid::Coord64;
+use crate::table::{ColumnKind, Table};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 
+pub const TITLE: &str = "Claw Contraption";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day13.txt"),
+        silver: Some("480"),
+        gold: Some("875318608908"),
+        parameters: &[],
+    },
+];
+
+const GOLD_ADJUSTMENT: &str = "gold_adjustment";
+
+pub const PARAMETERS: &[Parameter] = &[
+    Parameter {
+        name: GOLD_ADJUSTMENT,
+        description: "How far the prizes move in both directions for the second part",
+        default: 10000000000000,
+        min: 0,
+        max: 1000000000000000,
+    },
+];
+
+/// How far the buttons move the claw and where the prize is. With the gold adjustment the prize is far out of `i32` range.
 #[derive(Clone, Debug)]
 struct Problem {
-    ax: u64,
-    ay: u64,
-    bx: u64,
-    by: u64,
-    px: u64,
-    py: u64,
+    a: Coord64,
+    b: Coord64,
+    prize: Coord64,
 }
 
 const A_COST: u64 = 3;
 const B_COST: u64 = 1;
-const GOLD_ADJUSTMENT: u64 = 10000000000000;
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub fn puzzle(input: &str, part: Part, parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let re = Regex::new(r"Button A: X\+(?P<AX>\d*), Y\+(?P<AY>\d*)\nButton B: X\+(?P<BX>\d*), Y\+(?P<BY>\d*)\nPrize: X\=(?P<PX>\d*), Y\=(?P<PY>\d*)").unwrap();
-    let problems: Vec<Problem> = re.captures_iter(input).into_iter().filter_map(|captures| {
-        let ax = captures.name("AX").map(|s| s.as_str().parse::<u64>().ok()).unwrap_or(None);
-        let ay = captures.name("AY").map(|s| s.as_str().parse::<u64>().ok()).unwrap_or(None);
-        let bx = captures.name("BX").map(|s| s.as_str().parse::<u64>().ok()).unwrap_or(None);
-        let by = captures.name("BY").map(|s| s.as_str().parse::<u64>().ok()).unwrap_or(None);
-        let px = captures.name("PX").map(|s| s.as_str().parse::<u64>().ok()).unwrap_or(None);
-        let py = captures.name("PY").map(|s| s.as_str().parse::<u64>().ok()).unwrap_or(None);
+    let problems: Vec<Problem> = re.captures_iter(input).filter_map(|captures| {
+        let ax = captures.name("AX").map(|s| s.as_str().parse::<i64>().ok()).unwrap_or(None);
+        let ay = captures.name("AY").map(|s| s.as_str().parse::<i64>().ok()).unwrap_or(None);
+        let bx = captures.name("BX").map(|s| s.as_str().parse::<i64>().ok()).unwrap_or(None);
+        let by = captures.name("BY").map(|s| s.as_str().parse::<i64>().ok()).unwrap_or(None);
+        let px = captures.name("PX").map(|s| s.as_str().parse::<i64>().ok()).unwrap_or(None);
+        let py = captures.name("PY").map(|s| s.as_str().parse::<i64>().ok()).unwrap_or(None);
         if let (Some(ax), Some(ay), Some(bx), Some(by), Some(px), Some(py)) = (ax, ay, bx, by, px, py) {
             Some(Problem {
-                ax,
-                ay,
-                bx,
-                by,
-                px,
-                py,
+                a: Coord64::new(ax, ay),
+                b: Coord64::new(bx, by),
+                prize: Coord64::new(px, py),
             })
         } else {
             None
         }
     }).collect::<Vec<_>>();
+    stopwatch.lap(Phase::Parse);
 
-    let mut status = Vec::new();
-    let mut status_gold = Vec::new();
+    let mut status = Table::new(&[
+        ("Machine", ColumnKind::Integer),
+        ("A presses", ColumnKind::Integer),
+        ("B presses", ColumnKind::Integer),
+        ("Tokens", ColumnKind::Integer),
+    ]);
+    let mut status_gold = Table::new(&[
+        ("Machine", ColumnKind::Integer),
+        ("A", ColumnKind::Float),
+        ("B", ColumnKind::Float),
+        ("Tokens", ColumnKind::Integer),
+    ]);
     let mut total_coins = 0;
     let mut total_coins_gold = 0;
-    for problem in problems.iter() {
-        if let Some(coins) = solve(problem, 0, 0, &mut status) {
-            total_coins += coins;
+    let gold_adjustment: i64 = parameters.get(GOLD_ADJUSTMENT);
+    for (machine, problem) in problems.iter().enumerate() {
+        if part.silver() {
+            let mut found = Vec::new();
+            if let Some(coins) = solve(problem, 0, 0, &mut found) {
+                total_coins += coins;
+            }
+            for (a_presses, b_presses) in found {
+                status.push(vec![machine.into(), a_presses.into(), b_presses.into(), (a_presses * A_COST + b_presses * B_COST).into()]);
+            }
         }
-        let mut problem = problem.clone();
-        problem.px += GOLD_ADJUSTMENT;
-        problem.py += GOLD_ADJUSTMENT;
-        if let Some(coins) = solve_gold(&problem, &mut status_gold) {
-            total_coins_gold += coins;
+        stopwatch.lap(Phase::Silver);
+        if part.gold() {
+            let mut problem = problem.clone();
+            problem.prize = problem.prize + Coord64::new(gold_adjustment, gold_adjustment);
+            let (a, b, coins) = solve_gold(&problem);
+            if let Some(coins) = coins {
+                total_coins_gold += coins;
+            }
+            status_gold.push(vec![machine.into(), a.into(), b.into(), coins.into()]);
         }
+        stopwatch.lap(Phase::Gold);
     }
 
     let mut tabs = Vec::new();
     tabs.push(Tab {
         title: "Input".to_string(),
-        strings: problems.iter().map(|problem| format!("Problem: a: {}, {}, b: {}, {}, p: {}, {}", problem.ax, problem.ay, problem.bx, problem.by, problem.px, problem.py)).collect(),
-        grid: vec![],
+        strings: problems.iter().map(|problem| format!("Problem: a: {}, b: {}, p: {}", problem.a, problem.b, problem.prize)).collect(),
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Status".to_string(),
-        strings: status,
-        grid: vec![],
+        strings: vec![],
+        content: TabContent::Table(status),
     });
     tabs.push(Tab {
         title: "Status gold".to_string(),
-        strings: status_gold,
-        grid: vec![],
+        strings: vec![],
+        content: TabContent::Table(status_gold),
     });
     DayOutput {
         silver_output: format!("{}", total_coins),
         gold_output: format!("{}", total_coins_gold),
-        diagnostic: Diagnostic::with_tabs(tabs, format!("")),
+        diagnostic: Diagnostic::with_tabs(tabs, String::new()),
     }
 }
 
-fn solve(problem: &Problem, a_presses: u64, b_presses: u64, status: &mut Vec<String>) -> Option<u64> {
-    let x = problem.ax * a_presses + problem.bx * b_presses;
-    let y = problem.ay * a_presses + problem.by * b_presses;
-    if problem.px == x && problem.py == y {
-        status.push(format!("Found at a: {}, b: {}", a_presses, b_presses));
+/// Collects the presses of every solution it finds in `found`.
+fn solve(problem: &Problem, a_presses: u64, b_presses: u64, found: &mut Vec<(u64, u64)>) -> Option<u64> {
+    if problem.a * a_presses as i64 + problem.b * b_presses as i64 == problem.prize {
+        found.push((a_presses, b_presses));
         Some(a_presses * A_COST + b_presses * B_COST)
     } else {
         // status.push(format!("a: {}, b: {}", a_presses, b_presses));
         if a_presses > 0 {
             if a_presses <= 100 {
-                solve(problem, a_presses + 1, b_presses, status)
+                solve(problem, a_presses + 1, b_presses, found)
             } else {
                 None
             }
         } else {
             let a_path = if a_presses <= 100 {
-                solve(problem, a_presses + 1, b_presses, status)
+                solve(problem, a_presses + 1, b_presses, found)
             } else {
                 None
             };
             let b_path = if b_presses <= 100 {
-                solve(problem, a_presses, b_presses + 1, status)
+                solve(problem, a_presses, b_presses + 1, found)
             } else {
                 None
             };
@@ -114,13 +158,14 @@ fn solve(problem: &Problem, a_presses: u64, b_presses: u64, status: &mut Vec<Str
     }
 }
 
-fn solve_gold(problem: &Problem, status: &mut Vec<String>) -> Option<u64> {
-    let ax = problem.ax as f64;
-    let ay = problem.ay as f64;
-    let bx = problem.bx as f64;
-    let by = problem.by as f64;
-    let px = problem.px as f64;
-    let py = problem.py as f64;
+/// The presses that solve the equations, and the tokens if they are whole numbers.
+fn solve_gold(problem: &Problem) -> (f64, f64, Option<u64>) {
+    let ax = problem.a.x() as f64;
+    let ay = problem.a.y() as f64;
+    let bx = problem.b.x() as f64;
+    let by = problem.b.y() as f64;
+    let px = problem.prize.x() as f64;
+    let py = problem.prize.y() as f64;
 
     // ax * a + bx * b = px
     // ay * a + by * b = py
@@ -131,25 +176,22 @@ fn solve_gold(problem: &Problem, status: &mut Vec<String>) -> Option<u64> {
     let b = (px / ax - py / ay) / (bx / ax - by / ay);
     let a = (px - bx * b) / ax;
 
-    status.push(format!("a{}, b{}", a, b));
-
     // These systems of linear equations are such that there's only one solution
     // If that solution happens to be an integer, then we ship it.
-    match (try_to_int(a, status), try_to_int(b, status)) {
+    let coins = match (try_to_int(a), try_to_int(b)) {
         (Some(a), Some(b)) => {
-            status.push(format!("int a{}, b{}", a, b));
             Some(a * 3 + b)
         }
         (_, _) => {
             None
         }
-    }
+    };
+    (a, b, coins)
 }
 
-fn try_to_int(input: f64, status: &mut Vec<String>) -> Option<u64> {
+fn try_to_int(input: f64) -> Option<u64> {
     let int = input.round() as u64;
     let loopback = int as f64;
-    status.push(format!("input: {}, int: {}, loopback: {}", input, int, loopback));
     let delta = f64::abs(input - loopback);
     if delta < 0.001 {
         Some(int)
@@ -1,14 +1,62 @@
-use std::ops::Deref;
+use std::rc::Rc;
 use regex::Regex;
 use yew::classes;
-use crate::app::{class_string, DayOutput, Diagnostic, Tab};
+use crate::app::{class_string, DayOutput, Diagnostic, Example, LazyTab, Part, Tab, TabContent};
+use crate::chart::{Chart, ChartSeries};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 use crate::common::capture_parse;
-use crate::grid::{Coord, Grid};
+use crate::grid::{Coord, Coord64, Grid};
 
+pub const TITLE: &str = "Restroom Redoubt";
 
-const GRID_WIDTH: i32 = 101;
-const GRID_HEIGHT: i32 = 103;
-const SECONDS: i32 = 100;
+// The second part looks for a picture, which the example doesn't have
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day14.txt"),
+        silver: Some("12"),
+        gold: None,
+        parameters: &[(GRID_WIDTH, 11), (GRID_HEIGHT, 7)],
+    },
+];
+
+const GRID_WIDTH: &str = "grid_width";
+const GRID_HEIGHT: &str = "grid_height";
+const SECONDS: &str = "seconds";
+
+pub const PARAMETERS: &[Parameter] = &[
+    Parameter {
+        name: GRID_WIDTH,
+        description: "Width of the room in tiles",
+        default: 101,
+        min: 1,
+        max: 1000,
+    },
+    Parameter {
+        name: GRID_HEIGHT,
+        description: "Height of the room in tiles",
+        default: 103,
+        min: 1,
+        max: 1000,
+    },
+    Parameter {
+        name: SECONDS,
+        description: "How long the robots move before the safety score is taken",
+        default: 100,
+        min: 0,
+        max: 1000000,
+    },
+];
+
+/// How many seconds get a tab and a point in the safety score chart, for searching pictures by hand.
+const SEARCH_SECONDS: i32 = 400;
+
+#[derive(Clone, Copy)]
+struct Room {
+    width: i32,
+    height: i32,
+}
 
 #[derive(Clone, Debug)]
 struct Robot {
@@ -18,9 +66,9 @@ struct Robot {
     vy: i32,
 }
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub fn puzzle(input: &str, part: Part, parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let re = Regex::new(r"p\=(?<px>\d+),(?<py>\d+) v=(?<vx>-?\d+),(?<vy>-?\d+)").unwrap();
-    let robots: Vec<Robot> = re.captures_iter(input).into_iter().filter_map(|captures| {
+    let robots: Vec<Robot> = re.captures_iter(input).filter_map(|captures| {
         match (
             capture_parse(&captures, "px"),
             capture_parse(&captures, "py"),
@@ -33,6 +81,11 @@ pub fn puzzle(input: &str) -> DayOutput {
             _ => None
         }
     }).collect();
+    let room = Room {
+        width: parameters.get(GRID_WIDTH),
+        height: parameters.get(GRID_HEIGHT),
+    };
+    stopwatch.lap(Phase::Parse);
 
     let mut errors = Vec::new();
     // let input_grid = Grid::from(input, |character| {
@@ -40,8 +93,9 @@ pub fn puzzle(input: &str) -> DayOutput {
     // });
     let mut tabs = vec![];
 
-    let silver_grid = apply_movement(&robots, SECONDS, &mut errors);
-    let silver = calculate_safety_score(&silver_grid);
+    let silver_grid = apply_movement(&robots, &room, parameters.get(SECONDS), &mut errors);
+    let silver = calculate_safety_score(&silver_grid, &room);
+    stopwatch.lap(Phase::Silver);
     // By manually searching, we find that there is a vertical constellation at 68, 169, 270
     // As well as a horizontal constellation at 136, 239, 342.
     // These are constant spacings of 101 and 103.
@@ -52,21 +106,31 @@ pub fn puzzle(input: &str) -> DayOutput {
     // And horizontal constellations at 18, 121, for a spacing of 103
     // y = 101x + 77, y = 103z + 18
     // y = 8258 + 10403 n
-    let grid = apply_movement(&robots, 7037, &mut errors);
-    add_tab(&mut tabs, 7037, grid);
-    let grid = apply_movement(&robots, 17440, &mut errors);
-    add_tab(&mut tabs, 17440, grid);
-    let starting_point = 8258;
-    let repeat_every = 10403;
-    let grid = apply_movement(&robots, starting_point, &mut errors);
-    add_tab(&mut tabs, starting_point, grid);
-    let grid = apply_movement(&robots, starting_point + repeat_every, &mut errors);
-    add_tab(&mut tabs, starting_point + repeat_every, grid);
-    // Manual search data
-    for seconds in 0..400 {
-        let grid = apply_movement(&robots, seconds, &mut errors);
-        add_tab(&mut tabs, seconds, grid);
+    let mut picture_tabs = vec![];
+    let mut search_tabs = vec![];
+    if part.gold() {
+        let robots = Rc::new(robots.clone());
+        let starting_point = 8258;
+        let repeat_every = 10403;
+        for seconds in [7037, 17440, starting_point, starting_point + repeat_every] {
+            picture_tabs.push(lazy_seconds_tab(&robots, room, seconds));
+        }
+        // Manual search data
+        for seconds in 0..SEARCH_SECONDS {
+            search_tabs.push(lazy_seconds_tab(&robots, room, seconds));
+        }
+        // Pictures clump the robots into one quadrant, which shows as a dip in the score
+        let safety_scores = (0..SEARCH_SECONDS).map(|seconds| {
+            let grid = apply_movement(&robots, &room, seconds, &mut Vec::new());
+            (seconds as f64, calculate_safety_score(&grid, &room) as f64)
+        }).collect();
+        tabs.push(Tab {
+            title: "Safety score".to_string(),
+            strings: vec![],
+            content: TabContent::Chart(Chart::line("Seconds".to_string(), "Safety score".to_string(), vec![ChartSeries::new(String::new(), safety_scores)])),
+        });
     }
+    stopwatch.lap(Phase::Gold);
 
     // let mut used_coords = HashSet::new();
     // let mut diagnostic_strings = Vec::new();
@@ -74,71 +138,70 @@ pub fn puzzle(input: &str) -> DayOutput {
     tabs.insert(0, Tab {
         title: "Input".to_string(),
         strings: robots.iter().map(|robot|format!("{:?}", robot)).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.insert(1, Tab {
         title: "Grid".to_string(),
         strings: vec![],
-        grid: silver_grid.to_tab_grid(),
+        content: TabContent::Grid(silver_grid.to_tab_grid()),
     });
     DayOutput {
         silver_output: format!("{}", silver),
         gold_output: format!("{}", 7037),
-        diagnostic: Diagnostic::with_tabs(tabs, format!("{:?}", errors)),
+        diagnostic: Diagnostic::with_tabs(tabs, format!("{:?}", errors))
+            .with_lazy_tabs(picture_tabs)
+            .with_lazy_series("Seconds".to_string(), search_tabs),
     }
 }
 
-fn add_tab(tabs: &mut Vec<Tab>, seconds: i32, grid: Grid<u64>) {
-    tabs.push(Tab {
-        title: format!("G{}", seconds),
-        strings: vec![],
-        grid: grid.to_tab_grid_class(|num| if *num > 0 {
-            class_string("bg-slate-300 text-slate-900")
-        } else {
-            classes!("")
-        }),
-    });
+/// The robots only get moved when the tab is opened.
+fn lazy_seconds_tab(robots: &Rc<Vec<Robot>>, room: Room, seconds: i32) -> LazyTab {
+    let robots = robots.clone();
+    LazyTab::new(format!("G{}", seconds), move || {
+        let mut errors = Vec::new();
+        let grid = apply_movement(&robots, &room, seconds, &mut errors);
+        Tab {
+            title: String::new(),
+            strings: errors,
+            content: TabContent::Grid(grid.to_tab_grid_class(|num| if *num > 0 {
+                class_string("bg-slate-300 text-slate-900")
+            } else {
+                classes!("")
+            })),
+        }
+    })
 }
 
-fn apply_movement(robots: &Vec<Robot>, seconds: i32, errors: &mut Vec<String>) -> Grid<u64> {
-    let mut grid: Grid<u64> = Grid::new_repeat(GRID_WIDTH as usize, GRID_HEIGHT as usize, 0);
+fn apply_movement(robots: &[Robot], room: &Room, seconds: i32, errors: &mut Vec<String>) -> Grid<u64> {
+    let mut grid: Grid<u64> = Grid::new_repeat(room.width as usize, room.height as usize, 0);
     for robot in robots.iter() {
-        let x = (robot.px + robot.vx * seconds) % GRID_WIDTH;
-        let y = (robot.py + robot.vy * seconds) % GRID_HEIGHT;
-        let x = if x < 0 {
-            GRID_WIDTH + x
-        } else {
-            x
-        };
-        let y = if y < 0 {
-            GRID_HEIGHT + y
-        } else {
-            y
-        };
-        if let Some(tile) = grid.get_mut(Coord::new(x, y)) {
+        // Velocity times seconds overflows an i32 long before the wrap brings it back into the room
+        let position = Coord64::new(robot.px, robot.py) + Coord64::new(robot.vx, robot.vy) * i64::from(seconds);
+        let position = position.rem_euclid(&Coord64::new(room.width, room.height));
+        if let Some(tile) = Coord::try_from(position).ok().and_then(|position| grid.get_mut(position)) {
             *tile += 1;
         } else {
-            errors.push(format!("Failed to get coordinate x {}, y {}", x, y));
+            errors.push(format!("Failed to get coordinate {}", position));
         }
     }
     grid
 }
 
 
-fn calculate_safety_score(grid: &Grid<u64>) -> u64 {
+fn calculate_safety_score(grid: &Grid<u64>, room: &Room) -> u64 {
     let mut quadrants = [0, 0, 0, 0];
     for coord in grid.get_all_coords() {
         if let Some(num_robots) = grid.get(coord) {
-            if coord.deref().0 * 2 + 1 < GRID_WIDTH {
-                if coord.deref().1 * 2 + 1 < GRID_HEIGHT {
+            if coord.x() * 2 + 1 < room.width {
+                if coord.y() * 2 + 1 < room.height {
                     quadrants[0] += *num_robots;
-                } else if coord.deref().1 * 2 + 1 > GRID_HEIGHT {
+                } else if coord.y() * 2 + 1 > room.height {
                     quadrants[1] += *num_robots;
                 }
-            } else if coord.deref().0 * 2 + 1 > GRID_WIDTH {
-                if coord.deref().1 * 2 + 1 < GRID_HEIGHT {
+            } else if coord.x() * 2 + 1 > room.width {
+                if coord.y() * 2 + 1 < room.height {
                     quadrants[2] += *num_robots;
-                } else if coord.deref().1 * 2 + 1 > GRID_HEIGHT {
+                } else if coord.y() * 2 + 1 > room.height {
                     quadrants[3] += *num_robots;
                 }
             }
@@ -1,7 +1,33 @@
 use std::fmt::{Display, Formatter};
-use std::ops::Deref;
-use crate::app::{DayOutput, Diagnostic, Tab};
-use crate::grid::{Coord, Grid};
+use std::rc::Rc;
+use crate::app::{DayOutput, Diagnostic, DiagnosticEntry, Example, LazyTab, Part, Tab, TabContent};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
+use crate::grid::{Coord, Direction, Grid};
+
+pub const TITLE: &str = "Warehouse Woes";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Small example",
+        input: include_str!("../res/examples/day15.txt"),
+        silver: Some("2028"),
+        gold: None,
+        parameters: &[],
+    },
+    Example {
+        name: "Larger example",
+        input: include_str!("../res/examples/day15-2.txt"),
+        silver: Some("10092"),
+        gold: Some("9021"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+/// Roughly how many frames the move animations have
+const FRAMES: usize = 200;
 
 #[derive(PartialEq, Clone, Debug)]
 enum Tile {
@@ -13,46 +39,49 @@ enum Tile {
     Empty,
 }
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let mut split = input.split("\n\n");
     let mut errors = Vec::new();
     let mut tabs = vec![];
     let mut coordinate_sum = 0;
     let mut coordinate_sum_gold = 0;
+    let mut silver_frames = vec![];
+    let mut gold_frames = vec![];
     if let (Some(input_grid), Some(input_movements)) = (split.next(), split.next()) {
-        let starting_grid = Grid::from_filtered(input_grid, |character| {
+        let starting_grid = Grid::parse_with_index_filtered(input_grid, |character, x, y| {
             match character {
                 '#' => Some(Tile::Wall),
                 'O' => Some(Tile::Box),
                 '@' => Some(Tile::Robot),
                 '.' => Some(Tile::Empty),
                 invalid => {
-                    errors.push(format!("Found invalid tile '{}'", invalid));
+                    errors.push(DiagnosticEntry::error(format!("Found invalid tile '{}'", invalid)).at(y as usize, x as usize, 1));
                     None
                 }
             }
+        }).unwrap_or_else(|error| {
+            errors.push(DiagnosticEntry::error(error));
+            Grid::default()
         });
-        let starting_grid_gold = Grid::from_filtered_flatten(input_grid, |character| {
+        // Invalid tiles and ragged rows were already reported above
+        let starting_grid_gold = Grid::parse_filtered_flatten(input_grid, |character| {
             match character {
                 '#' => Some(vec![Tile::Wall, Tile::Wall]),
                 'O' => Some(vec![Tile::BoxLeft, Tile::BoxRight]),
                 '@' => Some(vec![Tile::Robot, Tile::Empty]),
                 '.' => Some(vec![Tile::Empty, Tile::Empty]),
-                invalid => {
-                    errors.push(format!("Found invalid tile '{}'", invalid));
-                    None
-                }
+                _ => None,
             }
-        });
-        let movements = input_movements.chars().filter_map(|character| {
-            match character {
-                '^' => Some(Coord::new(0, -1)),
-                '>' => Some(Coord::new(1, 0)),
-                'v' => Some(Coord::new(0, 1)),
-                '<' => Some(Coord::new(-1, 0)),
-                '\n' => None,
-                invalid => {
-                    errors.push(format!("Found invalid command '{}'", invalid));
+        }).unwrap_or_default();
+        // The movements start after the grid and the empty line below it
+        let first_movement_line = input_grid.split('\n').count() + 1;
+        let movements = input_movements.split('\n').enumerate().flat_map(|(line, text)| {
+            text.chars().enumerate().map(move |(column, character)| (line, column, character))
+        }).filter_map(|(line, column, character)| {
+            match Direction::from_arrow(character).filter(|direction| direction.is_orthogonal()) {
+                Some(direction) => Some(direction.offset()),
+                None => {
+                    errors.push(DiagnosticEntry::error(format!("Found invalid command '{}'", character)).at(first_movement_line + line, column, 1));
                     None
                 }
             }
@@ -61,56 +90,86 @@ pub fn puzzle(input: &str) -> DayOutput {
         tabs.push(Tab {
             title: "Start Grid".to_string(),
             strings: vec![],
-            grid: starting_grid.to_tab_grid(),
+            content: TabContent::Grid(starting_grid.to_tab_grid()),
         });
         tabs.push(Tab {
             title: "Start Grid Gold".to_string(),
             strings: vec![],
-            grid: starting_grid_gold.to_tab_grid(),
+            content: TabContent::Grid(starting_grid_gold.to_tab_grid()),
         });
         tabs.push(Tab {
             title: "Input Commands".to_string(),
             strings: vec![format!("{:?}", movements)],
-            grid: vec![],
+            content: TabContent::Strings,
         });
+        stopwatch.lap(Phase::Parse);
 
-        let mut grid = starting_grid.clone();
-        if let Some(mut robot_position) = grid.find(|tile| *tile == Tile::Robot) {
-            for movement in movements.iter() {
-                apply_movement(&mut grid, &mut robot_position, movement, true, &mut errors);
-            }
-        };
-        coordinate_sum = calculate_gps_sum(&grid);
-        let mut grid_gold = starting_grid_gold.clone();
-        if let Some(mut robot_position) = grid_gold.find(|tile| *tile == Tile::Robot) {
-            for movement in movements.iter() {
-                let should_apply = apply_movement(&mut grid_gold, &mut robot_position, movement, false, &mut errors);
-                if should_apply {
-                    apply_movement(&mut grid_gold, &mut robot_position, movement, true, &mut errors);
-                }
-            }
-        };
-        coordinate_sum_gold = calculate_gps_sum(&grid_gold);
-        tabs.insert(3, Tab {
-            title: "Output Grid".to_string(),
-            strings: vec![],
-            grid: grid.to_tab_grid(),
-        });
-        tabs.insert(4, Tab {
-            title: "Output Grid Gold".to_string(),
-            strings: vec![],
-            grid: grid_gold.to_tab_grid(),
-        });
+        let movements = Rc::new(movements);
+        if part.silver() {
+            let grid = simulate(&starting_grid, &movements, false);
+            coordinate_sum = calculate_gps_sum(&grid);
+            tabs.push(Tab {
+                title: "Output Grid".to_string(),
+                strings: vec![],
+                content: TabContent::Grid(grid.to_tab_grid()),
+            });
+            silver_frames = lazy_frames("S", &starting_grid, &movements, false);
+        }
+        stopwatch.lap(Phase::Silver);
+        if part.gold() {
+            let grid_gold = simulate(&starting_grid_gold, &movements, true);
+            coordinate_sum_gold = calculate_gps_sum(&grid_gold);
+            tabs.push(Tab {
+                title: "Output Grid Gold".to_string(),
+                strings: vec![],
+                content: TabContent::Grid(grid_gold.to_tab_grid()),
+            });
+            gold_frames = lazy_frames("G", &starting_grid_gold, &movements, true);
+        }
+        stopwatch.lap(Phase::Gold);
+    } else {
+        errors.push(DiagnosticEntry::error("Expected the grid and the movements separated by an empty line".to_string()));
     }
     DayOutput {
         silver_output: format!("{}", coordinate_sum),
         gold_output: format!("{}", coordinate_sum_gold),
-        diagnostic: Diagnostic::with_tabs(tabs, format!("Errors: {:?}", errors)),
+        diagnostic: Diagnostic::with_tabs(tabs, format!("{} errors", errors.len()))
+            .with_entries(errors)
+            .with_lazy_series("Silver moves".to_string(), silver_frames)
+            .with_lazy_series("Gold moves".to_string(), gold_frames),
     }
 }
 
-fn apply_movement(grid: &mut Grid<Tile>, position: &mut Coord, movement: &Coord, apply: bool, error: &mut Vec<String>) -> bool {
-    let new_position = position.add(movement);
+/// Moves the robot through the first movements. Wide boxes can only move if both halves can, so that is checked first.
+fn simulate(starting_grid: &Grid<Tile>, movements: &[Coord], wide: bool) -> Grid<Tile> {
+    let mut grid = starting_grid.clone();
+    if let Some(mut robot_position) = grid.find(|tile| *tile == Tile::Robot) {
+        for movement in movements.iter() {
+            if !wide || apply_movement(&mut grid, &mut robot_position, movement, false) {
+                apply_movement(&mut grid, &mut robot_position, movement, true);
+            }
+        }
+    };
+    grid
+}
+
+/// About FRAMES tabs spread over the movements, each simulating up to its move when it is opened.
+fn lazy_frames(prefix: &str, starting_grid: &Grid<Tile>, movements: &Rc<Vec<Coord>>, wide: bool) -> Vec<LazyTab> {
+    let starting_grid = Rc::new(starting_grid.clone());
+    let moves_per_frame = movements.len().div_ceil(FRAMES).max(1);
+    (0..movements.len()).step_by(moves_per_frame).chain([movements.len()]).map(|moves| {
+        let starting_grid = starting_grid.clone();
+        let movements = movements.clone();
+        LazyTab::new(format!("{}{}", prefix, moves), move || Tab {
+            title: String::new(),
+            strings: vec![format!("After {} of {} moves", moves, movements.len())],
+            content: TabContent::Grid(simulate(&starting_grid, &movements[..moves], wide).to_tab_grid()),
+        })
+    }).collect()
+}
+
+fn apply_movement(grid: &mut Grid<Tile>, position: &mut Coord, movement: &Coord, apply: bool) -> bool {
+    let new_position = *position + *movement;
     match grid.get(new_position) {
         None => {
             false
@@ -120,24 +179,24 @@ fn apply_movement(grid: &mut Grid<Tile>, position: &mut Coord, movement: &Coord,
             let should_move = match target_tile {
                 Tile::Wall => false,
                 Tile::Box => {
-                    apply_movement(grid, &mut new_position.clone(), movement, apply, error)
+                    apply_movement(grid, &mut new_position.clone(), movement, apply)
                 }
                 Tile::BoxLeft => {
-                    if movement.deref().0 == 0 {
-                        let moved_left = apply_movement(grid, &mut new_position.clone(), movement, apply, error);
-                        let moved_right = apply_movement(grid, &mut (new_position.clone().add(&Coord::new(1, 0))), movement, apply, error);
+                    if movement.x() == 0 {
+                        let moved_left = apply_movement(grid, &mut new_position.clone(), movement, apply);
+                        let moved_right = apply_movement(grid, &mut (new_position + Coord::new(1, 0)), movement, apply);
                         moved_left && moved_right
                     } else {
-                        apply_movement(grid, &mut new_position.clone(), movement, apply, error)
+                        apply_movement(grid, &mut new_position.clone(), movement, apply)
                     }
                 }
                 Tile::BoxRight => {
-                    if movement.deref().0 == 0 {
-                        let moved_left = apply_movement(grid, &mut (new_position.clone().add(&Coord::new(-1, 0))), movement, apply, error);
-                        let moved_right = apply_movement(grid, &mut new_position.clone(), movement, apply, error);
+                    if movement.x() == 0 {
+                        let moved_left = apply_movement(grid, &mut (new_position + Coord::new(-1, 0)), movement, apply);
+                        let moved_right = apply_movement(grid, &mut new_position.clone(), movement, apply);
                         moved_left && moved_right
                     } else {
-                        apply_movement(grid, &mut new_position.clone(), movement, apply, error)
+                        apply_movement(grid, &mut new_position.clone(), movement, apply)
                     }
                 }
                 Tile::Robot => false,
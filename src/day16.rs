@@ -1,24 +1,72 @@
 use std::collections::{HashMap, HashSet};
-use std::collections::hash_map::Entry;
 use std::fmt::{Display, Formatter};
-use std::ops::Deref;
-use crate::app::{class_string, DayOutput, Diagnostic, Tab};
-use crate::grid::{Coord, Grid};
+use std::rc::Rc;
+use crate::app::{class_string, DayOutput, Diagnostic, DiagnosticEntry, Example, LazyTab, Part, Tab, TabContent};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
+use crate::grid::{Coord, Direction, Grid};
+use crate::pathfinding::Search;
 
-type Key = (Coord, Coord);
-type TileData = (u64, HashSet<Key>);
-type CandidateMap = HashMap<Key, TileData>;
-type VisitedTile = (TileData, Direction);
+pub const TITLE: &str = "Reindeer Maze";
 
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "First example",
+        input: include_str!("../res/examples/day16.txt"),
+        silver: Some("7036"),
+        gold: Some("45"),
+        parameters: &[],
+    },
+    Example {
+        name: "Second example",
+        input: include_str!("../res/examples/day16-2.txt"),
+        silver: Some("11048"),
+        gold: Some("64"),
+        parameters: &[],
+    },
+];
+
+const STEP_COST: &str = "step_cost";
+const TURN_COST: &str = "turn_cost";
+
+pub const PARAMETERS: &[Parameter] = &[
+    Parameter {
+        name: STEP_COST,
+        description: "Score for moving one tile forward",
+        default: 1,
+        min: 1,
+        max: 1000000,
+    },
+    Parameter {
+        name: TURN_COST,
+        description: "Score for turning 90 degrees",
+        default: 1000,
+        min: 0,
+        max: 1000000,
+    },
+];
+
+type Key = (Coord, Direction);
+type TileData = (u64, Vec<Key>);
+type VisitedTile = (TileData, Mark);
+
+/// What one step of the search changed. The step tabs replay these from the start instead of each keeping a copy of the search.
+#[derive(Clone, Debug)]
+struct StepChanges {
+    /// The state the step settled and its cost
+    expanded: (Key, u64),
+    /// Settled states with their cost and predecessors after the step
+    settled: Vec<(Key, TileData)>,
+    /// States that are reached but not settled, with their cost and predecessors after the step
+    reached: Vec<(Key, TileData)>,
+}
+
+/// What a visited tile shows: the way it was settled facing, or that it's only reached, or settled from several ways at once.
 #[derive(PartialEq, Clone, Debug)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
+enum Mark {
+    Facing(Direction),
     Frontier,
     Split,
-    Error,
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -30,10 +78,11 @@ enum Tile {
 }
 
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub fn puzzle(input: &str, part: Part, parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let mut start = None;
     let mut end = None;
-    let input_grid = Grid::from_with_index_filtered(input, |character, x, y| {
+    let mut entries = Vec::new();
+    let input_grid = Grid::parse_with_index_filtered(input, |character, x, y| {
         match character {
             '#' => Some(Tile::Wall),
             '.' => Some(Tile::Empty),
@@ -47,75 +96,85 @@ pub fn puzzle(input: &str) -> DayOutput {
             }
             _ => None,
         }
+    }).unwrap_or_else(|error| {
+        entries.push(DiagnosticEntry::error(error));
+        // Without a grid there is nothing to search
+        start = None;
+        end = None;
+        Grid::default()
     });
+    let step_cost: u64 = parameters.get(STEP_COST);
+    let turn_cost: u64 = parameters.get(TURN_COST);
+    stopwatch.lap(Phase::Parse);
     let mut tabs = vec![
         Tab {
             title: "Tab".to_string(),
             strings: vec![],
-            grid: input_grid.to_tab_grid(),
+            content: TabContent::Grid(input_grid.to_tab_grid()),
         },
     ];
 
+    let mut lazy_tabs = Vec::new();
+    let mut message = String::new();
     let mut silver = None;
     let mut gold = 0;
     if let (Some(start), Some(end)) = (start, end) {
-        let mut frontier: CandidateMap = create_frontier(&input_grid, start);
-        let mut explored: CandidateMap = HashMap::new();
-        // for step in 0..2000 {
-        let mut step = 0;
-        while !frontier.is_empty() {
-            if let Some((key, frontier_data)) = frontier.iter().next() {
-                let key = key.clone();
-                let frontier_data = frontier_data.clone();
-                let frontier_cost = frontier_data.0;
-                frontier.remove(&key);
-
-                add_to_candidate_map(&mut explored, key, frontier_data);
-                let (source, dir) = key;
-
-                for (new_position, new_dir, new_cost) in [
-                    (source.add(&dir), dir, frontier_cost + 1),
-                    (source, dir.rotate_left(), frontier_cost + 1000),
-                    (source, dir.rotate_right(), frontier_cost + 1000),
-                ] {
-                    let new_came_from = HashSet::from([key]);
-                    let (exists, exists_but_expensive) = if let Some((explored_cost, explored_came_from)) = explored.get_mut(&(new_position, new_dir)) {
-                        if *explored_cost == new_cost {
-                            explored_came_from.extend(new_came_from.iter());
-                        }
-                        (true, *explored_cost > new_cost)
-                    } else {
-                        (false, false)
-                    };
-                    let in_grid = Some(&Tile::Empty) == input_grid.get(new_position);
-                    if in_grid && (!exists || exists_but_expensive) {
-                        add_to_candidate_map(&mut frontier, (new_position, new_dir), (new_cost, new_came_from));
-                    }
-                }
-                if step < 200 {
-                    add_tab(&input_grid, &mut tabs, &frontier, &explored, format!("S{}", step), source, dir);
-                }
-                step += 1;
-            }
+        let shared_grid = Rc::new(input_grid.clone());
+        let starts = (input_grid.get(start) == Some(&Tile::Empty)).then_some((start, Direction::Right));
+        let moves = |(position, dir): Key| {
+            [
+                (position + dir.offset(), dir, step_cost),
+                (position, dir.rotate_left(), turn_cost),
+                (position, dir.rotate_right(), turn_cost),
+            ].into_iter()
+                .filter(|(new_position, _, _)| input_grid.get(*new_position) == Some(&Tile::Empty))
+                .map(|(new_position, new_dir, cost)| ((new_position, new_dir), cost))
+        };
+        let mut steps = Vec::new();
+        let search: Search<Key> = Search::new(starts).run(moves, |_| 0, |(position, _)| position == end, |search, key, cost| {
+            // Only the moves out of the settled state can have changed a cost
+            let reached = moves(key)
+                .filter(|(next, _)| !search.is_settled(*next))
+                .map(|(next, _)| (next, tile_data(search, next)))
+                .collect();
+            steps.push(StepChanges {
+                expanded: (key, cost),
+                settled: vec![(key, tile_data(search, key))],
+                reached,
+            });
+        });
+        let steps = Rc::new(steps);
+        for (step, changes) in steps.iter().enumerate().take(200) {
+            lazy_tabs.push(lazy_step_tab(&shared_grid, &steps, format!("S{}", step), step + 1, Some(changes.expanded)));
         }
-        add_tab(&input_grid, &mut tabs, &frontier, &explored, format!("Final state, {} steps", step), Coord::new(0, 0), Coord::new(0, 0));
+        lazy_tabs.push(lazy_step_tab(&shared_grid, &steps, format!("Final state, {} steps", search.expansions()), steps.len(), None));
+        let ends = Direction::ORTHOGONAL.map(|dir| (end, dir));
+        let end_cost = search.cheapest(ends.iter().copied());
+        message = match end_cost {
+            Some((_, end_cost)) => format!("Expanded {} states ({} reached) until the end was settled at {}", search.expansions(), search.costs().len(), end_cost),
+            None => format!("Expanded all {} reachable states without reaching the end", search.expansions()),
+        };
 
-        if let Some((end_key, cost)) = get_end_tile(&mut explored, end) {
+        stopwatch.lap(Phase::Silver);
+        if let Some((_end_key, cost)) = end_cost {
             silver = Some(cost);
-            let path_tiles: HashSet<Coord> = follow_path(&explored, end_key);
-            add_tab_gold(&input_grid, &mut tabs, &path_tiles, format!("Gold"));
-            gold = path_tiles.len();
+            if part.gold() {
+                let path_tiles: HashSet<Coord> = search.on_shortest_paths(ends).into_iter().map(|(position, _dir)| position).collect();
+                add_tab_gold(&input_grid, &mut tabs, &path_tiles, "Gold".to_string());
+                gold = path_tiles.len();
+            }
         }
+        stopwatch.lap(Phase::Gold);
 
         tabs.insert(1, Tab {
             title: "Frontier".to_string(),
-            strings: frontier.iter().map(|a| format!("{:?}", a)).collect(),
-            grid: vec![],
+            strings: search.frontier().map(|key| format!("{:?}", (key, tile_data(&search, key)))).collect(),
+            content: TabContent::Strings,
         });
         tabs.insert(2, Tab {
             title: "Explored".to_string(),
-            strings: explored.iter().map(|a| format!("{:?}", a)).collect(),
-            grid: vec![],
+            strings: search.settled().map(|key| format!("{:?}", (key, tile_data(&search, key)))).collect(),
+            content: TabContent::Strings,
         });
     }
 
@@ -123,41 +182,13 @@ pub fn puzzle(input: &str) -> DayOutput {
     DayOutput {
         silver_output: format!("{}", silver.unwrap_or(0)),
         gold_output: format!("{}", gold),
-        diagnostic: Diagnostic::with_tabs(tabs, format!("")),
-    }
-}
-
-fn create_frontier(grid: &Grid<Tile>, start: Coord) -> CandidateMap {
-    let start_tile = grid.get(start);
-    if let Some(Tile::Empty) = start_tile {
-        HashMap::from_iter([((start, Coord::new(1, 0)), (0, HashSet::new()))].into_iter())
-    } else {
-        HashMap::new()
+        diagnostic: Diagnostic::with_tabs(tabs, message).with_entries(entries).with_lazy_series("Search steps".to_string(), lazy_tabs),
     }
 }
 
-
-fn add_to_candidate_map(map: &mut CandidateMap, key: (Coord, Coord), new_data: TileData) {
-    match map.entry(key) {
-        Entry::Occupied(mut occupied) => {
-            combine_tile_data(occupied.get_mut(), &new_data);
-        }
-        Entry::Vacant(vacant) => {
-            vacant.insert(new_data);
-        }
-    }
-}
-
-// Returns true if first was used
-fn combine_tile_data(first: &mut TileData, second: &TileData) {
-    let (first_cost, first_came_from) = first;
-    let (second_cost, second_came_from) = second;
-    if *second_cost < *first_cost {
-        *first_came_from = second_came_from.clone();
-        *first_cost = *second_cost;
-    } else if *second_cost == *first_cost {
-        first_came_from.extend(second_came_from.iter());
-    }
+/// The cost of a search node and the nodes it can be reached from at that cost.
+fn tile_data(search: &Search<Key>, key: Key) -> TileData {
+    (search.cost(key).unwrap_or(0), search.predecessors(key).to_vec())
 }
 
 fn combine_tile(first: VisitedTile, second: VisitedTile) -> VisitedTile {
@@ -167,97 +198,69 @@ fn combine_tile(first: VisitedTile, second: VisitedTile) -> VisitedTile {
         ((second_cost, second_came_from), second_direction)
     } else if second_cost == first_cost {
         first_came_from.extend(second_came_from.iter());
-        ((first_cost, first_came_from), Direction::Split)
+        ((first_cost, first_came_from), Mark::Split)
     } else {
         ((first_cost, first_came_from), first_direction)
     }
 }
 
-fn get_end_tile(explored: &mut CandidateMap, end: Coord) -> Option<(Key, u64)> {
-    let mut output = None;
-    for dir in Coord::get_orthagonal_dirs().into_iter() {
-        let key = (end, dir);
-        if let Some((new_cost, _came_from)) = explored.get(&key) {
-            match output {
-                None => {
-                    output = Some((key, *new_cost));
-                }
-                Some((_, cost_value)) => {
-                    if *new_cost < cost_value {
-                        output = Some((key, *new_cost));
-                    }
-                }
-            }
-        }
-    }
-    output
+//////////////////////////////////
+// Visualization code
+//////////////////////////////////
+/// The steps are shared by all step tabs, the grids are built when the tab is opened.
+fn lazy_step_tab(input_grid: &Rc<Grid<Tile>>, steps: &Rc<Vec<StepChanges>>, title: String, step_count: usize, expanded: Option<(Key, u64)>) -> LazyTab {
+    let input_grid = input_grid.clone();
+    let steps = steps.clone();
+    LazyTab::new(title, move || render_step(&input_grid, &steps[..step_count], expanded))
 }
 
-fn follow_path(map: &CandidateMap, end_key: Key) -> HashSet<Coord> {
-    let mut remaining_tiles = HashSet::from([end_key]);
-    let mut path_tiles = HashSet::new();
-    while !remaining_tiles.is_empty() {
-        if let Some(next_key) = remaining_tiles.iter().next() {
-            let next_key = next_key.clone();
-            remaining_tiles.take(&next_key);
-            path_tiles.insert(next_key.0);
-            if let Some((_cost, came_from)) = map.get(&next_key) {
-                remaining_tiles.extend(came_from.iter());
-            }
-        }
+/// The settled and the reached but unsettled states after the steps.
+fn replay(steps: &[StepChanges]) -> (HashMap<Key, TileData>, HashMap<Key, TileData>) {
+    let mut settled = HashMap::new();
+    let mut frontier = HashMap::new();
+    for changes in steps {
+        frontier.remove(&changes.expanded.0);
+        settled.extend(changes.settled.iter().cloned());
+        frontier.extend(changes.reached.iter().cloned());
     }
-    path_tiles
+    (settled, frontier)
 }
 
-
-//////////////////////////////////
-// Visualization code
-//////////////////////////////////
-fn add_tab(input_grid: &Grid<Tile>, tabs: &mut Vec<Tab>, frontier: &CandidateMap, explored: &CandidateMap, title: String, source: Coord, dir: Coord) {
+/// The search after the steps, with the state the last one settled and its cost.
+fn render_step(input_grid: &Grid<Tile>, steps: &[StepChanges], expanded: Option<(Key, u64)>) -> Tab {
+    let (settled, frontier) = replay(steps);
     let mut grids = HashMap::new();
-    for dir in Coord::get_orthagonal_dirs() {
+    for dir in Direction::ORTHOGONAL {
         grids.insert(dir, input_grid.clone());
     }
     // Apply the explored tiles to the grid
-    for ((explored_pos, explored_dir), tile_data) in explored.iter() {
-        grids.get_mut(explored_dir).map(|grid| {
-            if let Some(tile_handle) = grid.get_mut(*explored_pos) {
-                let tile_data = tile_data.clone();
-                let direction = match explored_dir.deref() {
-                    (1, 0) => Direction::Right,
-                    (0, 1) => Direction::Down,
-                    (-1, 0) => Direction::Left,
-                    (0, -1) => Direction::Up,
-                    _ => Direction::Error,
-                };
-                *tile_handle = Tile::Visited((tile_data, direction));
+    for ((explored_pos, explored_dir), tile_data) in settled {
+        if let Some(grid) = grids.get_mut(&explored_dir) {
+            if let Some(tile_handle) = grid.get_mut(explored_pos) {
+                *tile_handle = Tile::Visited((tile_data, Mark::Facing(explored_dir)));
             }
-        });
+        }
     }
     // Apply the frontier tiles to the grid
-    for ((frontier_pos, frontier_dir), tile_data) in frontier.iter() {
-        grids.get_mut(frontier_dir).map(|grid| {
-            if let Some(tile_handle) = grid.get_mut(*frontier_pos) {
-                let tile_data = tile_data.clone();
-                *tile_handle = Tile::Visited((tile_data, Direction::Frontier));
+    for ((frontier_pos, frontier_dir), tile_data) in frontier {
+        if let Some(grid) = grids.get_mut(&frontier_dir) {
+            if let Some(tile_handle) = grid.get_mut(frontier_pos) {
+                *tile_handle = Tile::Visited((tile_data, Mark::Frontier));
             }
-        });
+        }
     }
     // Merge the directional grids. Both on top of each other, and with a "mushed" one
     // The "mushed" one uses the smallest cost for each tile
     let mut grid = Grid::new();
-    if let Some((first, rest)) = Coord::get_orthagonal_dirs().split_first() {
+    if let Some((first, rest)) = Direction::ORTHOGONAL.split_first() {
         if let Some(mut mushed_grid) = grids.remove(first) {
             let mut appended_grid = mushed_grid.clone();
-            for coord in rest {
-                if let Some(new_grid) = grids.remove(coord) {
+            for dir in rest {
+                if let Some(new_grid) = grids.remove(dir) {
                     mushed_grid.mush(&new_grid, |first, second| {
-                        match (first, second) {
-                            (Tile::Visited(first_visited), Tile::Visited(second_visited)) => {
-                                let new_visited = combine_tile(first_visited.clone(), second_visited.clone());
-                                *first_visited = new_visited;
-                            }
-                            (_, _) => {}
+                        if let (Tile::Visited(first_visited), Tile::Visited(second_visited)) = (first, second) {
+                            let new_visited = combine_tile(first_visited.clone(), second_visited.clone());
+                            *first_visited = new_visited;
                         }
                     });
                     appended_grid.append(new_grid);
@@ -267,10 +270,13 @@ fn add_tab(input_grid: &Grid<Tile>, tabs: &mut Vec<Tab>, frontier: &CandidateMap
             grid.append(appended_grid);
         }
     }
-    tabs.push(Tab {
-        title,
-        strings: vec![format!("{} {}", source, dir)],
-        grid: grid.to_tab_grid_title_class(|cell, x, y| {
+    Tab {
+        title: String::new(),
+        strings: vec![match expanded {
+            Some(((source, dir), cost)) => format!("Settled {} facing {} at {}", source, dir, cost),
+            None => format!("Settled {} states", steps.len()),
+        }],
+        content: TabContent::Grid(grid.to_tab_grid_title_class(|cell, x, y| {
             match cell.clone() {
                 Tile::Wall => (String::new(), yew::Classes::new()),
                 Tile::Empty => (String::new(), yew::Classes::new()),
@@ -286,8 +292,8 @@ fn add_tab(input_grid: &Grid<Tile>, tabs: &mut Vec<Tab>, frontier: &CandidateMap
                 }
                 Tile::Path => (String::new(), yew::Classes::new()),
             }
-        }),
-    })
+        })),
+    }
 }
 
 fn add_tab_gold(input_grid: &Grid<Tile>, tabs: &mut Vec<Tab>, path_tiles: &HashSet<Coord>, title: String) {
@@ -301,7 +307,7 @@ fn add_tab_gold(input_grid: &Grid<Tile>, tabs: &mut Vec<Tab>, path_tiles: &HashS
     tabs.push(Tab {
         title,
         strings: vec![],
-        grid: grid.to_tab_grid(),
+        content: TabContent::Grid(grid.to_tab_grid()),
     });
 }
 
@@ -312,15 +318,11 @@ impl Display for Tile {
         match self {
             Tile::Wall => f.write_str("#"),
             Tile::Empty => f.write_str("."),
-            Tile::Visited((_tile_data, direction)) => {
-                match direction {
-                    Direction::Up => f.write_str("^"),
-                    Direction::Right => f.write_str(">"),
-                    Direction::Down => f.write_str("v"),
-                    Direction::Left => f.write_str("<"),
-                    Direction::Frontier => f.write_str("o"),
-                    Direction::Split => f.write_str("x"),
-                    Direction::Error => f.write_str("!"),
+            Tile::Visited((_tile_data, mark)) => {
+                match mark {
+                    Mark::Facing(direction) => write!(f, "{}", direction),
+                    Mark::Frontier => f.write_str("o"),
+                    Mark::Split => f.write_str("x"),
                 }
             }
             Tile::Path => f.write_str("o"),
@@ -1,8 +1,34 @@
 use std::collections::{HashMap, HashSet};
 use regex::Regex;
-use crate::app::{DayOutput, Diagnostic, Tab};
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab, TabContent};
+use crate::chart::{Chart, ChartSeries};
+use crate::tree::Tree;
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 use crate::common::capture_parse;
 
+pub const TITLE: &str = "Chronospatial Computer";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day17.txt"),
+        silver: Some("4,6,3,5,6,3,5,2,1,0"),
+        gold: None,
+        parameters: &[],
+    },
+    Example {
+        name: "Gold example",
+        input: include_str!("../res/examples/day17-2.txt"),
+        silver: None,
+        gold: Some("117440"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(PartialEq, Clone, Debug)]
 enum Instruction {
     ADV(Combo),
@@ -36,12 +62,13 @@ struct State {
 }
 
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let re = Regex::new(r"Register A: (?P<a>\d+)\nRegister B: (?P<b>\d+)\nRegister C: (?P<c>\d+)\n\nProgram: (?P<program>\d(,\d)*)").unwrap();
     let mut errors = Vec::new();
     let mut output_silver: Option<Vec<u8>> = None;
     let mut output_gold = None;
     let mut diagnostic_gold = Vec::new();
+    let mut trace = Tree::new();
     let mut diagnostic_stepped = Vec::new();
     if let Some(captures) = re.captures(input) {
         let a = capture_parse(&captures, "a");
@@ -60,25 +87,35 @@ pub fn puzzle(input: &str) -> DayOutput {
                 c,
                 output: vec![],
             };
-            let silver_state = run_program(&program, input_state.clone(), 1000);
-            output_silver = Some(silver_state.output.clone());
-
-            let mut subprograms = Vec::new();
-            for i in (0..(program.len())).rev() {
-                subprograms.push(program[i..].to_vec());
+            stopwatch.lap(Phase::Parse);
+            if part.silver() {
+                let silver_state = run_program(&program, input_state.clone(), 1000);
+                output_silver = Some(silver_state.output.clone());
             }
-            errors.push(format!("{:?}", subprograms));
-            match gold_find_next(&program, input_state.clone(), &subprograms, 0, &mut diagnostic_gold) {
-                Ok(successes) => {
-                    let mut sorted_successes = successes.iter().collect::<Vec<_>>();
-                    sorted_successes.sort();
-                    errors.push(format!("Gold solutions: {:?}", sorted_successes));
-                    output_gold = successes.into_iter().min();
+            stopwatch.lap(Phase::Silver);
+
+            if part.gold() {
+                let mut subprograms = Vec::new();
+                for i in (0..(program.len())).rev() {
+                    subprograms.push(program[i..].to_vec());
                 }
-                Err(longest_failure) => {
-                    errors.push(format!("Couldn't find subprogram {:?}", longest_failure));
+                errors.push(format!("{:?}", subprograms));
+                let root = trace.add(None, "a = 0".to_string());
+                let result = gold_find_next(&program, input_state.clone(), &subprograms, 0, &mut diagnostic_gold, &mut trace, root);
+                trace.set_count(root, result.as_ref().map_or(0, |successes| successes.len() as u64));
+                match result {
+                    Ok(successes) => {
+                        let mut sorted_successes = successes.iter().collect::<Vec<_>>();
+                        sorted_successes.sort();
+                        errors.push(format!("Gold solutions: {:?}", sorted_successes));
+                        output_gold = successes.into_iter().min();
+                    }
+                    Err(longest_failure) => {
+                        errors.push(format!("Couldn't find subprogram {:?}", longest_failure));
+                    }
                 }
             }
+            stopwatch.lap(Phase::Gold);
 
             diagnostic_stepped = run_program_step(&program, input_state.clone(), 1000);
         }
@@ -92,23 +129,36 @@ pub fn puzzle(input: &str) -> DayOutput {
     tabs.push(Tab {
         title: "Stepped".to_string(),
         strings: diagnostic_stepped.into_iter().map(|a| format!("{:?}", a)).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
+    });
+    tabs.push(Tab {
+        title: "Output lengths".to_string(),
+        strings: vec![],
+        content: TabContent::Chart(Chart::bar("Output length".to_string(), "Attempts".to_string(), vec![
+            ChartSeries::new(String::new(), buckets.into_iter().map(|(length, count)| (length as f64, count as f64)).collect()),
+        ])),
+    });
+    tabs.push(Tab {
+        title: "Search trace".to_string(),
+        strings: vec!["Every attempted a with its output, counting the solutions found below it".to_string()],
+        content: TabContent::Tree(trace),
     });
     tabs.push(Tab {
         title: "Progression".to_string(),
         strings: diagnostic_gold.into_iter().take(3000).map(|a| format!("{:?}", a)).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
 
     let formatted_output = output_silver.map(|list| list.into_iter().map(|number| number.to_string()).collect::<Vec<_>>().join(","));
     DayOutput {
-        silver_output: format!("{}", formatted_output.unwrap_or(String::new())),
-        gold_output: format!("{}", output_gold.map(|set| format!("{:?}", set)).unwrap_or(String::new())),
+        silver_output: formatted_output.unwrap_or_default(),
+        gold_output: output_gold.map(|set| format!("{:?}", set)).unwrap_or_default(),
         diagnostic: Diagnostic::with_tabs(tabs, format!("{:?}", errors)),
     }
 }
 
-fn gold_find_next(program: &[u8], input_state: State, remaining_subprograms: &[Vec<u8>], a: u64, attempts: &mut Vec<(u64, State)>) -> Result<HashSet<u64>, Vec<u8>> {
+/// Every attempt is added to `trace` below `parent`.
+fn gold_find_next(program: &[u8], input_state: State, remaining_subprograms: &[Vec<u8>], a: u64, attempts: &mut Vec<(u64, State)>, trace: &mut Tree, parent: usize) -> Result<HashSet<u64>, Vec<u8>> {
     match remaining_subprograms.split_first() {
         None => {
             Ok(HashSet::from_iter([a]))
@@ -123,20 +173,20 @@ fn gold_find_next(program: &[u8], input_state: State, remaining_subprograms: &[V
                     a: attempted_a,
                     ..input_state.clone()
                 };
-                let output_state = run_program(&program, state, 1000);
+                let output_state = run_program(program, state, 1000);
                 attempts.push((a, output_state.clone()));
+                let node = trace.add(Some(parent), format!("a = {}: {:?}", attempted_a, output_state.output));
                 if output_state.output == *subprogram {
-                    match gold_find_next(program, input_state.clone(), remaining_subprograms, attempted_a, attempts) {
+                    match gold_find_next(program, input_state.clone(), remaining_subprograms, attempted_a, attempts, trace, node) {
                         Ok(success) => {
-                            successes.extend(success.into_iter());
+                            trace.set_count(node, success.len() as u64);
+                            successes.extend(success);
                         }
                         Err(failure) => {
-                            match &longest_failure {
-                                None => {}
-                                Some(current_longest) => {
-                                    if failure.len() > current_longest.len() {
-                                        longest_failure = Some(failure);
-                                    }
+                            trace.set_count(node, 0);
+                            if let Some(current_longest) = &longest_failure {
+                                if failure.len() > current_longest.len() {
+                                    longest_failure = Some(failure);
                                 }
                             }
                         }
@@ -200,42 +250,22 @@ fn run_program_step(instructions: &[u8], mut state: State, max_commands: usize)
 fn decode_instruction(opcode: u8, operand: u8) -> Option<Instruction> {
     match opcode {
         0 => {
-            if let Some(combo) = Combo::from_operand(operand) {
-                Some(Instruction::ADV(combo))
-            } else {
-                None
-            }
+            Combo::from_operand(operand).map(Instruction::ADV)
         }
         1 => Some(Instruction::BXL(operand)),
         2 => {
-            if let Some(combo) = Combo::from_operand(operand) {
-                Some(Instruction::BST(combo))
-            } else {
-                None
-            }
+            Combo::from_operand(operand).map(Instruction::BST)
         }
         3 => Some(Instruction::JNZ(operand)),
         4 => Some(Instruction::BXC),
         5 => {
-            if let Some(combo) = Combo::from_operand(operand) {
-                Some(Instruction::OUT(combo))
-            } else {
-                None
-            }
+            Combo::from_operand(operand).map(Instruction::OUT)
         }
         6 => {
-            if let Some(combo) = Combo::from_operand(operand) {
-                Some(Instruction::BDV(combo))
-            } else {
-                None
-            }
+            Combo::from_operand(operand).map(Instruction::BDV)
         }
         7 => {
-            if let Some(combo) = Combo::from_operand(operand) {
-                Some(Instruction::CDV(combo))
-            } else {
-                None
-            }
+            Combo::from_operand(operand).map(Instruction::CDV)
         }
         _ => None,
     }
@@ -250,7 +280,7 @@ fn apply_instruction(instruction: Instruction, mut state: State) -> State {
             state.a = new_state;
         }
         Instruction::BXL(operand) => {
-            state.b = state.b ^ operand as u64;
+            state.b ^= operand as u64;
         }
         Instruction::BST(combo) => {
             state.b = combo.get_number(&state) % 8;
@@ -261,7 +291,7 @@ fn apply_instruction(instruction: Instruction, mut state: State) -> State {
             }
         }
         Instruction::BXC => {
-            state.b = state.b ^ state.c;
+            state.b ^= state.c;
         }
         Instruction::OUT(combo) => {
             let item = combo.get_number(&state) % 8;
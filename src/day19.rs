@@ -1,22 +1,35 @@
 use std::collections::HashMap;
-use std::fmt::{Display, Formatter};
-use crate::app::{DayOutput, Diagnostic, Tab};
-use indextree::{Arena, NodeEdge, NodeId};
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab, TabContent};
+use crate::graph::Graph;
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
+use crate::tree::Tree;
+use indextree::{Arena, NodeId};
 
-struct ArenaPrinter<'a> {
-    arena: &'a Arena<char>,
-    root: NodeId,
-}
+pub const TITLE: &str = "Linen Layout";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day19.txt"),
+        silver: Some("6"),
+        gold: Some("16"),
+        parameters: &[],
+    },
+];
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub const PARAMETERS: &[Parameter] = &[];
+
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let mut split = input.split("\n\n");
     let (patterns, designs) = if let (Some(input_patterns), Some(input_designs)) = (split.next(), split.next()) {
-        let patterns = input_patterns.split(", ").into_iter().collect::<Vec<_>>();
-        let designs = input_designs.split("\n").into_iter().filter(|a| !a.is_empty()).collect::<Vec<_>>();
+        let patterns = input_patterns.split(", ").collect::<Vec<_>>();
+        let designs = input_designs.split("\n").filter(|a| !a.is_empty()).collect::<Vec<_>>();
         (patterns, designs)
     } else {
         (Vec::new(), Vec::new())
     };
+    stopwatch.lap(Phase::Parse);
 
     let errors: Vec<String> = Vec::new();
     let mut tabs = vec![];
@@ -27,33 +40,42 @@ pub fn puzzle(input: &str) -> DayOutput {
     }
 
     let mut memo = HashMap::new();
-    let results = designs.iter().map(|design| {
-        let possibilities = traverse(&arena, root_id, *design, &mut memo);
-        (design, possibilities)
-    }).collect::<Vec<_>>();
-    let num_passing = results.iter().filter(|(_, possibilities)| *possibilities > 0).count();
-    let combined_possibilities: u64 = results.iter().map(|(_, possibilities)| possibilities).sum();
-
+    let mut results = None;
+    let mut num_passing = 0;
+    if part.silver() {
+        let counted = count_arrangements(&arena, root_id, &designs, &mut memo);
+        num_passing = counted.iter().filter(|(_, possibilities)| *possibilities > 0).count();
+        results = Some(counted);
+    }
+    stopwatch.lap(Phase::Silver);
 
-    let tree_view = format!("{}", ArenaPrinter {
-        arena: &arena,
-        root: root_id,
-    }).replace(" ", ".");
+    let mut combined_possibilities: u64 = 0;
+    if part.gold() {
+        // Silver already counts every arrangement, so gold only counts them when silver didn't run
+        let counted = results.get_or_insert_with(|| count_arrangements(&arena, root_id, &designs, &mut memo));
+        combined_possibilities = counted.iter().map(|(_, possibilities)| possibilities).sum();
+    }
+    stopwatch.lap(Phase::Gold);
 
     tabs.push(Tab {
         title: "Tree view".to_string(),
-        strings: tree_view.split("\n").into_iter().map(|item| item.to_string()).collect(),
-        grid: vec![],
+        strings: vec![],
+        content: TabContent::Tree(trie_tree(&arena, root_id)),
+    });
+    tabs.push(Tab {
+        title: "Trie".to_string(),
+        strings: vec![],
+        content: TabContent::Graph(trie_graph(&arena, root_id)),
     });
     tabs.push(Tab {
         title: "Patterns".to_string(),
         strings: patterns.into_iter().map(|pattern| pattern.to_string()).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Results".to_string(),
-        strings: results.into_iter().map(|(design, passes)| format!("{design}: {passes:?}")).collect(),
-        grid: vec![],
+        strings: results.unwrap_or_default().into_iter().map(|(design, passes)| format!("{design}: {passes:?}")).collect(),
+        content: TabContent::Strings,
     });
     DayOutput {
         silver_output: format!("{}", num_passing),
@@ -62,6 +84,11 @@ pub fn puzzle(input: &str) -> DayOutput {
     }
 }
 
+/// Every design with the number of ways the patterns can make it.
+fn count_arrangements<'a>(arena: &Arena<char>, root_id: NodeId, designs: &[&'a str], memo: &mut HashMap<&'a str, u64>) -> Vec<(&'a str, u64)> {
+    designs.iter().map(|design| (*design, traverse(arena, root_id, design, memo))).collect()
+}
+
 fn traverse<'a>(arena: &Arena<char>, root_id: NodeId, haystack: &'a str, memo: &mut HashMap<&'a str, u64>) -> u64 {
     if let Some(output) = memo.get(haystack) {
         return *output;
@@ -71,19 +98,17 @@ fn traverse<'a>(arena: &Arena<char>, root_id: NodeId, haystack: &'a str, memo: &
             0
         }
         Some(slices) => {
-            'block: loop {
-                let mut accumulator = 0;
-                for slice in slices {
-                    // let local_accumulated_slices = accumulated_slices.clone().into_iter().chain([haystack[..slice].to_string()]).collect();
-                    if slice == haystack.len() {
-                        accumulator += 1;
-                    }
-                    // options.push(format!("Slicing {} from {}, {} remains", &haystack[..slice], haystack, &haystack[slice..]));
-                    let possibilities = traverse(arena, root_id, &haystack[slice..], memo);
-                    accumulator += possibilities;
+            let mut accumulator = 0;
+            for slice in slices {
+                // let local_accumulated_slices = accumulated_slices.clone().into_iter().chain([haystack[..slice].to_string()]).collect();
+                if slice == haystack.len() {
+                    accumulator += 1;
                 }
-                break 'block accumulator;
+                // options.push(format!("Slicing {} from {}, {} remains", &haystack[..slice], haystack, &haystack[slice..]));
+                let possibilities = traverse(arena, root_id, &haystack[slice..], memo);
+                accumulator += possibilities;
             }
+            accumulator
         }
     };
     memo.insert(haystack, possibilities);
@@ -111,7 +136,48 @@ fn get_slices(arena: &Arena<char>, root: NodeId, haystack: &str) -> Option<Vec<u
         }
         index += 1;
     }
-    return Some(slices);
+    Some(slices)
+}
+
+/// Counts how many patterns end below every node, the end markers themselves don't get a count.
+fn trie_tree(arena: &Arena<char>, root: NodeId) -> Tree {
+    let mut tree = Tree::from_arena(arena, root, |character| if *character == '.' {
+        "end".to_string()
+    } else {
+        character.to_string()
+    });
+    let mut patterns = vec![0; tree.nodes.len()];
+    for index in (0..tree.nodes.len()).rev() {
+        let node = &tree.nodes[index];
+        if node.children.is_empty() && node.label == "end" {
+            patterns[index] = 1;
+        } else {
+            patterns[index] = node.children.iter().map(|child| patterns[*child]).sum();
+            tree.set_count(index, patterns[index]);
+        }
+    }
+    tree
+}
+
+/// Nodes are labelled with their character, but keyed by their arena index since characters repeat.
+fn trie_graph(arena: &Arena<char>, root: NodeId) -> Graph {
+    let mut graph = Graph::new(true);
+    let mut ends = Vec::new();
+    for node_id in root.descendants(arena) {
+        let key = node_id.to_string();
+        let index = graph.node(&key);
+        if let Some(node) = arena.get(node_id) {
+            graph.nodes[index].label = node.get().to_string();
+            if *node.get() == '.' {
+                ends.push(key.clone());
+            }
+            if let Some(parent) = node.parent() {
+                graph.edge(&parent.to_string(), &key, String::new());
+            }
+        }
+    }
+    graph.group("Pattern end".to_string(), ends.iter().map(String::as_str));
+    graph
 }
 
 fn add_string(arena: &mut Arena<char>, root: NodeId, string: &str) -> NodeId {
@@ -123,7 +189,7 @@ fn add_string(arena: &mut Arena<char>, root: NodeId, string: &str) -> NodeId {
     node_id
 }
 
-fn get_or_insert_child<'a>(arena: &mut Arena<char>, parent_id: NodeId, new_char: char) -> NodeId {
+fn get_or_insert_child(arena: &mut Arena<char>, parent_id: NodeId, new_char: char) -> NodeId {
     for child_id in parent_id.children(arena) {
         if let Some(child) = arena.get(child_id) {
             if *child.get() == new_char {
@@ -135,30 +201,3 @@ fn get_or_insert_child<'a>(arena: &mut Arena<char>, parent_id: NodeId, new_char:
     parent_id.append(new_node, arena);
     new_node
 }
-
-
-impl Display for ArenaPrinter<'_> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut depth: i32 = 0;
-        for edge in self.root.traverse(self.arena) {
-            match edge {
-                NodeEdge::Start(start) => {
-                    if let Some(node) = self.arena.get(start) {
-                        let character = *node.get();
-                        if character == '.' {
-                            f.write_str("*")?;
-                        } else {
-                            let padding = ".".repeat(std::cmp::max(depth - 1, 0) as usize);
-                            f.write_fmt(format_args!("\n{}{}", padding, character))?;
-                        }
-                    }
-                    depth += 1;
-                }
-                NodeEdge::End(_end) => {
-                    depth -= 1;
-                }
-            }
-        }
-        Ok(())
-    }
-}
\ No newline at end of file
@@ -1,78 +1,118 @@
 use regex::Regex;
 use Direction::*;
-use crate::app::{DayOutput, Diagnostic};
+use crate::app::{DayOutput, Diagnostic, DiagnosticEntry, Example, Part};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 
-pub fn puzzle(input: &str) -> DayOutput {
-    let re = Regex::new(r"([0-9]*) ").unwrap();
-    let mut num_matches = 0;
-    let reports = format!("{} ", input).split("\n")
-        .filter_map(|line| {
-            num_matches += 1;
-            let report = re.find_iter(&format!("{} ", line))
-                .filter_map(|current_match| {
-                    match current_match.as_str().trim().parse::<u64>() {
-                        Ok(level) => {
-                            Some(level)
-                        }
-                        _ => {
-                            None
-                        }
+pub const TITLE: &str = "Red-Nosed Reports";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day2.txt"),
+        silver: Some("2"),
+        gold: Some("4"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
+    let token = Regex::new(r"\S+").unwrap();
+    let mut entries = Vec::new();
+    let reports = input.split("\n")
+        .enumerate()
+        .filter_map(|(line_index, line)| {
+            let mut levels = Vec::new();
+            let mut columns = Vec::new();
+            for found in token.find_iter(line) {
+                match found.as_str().parse::<u64>() {
+                    Ok(level) => {
+                        levels.push(level);
+                        columns.push((found.start(), found.len()));
                     }
-                })
-                .collect::<Vec<_>>();
-            if report.is_empty() {
+                    Err(_) => {
+                        entries.push(DiagnosticEntry::error(format!("'{}' isn't a level", found.as_str())).at(line_index, found.start(), found.len()));
+                    }
+                }
+            }
+            if levels.is_empty() {
                 None
             } else {
-                Some(report)
+                Some(Report {
+                    line: line_index,
+                    levels,
+                    columns,
+                })
             }
         })
         .collect::<Vec<_>>();
 
+    stopwatch.lap(Phase::Parse);
+
     let report_length = reports.len();
     let mut safe_reports = 0;
     let mut safe_reports_allowing_one = 0;
-    let mut error_message = None;
-    for (_index, report) in reports.into_iter().enumerate() {
-        match judge_safety(&report) {
+    let mut unsafe_reports = 0;
+    let mut first_unsafe = None;
+    for Report { line, levels: report, columns } in reports.into_iter() {
+        let safety = judge_safety(&report);
+        stopwatch.lap(Phase::Silver);
+        match safety {
             Ok(()) => {
                 safe_reports += 1;
                 safe_reports_allowing_one += 1;
             }
-            Err(error) => {
-                if let None = error_message {
-                    error_message = Some(error);
+            Err((index, error)) => {
+                unsafe_reports += 1;
+                if first_unsafe.is_none() {
+                    let (column, length) = columns[index];
+                    first_unsafe = Some((error, line, column, length));
                 }
-                for i in 0..report.len() {
-                    let mut current_report = report.clone();
-                    current_report.remove(i);
-                    match judge_safety(&current_report) {
-                        Ok(()) => {
+                if part.gold() {
+                    for i in 0..report.len() {
+                        let mut current_report = report.clone();
+                        current_report.remove(i);
+                        if let Ok(()) = judge_safety(&current_report) {
                             safe_reports_allowing_one += 1;
                             break;
                         }
-                        Err(_) => {}
                     }
                 }
             }
         }
+        stopwatch.lap(Phase::Gold);
+    }
+    // One entry for all of them, most reports in the real input are unsafe
+    if let Some((error, line, column, length)) = first_unsafe {
+        entries.push(DiagnosticEntry::info(format!("{} of {} reports are unsafe, the first one here: {}", unsafe_reports, report_length, error)).at(line, column, length));
     }
     DayOutput {
         silver_output: format!("{}", safe_reports),
         gold_output: format!("{:?}", safe_reports_allowing_one),
-        diagnostic: Diagnostic::simple(format!("Num reports: {}, first error message \n{:?}", report_length, error_message)),
+        diagnostic: Diagnostic::simple(format!("Num reports: {}", report_length)).with_entries(entries),
     }
 }
 
+struct Report {
+    line: usize,
+    levels: Vec<u64>,
+    /// Where each level starts in its line, and how long it is
+    columns: Vec<(usize, usize)>,
+}
+
 enum Direction {
     Undetermined,
     Increasing,
     Decreasing,
 }
 
-fn judge_safety(report: &Vec<u64>) -> Result<(), String> {
+/// On failure, returns the index of the level where the report became unsafe.
+fn judge_safety(report: &[u64]) -> Result<(), (usize, String)> {
     let mut stored_previous = None;
     let mut direction = Undetermined;
-    for (index, level) in report.into_iter().enumerate() {
+    for (index, level) in report.iter().enumerate() {
         match stored_previous {
             None => {
                 stored_previous = Some(level);
@@ -85,12 +125,12 @@ fn judge_safety(report: &Vec<u64>) -> Result<(), String> {
                                 direction = Decreasing;
                             }
                             Increasing => {
-                                return Err(format!("Direction changed from increasing to decreasing at index {}.", index));
+                                return Err((index, format!("Direction changed from increasing to decreasing at index {}.", index)));
                             }
                             Decreasing => {}
                         }
                     } else {
-                        return Err(format!("Decremented with more than 3, {} to {}, index {}", previous_level, level, index));
+                        return Err((index, format!("Decremented with more than 3, {} to {}, index {}", previous_level, level, index)));
                     }
                 } else if level > previous_level {
                     if level - previous_level <= 3 {
@@ -100,14 +140,14 @@ fn judge_safety(report: &Vec<u64>) -> Result<(), String> {
                             }
                             Increasing => {}
                             Decreasing => {
-                                return Err(format!("Direction changed from decreasing to increasing at index {}.", index));
+                                return Err((index, format!("Direction changed from decreasing to increasing at index {}.", index)));
                             }
                         }
                     } else {
-                        return Err(format!("Incremented with more than 3, {} to {}, index {}", level, previous_level, index));
+                        return Err((index, format!("Incremented with more than 3, {} to {}, index {}", previous_level, level, index)));
                     }
                 } else { //equal
-                    return Err(format!("Two consecutive equal values at index {}", index));
+                    return Err((index, format!("Two consecutive equal values at index {}", index)));
                 }
                 stored_previous = Some(level);
             }
@@ -1,14 +1,43 @@
 use std::collections::{HashMap, HashSet};
-use std::collections::hash_map::Entry;
 use std::fmt::{Display, Formatter};
-use crate::app::{DayOutput, Diagnostic, Tab};
+use crate::app::{DayOutput, Diagnostic, DiagnosticEntry, Example, Part, Tab, TabContent};
+use crate::chart::{Chart, ChartSeries};
+use crate::table::{ColumnKind, Table};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 use crate::grid::{Coord, Grid};
-
-type Key = Coord;
-type TileData = (u64, HashSet<Key>);
-type CandidateMap = HashMap<Key, TileData>;
-
-const LIMIT: u64 = 100;
+use crate::pathfinding::bfs;
+
+pub const TITLE: &str = "Race Condition";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day20.txt"),
+        silver: Some("1"),
+        gold: Some("285"),
+        parameters: &[(LIMIT, 50)],
+    },
+    Example {
+        name: "Example, short cheats",
+        input: include_str!("../res/examples/day20.txt"),
+        silver: Some("5"),
+        gold: None,
+        parameters: &[(LIMIT, 20)],
+    },
+];
+
+const LIMIT: &str = "limit";
+
+pub const PARAMETERS: &[Parameter] = &[
+    Parameter {
+        name: LIMIT,
+        description: "How many picoseconds a cheat has to save to count",
+        default: 100,
+        min: 0,
+        max: 1000000,
+    },
+];
 
 #[derive(PartialEq, Clone, Debug)]
 enum Tile {
@@ -17,10 +46,11 @@ enum Tile {
     Path,
 }
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub fn puzzle(input: &str, part: Part, parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let mut start = None;
     let mut end = None;
-    let input_grid = Grid::from_with_index_filtered(input, |character, x, y| {
+    let mut entries = Vec::new();
+    let input_grid = Grid::parse_with_index_filtered(input, |character, x, y| {
         match character {
             '#' => Some(Tile::Wall),
             '.' => Some(Tile::Empty),
@@ -34,7 +64,15 @@ pub fn puzzle(input: &str) -> DayOutput {
             }
             _ => None,
         }
+    }).unwrap_or_else(|error| {
+        entries.push(DiagnosticEntry::error(error));
+        // Without a grid there is nothing to search
+        start = None;
+        end = None;
+        Grid::default()
     });
+    let limit: u64 = parameters.get(LIMIT);
+    stopwatch.lap(Phase::Parse);
 
     let mut tabs = vec![];
     let errors: Vec<String> = vec![];
@@ -43,66 +81,73 @@ pub fn puzzle(input: &str) -> DayOutput {
     tabs.push(Tab {
         title: "Input".to_string(),
         strings: vec![],
-        grid: input_grid.to_tab_grid(),
+        content: TabContent::Grid(input_grid.to_tab_grid()),
     });
     if let (Some(start), Some(end)) = (start, end) {
-        let explored = pathfind(&input_grid, &mut tabs, start);
-        let path_tiles = follow_path(&explored, end, true);
-        let default_total_steps = (path_tiles.len() - 1) as u64;
-        add_tab_path(&mut tabs, &input_grid, path_tiles, format!("Default"));
+        // Every cheat is judged by the distances from the start, so the search doesn't stop at the end
+        let search = bfs(&input_grid, start, None, |tile| *tile == Tile::Empty);
+        let explored = search.costs();
+        let path_tiles: HashSet<Coord> = search.path_to(end).unwrap_or_default().into_iter().collect();
+        let default_total_steps = path_tiles.len().saturating_sub(1) as u64;
+        add_tab_path(&mut tabs, &input_grid, path_tiles, "Default".to_string());
 
         let mut cheats_silver: Vec<((Coord, Coord), u64)> = Vec::new();
         let mut cheats_gold: Vec<((Coord, Coord), u64)> = Vec::new();
-        for cheat_from in explored.keys() {
-            for cheat_to in explored.keys() {
-                let steps = steps_away(cheat_from, cheat_to);
-                try_cheat(&explored, &mut cheats_silver, cheat_from, cheat_to, steps, 2);
-                try_cheat(&explored, &mut cheats_gold, cheat_from, cheat_to, steps, 20);
+        if part.silver() {
+            for cheat_from in explored.keys() {
+                for cheat_to in explored.keys() {
+                    let steps = cheat_from.manhattan(cheat_to);
+                    try_cheat(explored, &mut cheats_silver, cheat_from, cheat_to, steps, 2);
+                }
+            }
+        }
+        stopwatch.lap(Phase::Silver);
+        if part.gold() {
+            for cheat_from in explored.keys() {
+                for cheat_to in explored.keys() {
+                    let steps = cheat_from.manhattan(cheat_to);
+                    try_cheat(explored, &mut cheats_gold, cheat_from, cheat_to, steps, 20);
+                }
+            }
+        }
+        stopwatch.lap(Phase::Gold);
+        let mut cheats_table = Table::new(&[
+            ("From x", ColumnKind::Integer),
+            ("From y", ColumnKind::Integer),
+            ("To x", ColumnKind::Integer),
+            ("To y", ColumnKind::Integer),
+            ("Saved", ColumnKind::Integer),
+        ]);
+        for ((Coord((from_x, from_y)), Coord((to_x, to_y))), saved) in cheats_silver.iter() {
+            if *saved != default_total_steps {
+                cheats_table.push(vec![(*from_x).into(), (*from_y).into(), (*to_x).into(), (*to_y).into(), (*saved).into()]);
             }
         }
         tabs.push(Tab {
             title: "Cheats".to_string(),
-            strings: cheats_silver.iter().filter_map(|cheat| {
-                if cheat.1 != default_total_steps {
-                    Some(format!("{:?}", cheat))
-                } else {
-                    None
-                }
-            }).collect(),
-            grid: vec![],
+            strings: vec![],
+            content: TabContent::Table(cheats_table),
         });
         let mut saved_to_num_silver = HashMap::new();
         for (_key, delta) in cheats_silver.iter() {
             let num = saved_to_num_silver.entry(delta).or_insert(0);
             *num += 1;
-            if *delta >= LIMIT {
+            if *delta >= limit {
                 silver += 1;
             }
         }
 
-        tabs.push(Tab {
-            title: "Cheat summary".to_string(),
-            strings: saved_to_num_silver.iter().map(|(saved, num)| {
-                format!("saved: {}, num: {}", saved, num)
-            }).collect(),
-            grid: vec![],
-        });
+        tabs.push(cheat_summary_tab("Cheat summary".to_string(), &saved_to_num_silver));
         let mut saved_to_num_gold = HashMap::new();
         for (_key, delta) in cheats_gold.iter() {
             let num = saved_to_num_gold.entry(delta).or_insert(0);
             *num += 1;
-            if *delta >= LIMIT {
+            if *delta >= limit {
                 gold += 1;
             }
         }
 
-        tabs.push(Tab {
-            title: "Cheat summary gold".to_string(),
-            strings: saved_to_num_gold.iter().map(|(saved, num)| {
-                format!("saved: {}, num: {}", saved, num)
-            }).collect(),
-            grid: vec![],
-        });
+        tabs.push(cheat_summary_tab("Cheat summary gold".to_string(), &saved_to_num_gold));
     }
     // let mut front = TAKE;
     // let mut end = wall_coordinates.len();
@@ -125,29 +170,33 @@ pub fn puzzle(input: &str) -> DayOutput {
     DayOutput {
         silver_output: format!("{}", silver),
         gold_output: format!("{}", gold),
-        diagnostic: Diagnostic::with_tabs(tabs, format!("{:?}", errors)),
+        diagnostic: Diagnostic::with_tabs(tabs, format!("{:?}", errors)).with_entries(entries),
     }
 }
 
-fn try_cheat(explored: &CandidateMap, cheats: &mut Vec<((Coord, Coord), u64)>, cheat_from: &Key, cheat_to: &Key, steps: i32, max_steps: i32) {
+/// How many cheats save each number of picoseconds.
+fn cheat_summary_tab(title: String, saved_to_num: &HashMap<&u64, u64>) -> Tab {
+    let points = saved_to_num.iter().map(|(saved, num)| (**saved as f64, *num as f64)).collect();
+    Tab {
+        title,
+        strings: vec![],
+        content: TabContent::Chart(Chart::bar("Picoseconds saved".to_string(), "Cheats".to_string(), vec![ChartSeries::new(String::new(), points)])),
+    }
+}
+
+fn try_cheat(explored: &HashMap<Coord, u64>, cheats: &mut Vec<((Coord, Coord), u64)>, cheat_from: &Coord, cheat_to: &Coord, steps: u64, max_steps: u64) {
     if steps <= max_steps && cheat_to != cheat_from {
-        if let (Some(data_from), Some(data_to)) = (explored.get(cheat_from), explored.get(cheat_to)) {
-            let cost_from = data_from.0;
-            let cost_to = data_to.0;
-            if cost_to > cost_from {
-                if (cost_to - cost_from) > steps as u64 {
-                    let delta = (cost_to - cost_from) - steps as u64;
+        if let (Some(cost_from), Some(cost_to)) = (explored.get(cheat_from), explored.get(cheat_to)) {
+            let (cost_from, cost_to) = (*cost_from, *cost_to);
+            if cost_to > cost_from
+                && (cost_to - cost_from) > steps {
+                    let delta = (cost_to - cost_from) - steps;
                     cheats.push(((*cheat_from, *cheat_to), delta));
                 }
-            }
         }
     }
 }
 
-fn steps_away(start: &Coord, end: &Coord) -> i32 {
-    i32::abs(start.0.0 - end.0.0) + i32::abs(start.0.1 - end.0.1)
-}
-
 fn add_tab_path(tabs: &mut Vec<Tab>, grid: &Grid<Tile>, path_tiles: HashSet<Coord>, title: String) {
     let mut grid = grid.clone();
     // Apply the path tiles
@@ -158,105 +207,11 @@ fn add_tab_path(tabs: &mut Vec<Tab>, grid: &Grid<Tile>, path_tiles: HashSet<Coor
     }
     tabs.push(Tab {
         title,
-        strings: vec![format!("{} nanos", path_tiles.len() - 1)],
-        grid: grid.to_tab_grid(),
+        strings: vec![format!("{} nanos", path_tiles.len().saturating_sub(1))],
+        content: TabContent::Grid(grid.to_tab_grid()),
     });
 }
 
-fn pathfind(input_grid: &Grid<Tile>, _tabs: &mut Vec<Tab>, start: Coord) -> CandidateMap {
-    let grid = input_grid.clone();
-    let mut frontier: CandidateMap = create_frontier(&grid, start);
-    let mut explored: CandidateMap = HashMap::new();
-
-    while !frontier.is_empty() {
-        if let Some((key, frontier_data)) = frontier.iter().next() {
-            let key = key.clone();
-            let frontier_data = frontier_data.clone();
-            let frontier_cost = frontier_data.0;
-            frontier.remove(&key);
-
-            add_to_candidate_map(&mut explored, key, frontier_data);
-            let source = key;
-
-            for dir in Coord::get_orthagonal_dirs() {
-                let new_position = source.add(&dir);
-                let new_cost = frontier_cost + 1;
-                let new_came_from = HashSet::from([key]);
-                let (exists, exists_but_expensive) = if let Some((explored_cost, explored_came_from)) = explored.get_mut(&new_position) {
-                    if *explored_cost == new_cost {
-                        explored_came_from.extend(new_came_from.iter());
-                    }
-                    (true, *explored_cost > new_cost)
-                } else {
-                    (false, false)
-                };
-                let in_grid = Some(&Tile::Empty) == grid.get(new_position);
-                if in_grid && (!exists || exists_but_expensive) {
-                    add_to_candidate_map(&mut frontier, new_position, (new_cost, new_came_from));
-                }
-            }
-        }
-    }
-
-    explored
-}
-
-fn create_frontier(grid: &Grid<Tile>, start: Coord) -> CandidateMap {
-    let start_tile = grid.get(start);
-    if let Some(Tile::Empty) = start_tile {
-        HashMap::from_iter([(start, (0, HashSet::new()))].into_iter())
-    } else {
-        HashMap::new()
-    }
-}
-
-
-fn add_to_candidate_map(map: &mut CandidateMap, key: Key, new_data: TileData) {
-    match map.entry(key) {
-        Entry::Occupied(mut occupied) => {
-            combine_tile_data(occupied.get_mut(), &new_data);
-        }
-        Entry::Vacant(vacant) => {
-            vacant.insert(new_data);
-        }
-    }
-}
-
-fn combine_tile_data(first: &mut TileData, second: &TileData) {
-    let (first_cost, first_came_from) = first;
-    let (second_cost, second_came_from) = second;
-    if *second_cost < *first_cost {
-        *first_came_from = second_came_from.clone();
-        *first_cost = *second_cost;
-    } else if *second_cost == *first_cost {
-        first_came_from.extend(second_came_from.iter());
-    }
-}
-
-fn follow_path(map: &CandidateMap, end: Key, only_shortest: bool) -> HashSet<Coord> {
-    if let Some((end_key, _cost)) = map.get(&end).map(|tile_data| (end, tile_data.0)) {
-        let mut remaining_tiles = HashSet::from([end_key]);
-        let mut path_tiles = HashSet::<Coord>::new();
-        while !remaining_tiles.is_empty() {
-            if let Some(next_key) = remaining_tiles.iter().next() {
-                let next_key = next_key.clone();
-                remaining_tiles.remove(&next_key);
-                path_tiles.insert(next_key);
-                if let Some((_cost, came_from)) = map.get(&next_key) {
-                    if only_shortest {
-                        remaining_tiles.extend(came_from.iter().take(1));
-                    } else {
-                        remaining_tiles.extend(came_from.iter());
-                    }
-                }
-            }
-        }
-        path_tiles
-    } else {
-        HashSet::new()
-    }
-}
-
 impl Display for Tile {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -3,12 +3,43 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::hash::Hash;
-use crate::app::{DayOutput, Diagnostic, Tab};
-use crate::grid::{Coord, Grid};
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab, TabContent};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
+use crate::grid::{Coord, Direction, Grid};
 use itertools::Itertools;
 
-const DIRECTION_KEY_LEVELS_SILVER: usize = 2;
-const DIRECTION_KEY_LEVELS_GOLD: usize = 25;
+pub const TITLE: &str = "Keypad Conundrum";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day21.txt"),
+        silver: Some("126384"),
+        gold: Some("154115708116294"),
+        parameters: &[],
+    },
+];
+
+const DIRECTION_KEY_LEVELS_SILVER: &str = "direction_key_levels_silver";
+const DIRECTION_KEY_LEVELS_GOLD: &str = "direction_key_levels_gold";
+
+pub const PARAMETERS: &[Parameter] = &[
+    Parameter {
+        name: DIRECTION_KEY_LEVELS_SILVER,
+        description: "How many robots with directional keypads there are between you and the numeric keypad, first part",
+        default: 2,
+        min: 0,
+        max: 50,
+    },
+    Parameter {
+        name: DIRECTION_KEY_LEVELS_GOLD,
+        description: "How many robots with directional keypads there are between you and the numeric keypad, second part",
+        default: 25,
+        min: 0,
+        max: 50,
+    },
+];
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
 pub enum Action {
@@ -28,14 +59,19 @@ enum NumpadKey {
 }
 
 type ArrowLookup = HashMap<(Action, Action), Vec<Action>>;
-type CostLookup = HashMap<(Action, Action), u64>;
-
-pub fn puzzle(input: &str) -> DayOutput {
-    let silver = 0;
-    let gold = 0;
+/// Presses per pair of actions, in u128 because they grow about 2.5 times per level.
+type CostLookup = HashMap<(Action, Action), u128>;
+/// The sequence typed on each of the four keypad layers.
+type LayerSequences = (Vec<Action>, Vec<Action>, Vec<Action>, Vec<Action>);
+/// A code, its press count, and the press count of each pair of actions.
+type CodeCost = (u64, u128, Vec<((Action, Action), u128)>);
+
+pub fn puzzle(input: &str, part: Part, parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
+    let mut silver = 0;
+    let mut gold = 0;
     // Parse input
     let mut errors: Vec<String> = Vec::new();
-    let inputs = input.split("\n").into_iter().filter_map(|line| {
+    let inputs = input.split("\n").filter_map(|line| {
         let code = line.trim_matches('A').parse::<u64>().ok();
         let line = line.chars().filter_map(|char| {
             match char {
@@ -51,17 +87,18 @@ pub fn puzzle(input: &str) -> DayOutput {
             None
         }
     }).collect::<Vec<(Vec<NumpadKey>, u64)>>();
+    stopwatch.lap(Phase::Parse);
 
     let mut tabs: Vec<Tab> = Vec::new();
     let numpad_start = Coord::new(2, 3);
-    let numpad_grid = Grid(vec![
+    let numpad_grid = Grid::from_rows(vec![
         vec![NumpadKey::Number(7), NumpadKey::Number(8), NumpadKey::Number(9)],
         vec![NumpadKey::Number(4), NumpadKey::Number(5), NumpadKey::Number(6)],
         vec![NumpadKey::Number(1), NumpadKey::Number(2), NumpadKey::Number(3)],
         vec![NumpadKey::Empty, NumpadKey::Number(0), NumpadKey::A],
     ]);
     let direction_start = Coord::new(2, 0);
-    let direction_grid = Grid(vec![
+    let direction_grid = Grid::from_rows(vec![
         vec![Action::Empty, Action::Up, Action::A],
         vec![Action::Left, Action::Down, Action::Right],
     ]);
@@ -77,20 +114,26 @@ pub fn puzzle(input: &str) -> DayOutput {
         strings: lookup.iter().sorted().map(|(pair, actions)| {
             format!("{:?}, {:?}", pair, actions)
         }).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
 
     });
     // Construct lookup for direction grid
 
     // for middle_layers in 0..1 {
     add_comparison(&mut errors, &inputs, &mut tabs, numpad_start, &numpad_grid, direction_start, &direction_grid, &lookup, 0);
-    let silver = add_comparison(&mut errors, &inputs, &mut tabs, numpad_start, &numpad_grid, direction_start, &direction_grid, &lookup, DIRECTION_KEY_LEVELS_SILVER);
-    let gold = add_comparison(&mut errors, &inputs, &mut tabs, numpad_start, &numpad_grid, direction_start, &direction_grid, &lookup, DIRECTION_KEY_LEVELS_GOLD);
+    if part.silver() {
+        silver = add_comparison(&mut errors, &inputs, &mut tabs, numpad_start, &numpad_grid, direction_start, &direction_grid, &lookup, parameters.get(DIRECTION_KEY_LEVELS_SILVER));
+    }
+    stopwatch.lap(Phase::Silver);
+    if part.gold() {
+        gold = add_comparison(&mut errors, &inputs, &mut tabs, numpad_start, &numpad_grid, direction_start, &direction_grid, &lookup, parameters.get(DIRECTION_KEY_LEVELS_GOLD));
+    }
+    stopwatch.lap(Phase::Gold);
     // }
     tabs.push(Tab {
         title: "Errors".to_string(),
         strings: errors,
-        grid: vec![],
+        content: TabContent::Strings,
     });
     // tabs.push(Tab {
     //     title: "Silver solutions".to_string(),
@@ -110,14 +153,15 @@ pub fn puzzle(input: &str) -> DayOutput {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn add_comparison(mut errors: &mut Vec<String>, inputs: &Vec<(Vec<NumpadKey>, u64)>, tabs: &mut Vec<Tab>, numpad_start: Coord, numpad_grid: &Grid<NumpadKey>, direction_start: Coord, direction_grid: &Grid<Action>, lookup: &ArrowLookup, middle_layers: usize) -> u128{
     //let silver_brute_force = solve_silver_brute_force(&inputs, &numpad_grid, numpad_start, &direction_grid, direction_start, &mut errors);
     //let brute_force = solve_brute_force(&inputs, &numpad_grid, numpad_start, &direction_grid, direction_start, middle_layers, &mut errors);
     // let linear_expansions = solve_linear_expansion(&inputs, &numpad_grid, numpad_start, &lookup, middle_layers, &mut errors);
-    let thin_layer_lookups = solve_thin_layer_lookups(&inputs, &numpad_grid, numpad_start, &lookup, middle_layers, &mut errors);
-    let iterate_dict = solve_iterate_dict(&inputs, &numpad_grid, numpad_start, &lookup, middle_layers, &mut errors);
+    let thin_layer_lookups = solve_thin_layer_lookups(inputs, numpad_grid, numpad_start, lookup, middle_layers, errors);
+    let iterate_dict = solve_iterate_dict(inputs, numpad_grid, numpad_start, lookup, middle_layers, errors);
 
-    let answer_value = iterate_dict.iter().map(|(code, cost, _)| *code as u128 * *cost as u128).sum::<u128>();
+    let answer_value = iterate_dict.iter().map(|(code, cost, _)| *code as u128 * *cost).sum::<u128>();
 
     // let comparisons = p(silver_brute_force, "silver_brute").interleave(p(brute_force, "brute")).interleave(
     //     p(linear_expansions, "linear").interleave(p(thin_layer_lookups, "thin")));
@@ -130,7 +174,7 @@ fn add_comparison(mut errors: &mut Vec<String>, inputs: &Vec<(Vec<NumpadKey>, u6
         strings: comparisons.collect(),
         // strings: p(iterate_dict, "").collect(),
         // strings: Vec::new(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     answer_value
 }
@@ -171,18 +215,18 @@ fn manual_arrow_lookup(mut errors: &mut Vec<String>) -> HashMap<(Action, Action)
 
     map
 }
-fn construct_arrow_lookup(mut errors: &mut Vec<String>, direction_start: Coord, direction_grid: &Grid<Action>) -> (Vec<String>, HashMap<(Action, Action), Vec<Action>>) {
+fn construct_arrow_lookup(mut errors: &mut Vec<String>, direction_start: Coord, direction_grid: &Grid<Action>) -> (Vec<String>, ArrowLookup) {
     let optimal_clusters = Action::get_all_pairs().into_iter().map(|(first, second)| {
-        let mut outputs: Vec<(Vec<Action>, Vec<Action>, Vec<Action>, Vec<Action>)> = Vec::new();
-        let seqs1 = expand_all_brute_from_first(&direction_grid, &vec![first, second], &mut errors);
+        let mut outputs: Vec<LayerSequences> = Vec::new();
+        let seqs1 = expand_all_brute_from_first(direction_grid, &vec![first, second], errors);
         for seq1 in seqs1.into_iter() {
             // errors.push(format!("Seq 1 {}{} {:?}", first, second, seq1));
-            let seqs2 = expand_all_brute(&direction_grid, Action::Empty, direction_start, &vec![seq1.clone()], &mut errors);
+            let seqs2 = expand_all_brute(direction_grid, Action::Empty, direction_start, std::slice::from_ref(&seq1), errors);
             for seq2 in seqs2.into_iter() {
                 // errors.push(format!("Seq 2 {}{} {:?}", first, second, seq2));
-                let seqs3 = expand_all_brute(&direction_grid, Action::Empty, direction_start, &vec![seq2.clone()], &mut errors);
+                let seqs3 = expand_all_brute(direction_grid, Action::Empty, direction_start, std::slice::from_ref(&seq2), errors);
                 for seq3 in seqs3.into_iter() {
-                    let seqs4 = expand_all_brute(&direction_grid, Action::Empty, direction_start, &vec![seq3.clone()], &mut errors);
+                    let seqs4 = expand_all_brute(direction_grid, Action::Empty, direction_start, std::slice::from_ref(&seq3), errors);
                     for seq4 in seqs4.into_iter() {
                         outputs.push((seq1.clone(), seq2.clone(), seq3.clone(), seq4.clone()))
                     }
@@ -216,7 +260,7 @@ fn construct_arrow_lookup(mut errors: &mut Vec<String>, direction_start: Coord,
 pub fn expand_all_brute_from_first(grid: &Grid<Action>, sequences: &Vec<Action>, errors: &mut Vec<String>) -> Vec<Vec<Action>> {
     if let Some((first, rest)) = sequences.split_first() {
         if let Some(start) = grid.find(|item| *item == *first) {
-            expand_all_brute(&grid, Action::Empty, start, &vec![rest.to_vec()], errors)
+            expand_all_brute(grid, Action::Empty, start, &[rest.to_vec()], errors)
         } else {
             errors.push(format!("Found no item {} in grid", first));
             Vec::new()
@@ -227,19 +271,19 @@ pub fn expand_all_brute_from_first(grid: &Grid<Action>, sequences: &Vec<Action>,
     }
 }
 
-pub fn expand_all_brute<T>(grid: &Grid<T>, blank: T, start_pos: Coord, sequences: &Vec<Vec<T>>, errors: &mut Vec<String>) -> Vec<Vec<Action>>
+pub fn expand_all_brute<T>(grid: &Grid<T>, blank: T, start_pos: Coord, sequences: &[Vec<T>], errors: &mut [String]) -> Vec<Vec<Action>>
     where T: Eq + Hash + Clone {
     let mut coordinate_lookup: HashMap<T, Coord> = HashMap::new();
-    let _ = grid.map_grid(|cell, x, y| {
-        coordinate_lookup.entry(cell.clone()).or_insert(Coord((x as i32, y as i32)));
-    });
+    for (coord, cell) in grid.iter() {
+        coordinate_lookup.entry(cell.clone()).or_insert(coord);
+    }
     let mut all_output_sequences = Vec::new();
     let mut current_pos = start_pos;
     for sequence in sequences.iter() {
         let mut output_sequences = vec![vec![]];
         for item in sequence.iter() {
             if let Some(new_pos) = coordinate_lookup.get(item) {
-                let delta = new_pos.subtract(&current_pos);
+                let delta = *new_pos - current_pos;
                 let Coord((delta_x, delta_y)) = delta;
                 let mut vertical = vec![];
                 let mut horizontal = vec![];
@@ -264,11 +308,11 @@ pub fn expand_all_brute<T>(grid: &Grid<T>, blank: T, start_pos: Coord, sequences
                     }
                 }
                 if !vertical.is_empty() && !horizontal.is_empty() {
-                    let order_one = horizontal.clone().into_iter().chain(vertical.clone().into_iter()).chain([Action::A].into_iter()).collect::<Vec<_>>();
-                    let order_two = vertical.clone().into_iter().chain(horizontal.clone().into_iter()).chain([Action::A].into_iter()).collect::<Vec<_>>();
+                    let order_one = horizontal.clone().into_iter().chain(vertical.clone()).chain([Action::A]).collect::<Vec<_>>();
+                    let order_two = vertical.clone().into_iter().chain(horizontal.clone()).chain([Action::A]).collect::<Vec<_>>();
                     output_sequences = add_possibilities(output_sequences, vec![order_one, order_two], errors);
                 } else {
-                    let order_one = horizontal.clone().into_iter().chain(vertical.clone().into_iter()).chain([Action::A].into_iter()).collect::<Vec<_>>();
+                    let order_one = horizontal.clone().into_iter().chain(vertical.clone()).chain([Action::A]).collect::<Vec<_>>();
                     output_sequences = add_possibilities(output_sequences, vec![order_one], errors);
                 }
                 current_pos = *new_pos;
@@ -276,13 +320,13 @@ pub fn expand_all_brute<T>(grid: &Grid<T>, blank: T, start_pos: Coord, sequences
         }
         all_output_sequences.append(&mut output_sequences);
     }
-    let all_output_sequences = all_output_sequences.into_iter().filter(|sequence| {
+    
+    all_output_sequences.into_iter().filter(|sequence| {
         playback_ok(grid, blank.clone(), start_pos, sequence)
-    }).collect::<Vec<_>>();
-    all_output_sequences
+    }).collect::<Vec<_>>()
 }
 
-fn add_possibilities(sequences: Vec<Vec<Action>>, possibilities: Vec<Vec<Action>>, errors: &mut Vec<String>) -> Vec<Vec<Action>> {
+fn add_possibilities(sequences: Vec<Vec<Action>>, possibilities: Vec<Vec<Action>>, errors: &mut [String]) -> Vec<Vec<Action>> {
     // errors.push(format!("adding possibilities {:?}", possibilities));
     let mut output_sequences = Vec::new();
     for possibility in possibilities.into_iter() {
@@ -295,7 +339,7 @@ fn add_possibilities(sequences: Vec<Vec<Action>>, possibilities: Vec<Vec<Action>
     output_sequences
 }
 
-fn playback_ok<T>(grid: &Grid<T>, blank: T, start_pos: Coord, sequence: &Vec<Action>) -> bool
+fn playback_ok<T>(grid: &Grid<T>, blank: T, start_pos: Coord, sequence: &[Action]) -> bool
     where T: Eq + Hash + Clone {
     if let Some(tile) = grid.get(start_pos) {
         if *tile == blank {
@@ -306,23 +350,11 @@ fn playback_ok<T>(grid: &Grid<T>, blank: T, start_pos: Coord, sequence: &Vec<Act
     }
     let mut pos = start_pos;
     for action in sequence.iter() {
-        match action {
-            Action::Up => {
-                pos = pos.add(&Coord::new(0, -1));
-            }
-            Action::Down => {
-                pos = pos.add(&Coord::new(0, 1));
-            }
-            Action::Left => {
-                pos = pos.add(&Coord::new(-1, 0));
-            }
-            Action::Right => {
-                pos = pos.add(&Coord::new(1, 0));
-            }
-            Action::A => {}
-            Action::Empty => {
-                return false;
-            }
+        if *action == Action::Empty {
+            return false;
+        }
+        if let Some(direction) = action.direction() {
+            pos = pos + direction.offset();
         }
         if let Some(tile) = grid.get(pos) {
             if *tile == blank {
@@ -345,12 +377,22 @@ impl Action {
             Action::Right,
         ]
     }
+    /// The way the arrow keys move, `None` for A and the gap.
+    pub fn direction(self) -> Option<Direction> {
+        match self {
+            Action::Up => Some(Direction::Up),
+            Action::Down => Some(Direction::Down),
+            Action::Left => Some(Direction::Left),
+            Action::Right => Some(Direction::Right),
+            Action::A | Action::Empty => None,
+        }
+    }
     pub fn get_all_pairs() -> Vec<(Action, Action)> {
-        Self::get_all().into_iter().map(|first| {
+        Self::get_all().into_iter().flat_map(|first| {
             Self::get_all().into_iter().map(move |second| {
                 (first, second)
             })
-        }).flatten().collect()
+        }).collect()
     }
 }
 
@@ -358,10 +400,7 @@ impl Display for Action {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Action::A => f.write_str("A"),
-            Action::Up => f.write_str("^"),
-            Action::Down => f.write_str("v"),
-            Action::Left => f.write_str("<"),
-            Action::Right => f.write_str(">"),
+            Action::Up | Action::Down | Action::Left | Action::Right => write!(f, "{}", self.direction().unwrap()),
             Action::Empty => f.write_str("!"),
         }
     }
@@ -378,13 +417,13 @@ fn solve_silver_brute_force(
     numpad_start: Coord,
     direction_grid: &Grid<Action>,
     direction_start: Coord,
-    errors: &mut Vec<String>,
+    errors: &mut [String],
 ) -> Vec<(u64, usize, Vec<Action>)> {
     let mut outputs = Vec::new();
     for (input_row, code) in inputs {
-        let actions_one = expand_all_brute(&numpad_grid, NumpadKey::Empty, numpad_start, &vec![input_row.clone()], errors);
-        let actions_two = expand_all_brute(&direction_grid, Action::Empty, direction_start, &actions_one, errors);
-        let actions_three = expand_all_brute(&direction_grid, Action::Empty, direction_start, &actions_two, errors);
+        let actions_one = expand_all_brute(numpad_grid, NumpadKey::Empty, numpad_start, std::slice::from_ref(input_row), errors);
+        let actions_two = expand_all_brute(direction_grid, Action::Empty, direction_start, &actions_one, errors);
+        let actions_three = expand_all_brute(direction_grid, Action::Empty, direction_start, &actions_two, errors);
         // stepped_outputs.push(format!("{:?}", actions_one));
         // stepped_outputs.push(format!("{:?}", actions_two));
         let shortest = actions_three.into_iter().min_by_key(|sequence| sequence.len()).unwrap_or(Vec::new());
@@ -401,13 +440,13 @@ fn solve_brute_force(
     direction_grid: &Grid<Action>,
     direction_start: Coord,
     num_middle_layers: usize,
-    errors: &mut Vec<String>,
+    errors: &mut [String],
 ) -> Vec<(u64, usize, Vec<Action>)> {
     let mut outputs = Vec::new();
     for (input_row, code) in inputs {
-        let mut actions = expand_all_brute(&numpad_grid, NumpadKey::Empty, numpad_start, &vec![input_row.clone()], errors);
+        let mut actions = expand_all_brute(numpad_grid, NumpadKey::Empty, numpad_start, std::slice::from_ref(input_row), errors);
         for _ in 0..num_middle_layers {
-            actions = expand_all_brute(&direction_grid, Action::Empty, direction_start, &actions, errors);
+            actions = expand_all_brute(direction_grid, Action::Empty, direction_start, &actions, errors);
         }
         let shortest = actions.into_iter().min_by_key(|sequence| sequence.len()).unwrap_or(Vec::new());
         let length = shortest.len();
@@ -428,12 +467,12 @@ fn solve_linear_expansion(
     // let mut all_shortest = Vec::new();
     let mut outputs = Vec::new();
     for (input_row, code) in inputs {
-        let mut action_possibilities = expand_all_brute(&numpad_grid, NumpadKey::Empty, numpad_start, &vec![input_row.clone()], errors);
+        let mut action_possibilities = expand_all_brute(numpad_grid, NumpadKey::Empty, numpad_start, std::slice::from_ref(input_row), errors);
         let mut expanded_possibilities = Vec::new();
         for input_actions in action_possibilities.iter() {
             let mut actions = input_actions.clone();
             for _level in 0..num_middle_layers {
-                actions = expand_linear(&lookup, &actions, errors);
+                actions = expand_linear(lookup, &actions, errors);
             }
             expanded_possibilities.push(actions);
         }
@@ -472,15 +511,15 @@ fn solve_thin_layer_lookups(
     numpad_start: Coord,
     lookup: &ArrowLookup,
     num_middle_layers: usize,
-    errors: &mut Vec<String>,
-) -> Vec<(u64, u64)> {
+    errors: &mut [String],
+) -> Vec<(u64, u128)> {
     if num_middle_layers == 0 {
         return Vec::new()
     }
-    let cost_lookup = construct_layer_lookups(&lookup, num_middle_layers);
+    let cost_lookup = construct_layer_lookups(lookup, num_middle_layers);
     let mut outputs = Vec::new();
     for (input_row, code) in inputs {
-        let mut action_possibilities = expand_all_brute(&numpad_grid, NumpadKey::Empty, numpad_start, &vec![input_row.clone()], errors);
+        let mut action_possibilities = expand_all_brute(numpad_grid, NumpadKey::Empty, numpad_start, std::slice::from_ref(input_row), errors);
         let mut costs = Vec::new();
         for possibility in action_possibilities {
             let cost = find_cost_for_sequence(&cost_lookup, &possibility);
@@ -519,7 +558,7 @@ fn construct_layer_lookup(arrow_lookup: &ArrowLookup, sub_layer_lookup: &CostLoo
     let mut output = HashMap::new();
     for pair in Action::get_all_pairs() {
         if let Some(sequence) = arrow_lookup.get(&pair) {
-            let total_cost = find_cost_for_sequence(sub_layer_lookup, &sequence);
+            let total_cost = find_cost_for_sequence(sub_layer_lookup, sequence);
             output.insert(pair, total_cost);
         }
     }
@@ -527,10 +566,10 @@ fn construct_layer_lookup(arrow_lookup: &ArrowLookup, sub_layer_lookup: &CostLoo
 }
 
 fn construct_first_layer_lookup(arrow_lookup: &ArrowLookup) -> CostLookup {
-    HashMap::from_iter(arrow_lookup.iter().map(|(pair, list)| (pair.clone(), list.len() as u64)))
+    HashMap::from_iter(arrow_lookup.iter().map(|(pair, list)| (*pair, list.len() as u128)))
 }
 
-fn find_cost_for_sequence(cost_lookup: &CostLookup, sequence: &Vec<Action>) -> u64 {
+fn find_cost_for_sequence(cost_lookup: &CostLookup, sequence: &Vec<Action>) -> u128 {
     let mut last = Action::A;
     let mut total_cost = 0;
     for item in sequence {
@@ -550,10 +589,10 @@ fn solve_iterate_dict(
     lookup: &ArrowLookup,
     num_middle_layers: usize,
     errors: &mut Vec<String>,
-) -> Vec<(u64, u128, Vec<((Action, Action), u128)>)> {
+) -> Vec<CodeCost> {
     let mut outputs = Vec::new();
     for (input_row, code) in inputs {
-        let mut action_possibilities = expand_all_brute(&numpad_grid, NumpadKey::Empty, numpad_start, &vec![input_row.clone()], errors);
+        let mut action_possibilities = expand_all_brute(numpad_grid, NumpadKey::Empty, numpad_start, std::slice::from_ref(input_row), errors);
         let mut dicts = Vec::new();
         for possibility in action_possibilities {
             let mut transition_to_count = HashMap::new();
@@ -586,9 +625,9 @@ fn solve_iterate_dict(
 fn add_transitions_iterate_dict(transition_to_count: &mut HashMap<(Action, Action), u128>, possibility: &Vec<Action>, count: u128) {
     let mut last_action = Action::A;
     for action in possibility {
-        let transition = (last_action, action.clone());
+        let transition = (last_action, *action);
         *transition_to_count.entry(transition).or_insert(0) += count;
-        last_action = action.clone();
+        last_action = *action;
     }
 }
 
@@ -1,42 +1,89 @@
 use std::collections::hash_map::Entry::Vacant;
 use std::collections::HashMap;
-use crate::app::{DayOutput, Diagnostic, Tab};
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab, TabContent};
+use crate::chart::Chart;
+use crate::table::{ColumnKind, Table};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub const TITLE: &str = "Monkey Market";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day22.txt"),
+        silver: Some("37327623"),
+        gold: None,
+        parameters: &[],
+    },
+    Example {
+        name: "Gold example",
+        input: include_str!("../res/examples/day22-2.txt"),
+        silver: None,
+        gold: Some("23"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let input_numbers = input.split("\n")
-        .into_iter()
         .filter_map(|line| line.parse::<u64>().ok())
         .collect::<Vec<_>>();
+    stopwatch.lap(Phase::Parse);
     let errors: Vec<String> = Vec::new();
     let mut tabs: Vec<Tab> = Vec::new();
 
-    let outputs_silver = input_numbers.iter().map(|secret_number| {
+    let outputs_silver = input_numbers.iter().filter(|_| part.silver()).map(|secret_number| {
         evolve_repeatedly(*secret_number, 2000)
     }).collect::<Vec<_>>();
+    stopwatch.lap(Phase::Silver);
 
-    let sequence_to_profit_map: HashMap<Vec<i8>, u64> = generate_profit_map(&input_numbers);
+    let sequence_to_profit_map: HashMap<Vec<i8>, u64> = if part.gold() {
+        generate_profit_map(&input_numbers)
+    } else {
+        HashMap::new()
+    };
+    stopwatch.lap(Phase::Gold);
     let best_sequence = sequence_to_profit_map.iter().max_by_key(|(_sequence, profit)| **profit);
 
     tabs.push(Tab {
         title: "Inputs".to_string(),
         strings: input_numbers.iter().map(|item| format!("{}", item)).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Stepwise 123".to_string(),
         strings: evolve_stepwise(123, 10).iter().map(|item| format!("{}", item)).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
+    let mut outputs = Table::new(&[
+        ("Buyer", ColumnKind::Integer),
+        ("Initial secret", ColumnKind::Integer),
+        ("Secret after 2000", ColumnKind::Integer),
+    ]);
+    for (buyer, (initial, output)) in input_numbers.iter().zip(outputs_silver.iter()).enumerate() {
+        outputs.push(vec![buyer.into(), (*initial).into(), (*output).into()]);
+    }
     tabs.push(Tab {
         title: "Outputs".to_string(),
-        strings: outputs_silver.iter().map(|item| format!("{}", item)).collect(),
-        grid: vec![],
+        strings: vec![],
+        content: TabContent::Table(outputs),
     });
     tabs.push(Tab {
         title: "Best sequence".to_string(),
         strings: vec![best_sequence.map(|(seq, profit)|format!("Sequence: {:?}, profit: {}", seq, profit)).unwrap_or(String::new())],
-        grid: vec![],
+        content: TabContent::Strings,
     });
+    if part.gold() {
+        let profits = sequence_to_profit_map.values().map(|profit| *profit as f64).collect::<Vec<_>>();
+        tabs.push(Tab {
+            title: "Sequence profits".to_string(),
+            strings: vec![format!("{} sequences", profits.len())],
+            content: TabContent::Chart(Chart::histogram("Bananas".to_string(), &profits, 50)),
+        });
+    }
     DayOutput {
         silver_output: format!("{}", outputs_silver.into_iter().sum::<u64>()),
         gold_output: format!("{}", best_sequence.map(|(_seq, price)| *price).unwrap_or(0)),
@@ -103,8 +150,8 @@ fn get_price_change(last_price: u8, price: u8) -> i8 {
 fn evolve(secret_number: u64) -> u64 {
     let secret_number = prune(mix(secret_number, secret_number * 64));
     let secret_number = prune(mix(secret_number, secret_number / 32));
-    let secret_number = prune(mix(secret_number, secret_number * 2048));
-    secret_number
+    
+    prune(mix(secret_number, secret_number * 2048))
 }
 
 fn mix(secret_number: u64, value: u64) -> u64 {
@@ -1,10 +1,27 @@
 #![allow(unused_mut, unused_variables, dead_code)]
 
-use crate::app::{DayOutput, Diagnostic, Tab};
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab, TabContent};
+use crate::graph::Graph;
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub const TITLE: &str = "LAN Party";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day23.txt"),
+        silver: Some("7"),
+        gold: Some("co,de,ka,ta"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let errors: Vec<String> = Vec::new();
     let mut tabs: Vec<Tab> = Vec::new();
 
@@ -24,7 +41,7 @@ pub fn puzzle(input: &str) -> DayOutput {
     tabs.push(Tab {
         title: "Input parsed".to_string(),
         strings: pairs.iter().map(|a| format!("{:?}", a)).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     // Create a map of connections
     let mut string_connection_map = HashMap::<&str, Vec<&str>>::new();
@@ -42,25 +59,36 @@ pub fn puzzle(input: &str) -> DayOutput {
             next_id += 1;
             id
         });
-        let entry = string_connection_map.entry(left).or_insert(Vec::new());
+        let entry = string_connection_map.entry(left).or_default();
         entry.push(right);
-        let entry = string_connection_map.entry(right).or_insert(Vec::new());
+        let entry = string_connection_map.entry(right).or_default();
         entry.push(left);
 
-        let entry = connection_map.entry(left_id).or_insert(Vec::new());
+        let entry = connection_map.entry(left_id).or_default();
         entry.push(right_id);
         entry.sort();
-        let entry = connection_map.entry(right_id).or_insert(Vec::new());
+        let entry = connection_map.entry(right_id).or_default();
         entry.push(left_id);
         entry.sort();
     }
-    let historian_triplets = silver(&mut tabs, &string_connection_map);
+    stopwatch.lap(Phase::Parse);
+    let historian_triplets = if part.silver() {
+        silver(&mut tabs, &string_connection_map)
+    } else {
+        Vec::new()
+    };
+    stopwatch.lap(Phase::Silver);
 
-    let gold_result = gold(&connection_map);
+    let gold_result = if part.gold() {
+        gold(&connection_map)
+    } else {
+        Vec::new()
+    };
+    stopwatch.lap(Phase::Gold);
     tabs.push(Tab {
         title: "Gold result as numbers".to_string(),
         strings: gold_result.iter().map(|item| item.to_string()).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
 
     let reverse_lookup =
@@ -72,7 +100,19 @@ pub fn puzzle(input: &str) -> DayOutput {
     tabs.push(Tab {
         title: "Gold result".to_string(),
         strings: password_parts.iter().map(|item|item.to_string()).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
+    });
+    let mut network = Graph::new(false);
+    for (left, right) in pairs.iter() {
+        network.edge(left, right, String::new());
+    }
+    let historians = string_connection_map.keys().copied().filter(|name| name.starts_with('t')).collect::<Vec<_>>();
+    network.group("Starts with t".to_string(), historians);
+    network.group("Largest clique".to_string(), password_parts.iter().copied());
+    tabs.push(Tab {
+        title: "Network".to_string(),
+        strings: vec![],
+        content: TabContent::Graph(network),
     });
     let password = password_parts.into_iter().sorted().join(",");
 
@@ -95,8 +135,7 @@ fn gold(connection_map: &HashMap<u32, Vec<u32>>) -> Vec<u32> {
     }
     let result = suggestions
         .iter()
-        .max_by_key(|item| item.len())
-        .map(|item| item.clone())
+        .max_by_key(|item| item.len()).cloned()
         .unwrap_or(Vec::new());
     result
 }
@@ -124,7 +163,7 @@ fn gold_aux(
     for connection in connections {
         if !past_items.contains(connection) {
             let result = gold_aux(memoize, connection_map, *connection, &new_past_items);
-            if result.len() > 0 {
+            if !result.is_empty() {
                 suggestions.push(result);
             }
         }
@@ -136,11 +175,7 @@ fn gold_aux(
     } else {
         suggestions
             .iter()
-            .max_by_key(|item| item.len())
-            .map(|item| {
-                let mut item = item.clone();
-                item
-            })
+            .max_by_key(|item| item.len()).cloned()
             .unwrap_or(Vec::new())
     };
     memoize.insert((current, past_items.clone()), result.clone());
@@ -157,7 +192,7 @@ fn silver<'a>(
     // Order refers to which
     for (host, fo_connections) in connection_map.iter() {
         let host = *host;
-        for (fo_index, second) in fo_connections.into_iter().enumerate() {
+        for (fo_index, second) in fo_connections.iter().enumerate() {
             let second = *second;
             let so_connections = &connection_map[second];
             for third in so_connections {
@@ -200,7 +235,7 @@ fn silver<'a>(
             .sorted()
             .map(|triplet| format!("{:?}", triplet))
             .collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
 
     // Triplets starting with t
@@ -222,7 +257,7 @@ fn silver<'a>(
             .sorted()
             .map(|triplet| format!("{:?}", triplet))
             .collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     historian_triplets
 }
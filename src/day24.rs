@@ -1,11 +1,35 @@
-#![allow(unused_labels, dead_code, unused_mut)]
-use crate::app::{DayOutput, Diagnostic, Tab};
+#![allow(unused_labels, dead_code, unused_mut, clippy::type_complexity)]
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab, TabContent};
+use crate::graph::Graph;
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 use crate::common;
 use crate::common::combine_4;
 use itertools::Itertools;
 use regex::{Match, Regex};
 use std::collections::{HashMap, HashSet};
 
+pub const TITLE: &str = "Crossed Wires";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Small example",
+        input: include_str!("../res/examples/day24.txt"),
+        silver: Some("4"),
+        gold: None,
+        parameters: &[],
+    },
+    Example {
+        name: "Larger example",
+        input: include_str!("../res/examples/day24-2.txt"),
+        silver: Some("2024"),
+        gold: None,
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
 #[derive(Copy, Clone, Debug)]
 enum GateType {
     And,
@@ -19,7 +43,7 @@ enum Terminal<'a> {
     Gate(&'a str, GateType, &'a str),
 }
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let mut errors: Vec<String> = Vec::new();
     let mut tabs: Vec<Tab> = Vec::new();
     let mut input_split = input.split("\n\n");
@@ -111,12 +135,18 @@ pub fn puzzle(input: &str) -> DayOutput {
         ;
         mapping.insert(output, Terminal::Gate(first, *gate, second));
     }
+    stopwatch.lap(Phase::Parse);
 
     let re_input_x = Regex::new(r"x(?P<num>\d*)").expect("Should compile");
     let re_input_y = Regex::new(r"y(?P<num>\d*)").expect("Should compile");
-    let re_output = Regex::new(r"z(?P<num>\d*)").expect("Should compile");
+    let _re_output = Regex::new(r"z(?P<num>\d*)").expect("Should compile");
     let mut memoize = HashMap::<&str, bool>::new();
-    let silver = calculate_output(&mut errors, &mapping, &mut memoize);
+    let silver = if part.silver() {
+        calculate_output(&mut errors, &mapping, &mut memoize)
+    } else {
+        0
+    };
+    stopwatch.lap(Phase::Silver);
 
     // let plantuml_lines = create_plantuml(
     //     &mut errors,
@@ -170,12 +200,12 @@ pub fn puzzle(input: &str) -> DayOutput {
     tabs.push(Tab {
         title: "Influences".to_string(),
         strings: influences_tab,
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Input influences".to_string(),
         strings: input_influences_tab,
-        grid: vec![],
+        content: TabContent::Strings,
     });
 
     let mut tests = Vec::new();
@@ -198,47 +228,50 @@ pub fn puzzle(input: &str) -> DayOutput {
     let mut disruption_ranges: Vec<(u64, u64)> = Vec::new();
     let mut disruption_candidates: Vec<&str> = Vec::new();
     let mut reference_scores: HashMap<u64, i64> = HashMap::new();
-    for bits in 2..45_u64 {
-        let mut bit_ok = true;
-        let score = reference_scores.entry(bits).or_insert(0);
-        'x_loop: for x in 0..4 {
-            for y in 0..4 {
-                // Shift this to affect the current bit and the one below
-                let x = x << (bits - 2);
-                let y = y << (bits - 2);
-                // for x in 0..16 {
-                //     for y in 0..16 {
-                let expected = x + y;
-                let output = calculate_output_given_inputs(&mut errors, &mapping, x, y);
-                let comparison_bits = 45;
-                let correct_bits = get_correct_bits(expected, output, comparison_bits);
-                *score += correct_bits as i64;
-                if correct_bits != comparison_bits {
-                    if bit_ok {
-                        incremental_comparison.push(format!(
-                            "Bit {} not ok. x {x:b}, y {y:b}, expected {expected:b}, output {output:b}",
-                            bits
-                        ));
+    if part.gold() {
+        for bits in 2..45_u64 {
+            let mut bit_ok = true;
+            let score = reference_scores.entry(bits).or_insert(0);
+            'x_loop: for x in 0..4 {
+                for y in 0..4 {
+                    // Shift this to affect the current bit and the one below
+                    let x = x << (bits - 2);
+                    let y = y << (bits - 2);
+                    // for x in 0..16 {
+                    //     for y in 0..16 {
+                    let expected = x + y;
+                    let output = calculate_output_given_inputs(&mut errors, &mapping, x, y);
+                    let comparison_bits = 45;
+                    let correct_bits = get_correct_bits(expected, output, comparison_bits);
+                    *score += correct_bits as i64;
+                    if correct_bits != comparison_bits {
+                        if bit_ok {
+                            incremental_comparison.push(format!(
+                                "Bit {} not ok. x {x:b}, y {y:b}, expected {expected:b}, output {output:b}",
+                                bits
+                            ));
+                        }
+                        bit_ok = false;
                     }
-                    bit_ok = false;
+                    // incremental_comparison.push(format!("{} bits, {} + {} = {}, expected {}, {}/{} bits ok", bits, x, y, output, expected, correct_bits, bits));
                 }
-                // incremental_comparison.push(format!("{} bits, {} + {} = {}, expected {}, {}/{} bits ok", bits, x, y, output, expected, correct_bits, bits));
             }
-        }
-        if bit_ok {
-            if let Some(failing_bit) = last_failing_bit {
-                let diff = get_influence_diff(&mut errors, &mapping, last_ok_bit, failing_bit);
-                incremental_comparison.push(format!("{:?}", diff));
-                disruptions.push((last_ok_bit, failing_bit, diff.clone()));
-                disruption_ranges.push((last_ok_bit, failing_bit));
-                disruption_candidates.extend(diff);
-                last_failing_bit = None;
+            if bit_ok {
+                if let Some(failing_bit) = last_failing_bit {
+                    let diff = get_influence_diff(&mut errors, &mapping, last_ok_bit, failing_bit);
+                    incremental_comparison.push(format!("{:?}", diff));
+                    disruptions.push((last_ok_bit, failing_bit, diff.clone()));
+                    disruption_ranges.push((last_ok_bit, failing_bit));
+                    disruption_candidates.extend(diff);
+                    last_failing_bit = None;
+                }
+                last_ok_bit = bits;
+            } else {
+                last_failing_bit = Some(bits);
             }
-            last_ok_bit = bits;
-        } else {
-            last_failing_bit = Some(bits);
         }
     }
+    stopwatch.lap(Phase::Gold);
 
     let mut ok_swaps: Vec<(Vec<(&str, &str)>, u64, u64)> = Vec::new();
     let mut nok_swaps: Vec<(Vec<(&str, &str)>, u64, u64)> = Vec::new();
@@ -418,22 +451,22 @@ pub fn puzzle(input: &str) -> DayOutput {
     tabs.push(Tab {
         title: "Tests".to_string(),
         strings: tests,
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Incremental comparison".to_string(),
         strings: incremental_comparison,
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Ok swaps".to_string(),
         strings: ok_swaps.iter().map(|a| format!("{:?}", a)).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Nok swaps".to_string(),
         strings: nok_swaps.iter().map(|a| format!("{:?}", a)).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Reference scores".to_string(),
@@ -442,7 +475,7 @@ pub fn puzzle(input: &str) -> DayOutput {
             .sorted_by_key(|(bits, _)| **bits)
             .map(|(bits, score)| format!("{} {}", *bits, *score))
             .collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Score deltas".to_string(),
@@ -457,12 +490,35 @@ pub fn puzzle(input: &str) -> DayOutput {
                 format!("{}: {}", terminal_name, list)
             })
             .collect(),
-        grid: vec![],
+        content: TabContent::Strings,
+    });
+    let mut netlist = Graph::new(true);
+    for (output, terminal) in mapping.iter().sorted_by_key(|(output, _)| **output) {
+        match terminal {
+            Terminal::Bool(_) => {
+                netlist.node(output);
+            }
+            Terminal::Gate(first, gate, second) => {
+                let gate = format!("{:?}", gate).to_uppercase();
+                netlist.edge(first, output, gate.clone());
+                netlist.edge(second, output, gate);
+            }
+        }
+    }
+    let wires = netlist.nodes.iter().map(|node| node.label.clone()).collect::<Vec<_>>();
+    for (prefix, name) in [("x", "Inputs x"), ("y", "Inputs y"), ("z", "Outputs z")] {
+        netlist.group(name.to_string(), wires.iter().map(String::as_str).filter(|wire| wire.starts_with(prefix)));
+    }
+    netlist.group("Swapped".to_string(), manually_flipped_pairs.iter().flat_map(|(first, second)| [*first, *second]));
+    tabs.push(Tab {
+        title: "Netlist".to_string(),
+        strings: vec![],
+        content: TabContent::Graph(netlist),
     });
     tabs.push(Tab {
         title: "Terminals".to_string(),
         strings: terminals.iter().map(|item| format!("{:?}", item)).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Gates".to_string(),
@@ -470,7 +526,7 @@ pub fn puzzle(input: &str) -> DayOutput {
             .iter()
             .map(|item| format!("{:?}", item))
             .collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Memoize".to_string(),
@@ -479,7 +535,7 @@ pub fn puzzle(input: &str) -> DayOutput {
             .sorted_by_key(|item| item.0)
             .map(|item| format!("({:?}, {:?})", *item.0, if *item.1 { 1 } else { 0 }))
             .collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     // tabs.push(Tab {
     //     title: "Plantuml".to_string(),
@@ -489,7 +545,7 @@ pub fn puzzle(input: &str) -> DayOutput {
     tabs.push(Tab {
         title: "Errors".to_string(),
         strings: errors,
-        grid: vec![],
+        content: TabContent::Strings,
     });
 
     DayOutput {
@@ -502,7 +558,7 @@ pub fn puzzle(input: &str) -> DayOutput {
 fn find_swap_pairs_manual() -> Vec<Vec<(&'static str, &'static str)>> {
     // let disruption_candidates = vec!["trf", "tkq", "x34", "bwd", "hsv"];
     // let disruption_candidates = vec!["ghp", "trf", "nks", "jtg", "tkq", "bwd", "x34"];
-    let disruption_candidates = vec!["hmk", "fcd", "z16"];
+    let disruption_candidates = ["hmk", "fcd", "z16"];
     let mut swap_pairs = Vec::new();
     for (a_first_index, a_first) in disruption_candidates.iter().enumerate() {
         for a_second in disruption_candidates.iter().skip(a_first_index + 1) {
@@ -546,7 +602,7 @@ fn find_swap_pairs_counters<'a>(
     disruptions: &Vec<(u64, u64, Vec<&'a str>)>,
 ) -> Vec<Vec<(&'a str, &'a str)>> {
     let mut swap_pairs = Vec::new();
-    if let Some(collection_one) = &disruptions.get(0) {
+    if let Some(collection_one) = &disruptions.first() {
         let collection_one = &collection_one.2;
         if let Some(collection_two) = &disruptions.get(1) {
             let collection_two = &collection_two.2;
@@ -810,11 +866,11 @@ fn calculate_output<'a, 'b: 'a>(
     for terminal in mapping.keys() {
         if let Some(captures) = re_output.captures(terminal) {
             if let Some(bit) = common::capture_parse::<u32>(&captures, "num") {
-                let value = find_value(mapping, &mut memoize, terminal);
+                let value = find_value(mapping, memoize, terminal);
                 match value {
                     Ok(value) => {
                         let value = if value { 1u64 } else { 0u64 };
-                        result = result | (value << bit);
+                        result |= value << bit;
                     }
                     Err(error) => errors.push(error),
                 }
@@ -827,9 +883,9 @@ fn calculate_output<'a, 'b: 'a>(
 fn create_plantuml(
     errors: &mut Vec<String>,
     mapping: &HashMap<&str, Terminal>,
-    re_input_x: &Regex,
-    re_input_y: &Regex,
-    re_output: &Regex,
+    _re_input_x: &Regex,
+    _re_input_y: &Regex,
+    _re_output: &Regex,
     memoize: &mut HashMap<&str, bool>,
 ) -> Vec<String> {
     let mut plantuml_lines = Vec::new();
@@ -1,4 +1,20 @@
-use crate::app::{DayOutput, Diagnostic, Tab};
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab, TabContent};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
+
+pub const TITLE: &str = "Code Chronicle";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day25.txt"),
+        silver: Some("3"),
+        gold: None,
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
 
 type Shape = Vec<u8>;
 
@@ -10,7 +26,7 @@ enum Block {
     Error(String),
 }
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let errors: Vec<String> = Vec::new();
     let mut tabs: Vec<Tab> = Vec::new();
 
@@ -18,7 +34,7 @@ pub fn puzzle(input: &str) -> DayOutput {
         let mut split = block.split("\n");
         let first = split.next();
         let middle = split.clone().take(5).collect::<Vec<_>>();
-        let last = split.skip(5).next();
+        let last = split.nth(5);
         match (first, last) {
             (Some("#####"), Some(".....")) => {
                 parse_block(middle, Block::Lock)
@@ -44,38 +60,44 @@ pub fn puzzle(input: &str) -> DayOutput {
             Block::Error(_) => None,
         }
     }).collect::<Vec<_>>();
+    stopwatch.lap(Phase::Parse);
 
     let mut silver = 0;
     let overlaps = Vec::new();
-    for lock in locks.iter() {
-        for key in keys.iter() {
-            let compatible = check_compatible_overlap(lock, key);
-            if compatible {
-                silver += 1;
+    if part.silver() {
+        for lock in locks.iter() {
+            for key in keys.iter() {
+                let compatible = check_compatible_overlap(lock, key);
+                if compatible {
+                    silver += 1;
+                }
+                // overlaps.push(format!("{:?} + {:?} => {}", lock, key, compatible));
             }
-            // overlaps.push(format!("{:?} + {:?} => {}", lock, key, compatible));
         }
     }
+    stopwatch.lap(Phase::Silver);
+    // The last day has no gold puzzle, its star comes for free
+    stopwatch.lap(Phase::Gold);
 
     tabs.push(Tab {
         title: "Input".to_string(),
         strings: blocks.iter().map(|block| format!("{:?}", block)).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Locks".to_string(),
         strings: locks.iter().map(|block| format!("{:?}", block)).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Keys".to_string(),
         strings: keys.iter().map(|block| format!("{:?}", block)).collect(),
-        grid: vec![],
+        content: TabContent::Strings,
     });
     tabs.push(Tab {
         title: "Overlaps".to_string(),
         strings: overlaps,
-        grid: vec![],
+        content: TabContent::Strings,
     });
 
     DayOutput {
@@ -113,5 +135,5 @@ fn check_compatible_overlap(lock: &Shape, key: &Shape) -> bool {
             return false;
         }
     }
-    return true;
+    true
 }
\ No newline at end of file
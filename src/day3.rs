@@ -1,8 +1,31 @@
 use regex::{Match, Regex};
-use crate::app::{DayOutput, Diagnostic};
+use crate::app::{DayOutput, Diagnostic, Example, Part};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 
+pub const TITLE: &str = "Mull It Over";
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day3.txt"),
+        silver: Some("161"),
+        gold: None,
+        parameters: &[],
+    },
+    Example {
+        name: "Gold example",
+        input: include_str!("../res/examples/day3-2.txt"),
+        silver: None,
+        gold: Some("48"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let re = Regex::new(r"(?P<mul>mul\((?P<first>\d*),(?P<second>\d*)\))|(?P<dont>don't\(\))|(?P<do>do\(\))").unwrap();
 
     let mut capture_summary = Vec::new();
@@ -15,13 +38,13 @@ pub fn puzzle(input: &str) -> DayOutput {
                 captures.name("first"),
                 captures.name("second"),
             ));
-            if let Some(_) = captures.name("mul") {
+            if captures.name("mul").is_some() {
                 log::info!("mul");
-                double_parse(captures.name("first"), captures.name("second")).map(|pair| Action::Multiply(pair))
-            } else if let Some(_) = captures.name("dont") {
+                double_parse(captures.name("first"), captures.name("second")).map(Action::Multiply)
+            } else if captures.name("dont").is_some() {
                 log::info!("dont");
                 Some(Action::Dont)
-            } else if let Some(_) = captures.name("do") {
+            } else if captures.name("do").is_some() {
                 log::info!("do");
                 Some(Action::Do)
             } else {
@@ -29,35 +52,46 @@ pub fn puzzle(input: &str) -> DayOutput {
             }
         })
         .collect::<Vec<Action>>();
+    stopwatch.lap(Phase::Parse);
 
-    let silver_sum = actions.iter()
-        .filter_map(|action| {
-            match action {
-                Action::Multiply((first, second)) => Some(first * second),
-                _ => None,
-            }
-        })
-        .sum::<u64>();
-
-    let (_, gold_sum) = actions.iter()
-        .fold((true, 0), |(active, sum), action| {
-            match action {
-                Action::Multiply((first, second)) => {
-                    let sum = if active {
-                        sum + (*first * *second)
-                    } else {
-                        sum
-                    };
-                    (active, sum)
-                }
-                Action::Do => {
-                    (true, sum)
+    let silver_sum = if part.silver() {
+        actions.iter()
+            .filter_map(|action| {
+                match action {
+                    Action::Multiply((first, second)) => Some(first * second),
+                    _ => None,
                 }
-                Action::Dont => {
-                    (false, sum)
+            })
+            .sum::<u64>()
+    } else {
+        0
+    };
+    stopwatch.lap(Phase::Silver);
+
+    let (_, gold_sum) = if part.gold() {
+        actions.iter()
+            .fold((true, 0), |(active, sum), action| {
+                match action {
+                    Action::Multiply((first, second)) => {
+                        let sum = if active {
+                            sum + (*first * *second)
+                        } else {
+                            sum
+                        };
+                        (active, sum)
+                    }
+                    Action::Do => {
+                        (true, sum)
+                    }
+                    Action::Dont => {
+                        (false, sum)
+                    }
                 }
-            }
-        });
+            })
+    } else {
+        (true, 0)
+    };
+    stopwatch.lap(Phase::Gold);
     DayOutput {
         silver_output: format!("{}", silver_sum),
         gold_output: format!("{}", gold_sum),
@@ -1,6 +1,23 @@
-use crate::app::{DayOutput, Diagnostic};
+use crate::app::{DayOutput, Diagnostic, DiagnosticEntry, Example, Part};
+use crate::grid::{Coord, Direction, Grid};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 
-#[derive(Clone, Debug)]
+pub const TITLE: &str = "Ceres Search";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day4.txt"),
+        silver: Some("18"),
+        gold: Some("9"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum Letter {
     X,
     M,
@@ -8,130 +25,65 @@ enum Letter {
     S,
 }
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let mut errors: Vec<String> = Vec::new();
-    let grid = input.split("\n").map(|line| {
-        log::info!("{:?}", line);
-        line.chars().filter_map(|character| {
-            log::info!("{:?}", character);
-            match character {
-                'X' => Some(Letter::X),
-                'M' => Some(Letter::M),
-                'A' => Some(Letter::A),
-                'S' => Some(Letter::S),
-                c => {
-                    errors.push(format!("Found invalid character in input: {}", c));
-                    None
-                }
+    let mut entries = Vec::new();
+    let grid = Grid::parse_with_index(input, |character, x, y| {
+        match character {
+            'X' => Some(Letter::X),
+            'M' => Some(Letter::M),
+            'A' => Some(Letter::A),
+            'S' => Some(Letter::S),
+            c => {
+                entries.push(DiagnosticEntry::error(format!("Found invalid character '{}'", c)).at(y as usize, x as usize, 1));
+                None
             }
-        }).collect::<Vec<_>>()
-    }).collect::<Vec<_>>();
+        }
+    }).unwrap_or_else(|error| {
+        entries.push(DiagnosticEntry::error(error));
+        Grid::default()
+    });
+    let letter_at = |coord: Coord| grid.get(coord).copied().flatten();
+    stopwatch.lap(Phase::Parse);
 
     let mut num_found = 0;
-    for y in 0..grid.len() {
-        if let Some(row) = grid.get(y) {
-            for x in 0..(*row).len() {
-                let coord = (x as i32, y as i32);
-                if let Some(Letter::X) = find_coord(&grid, coord) {
-                    for dir in [
-                        (-1, -1),
-                        (-1, 0),
-                        (-1, 1),
-                        (0, -1),
-                        (0, 1),
-                        (1, -1),
-                        (1, 0),
-                        (1, 1),
-                    ] {
-                        let next_coord = add_coord(coord, dir);
-                        if let Some(Letter::M) = find_coord(&grid, next_coord) {
-                            let next_coord = add_coord(next_coord, dir);
-                            if let Some(Letter::A) = find_coord(&grid, next_coord) {
-                                let next_coord = add_coord(next_coord, dir);
-                                if let Some(Letter::S) = find_coord(&grid, next_coord) {
-                                    num_found += 1;
-                                    errors.push(format!("Found in direction {:?} starting at {:?} ending at {:?}", dir, coord, next_coord));
-                                }
-                            }
-                        }
-                    }
+    if part.silver() {
+        for (coord, letter) in grid.iter() {
+            if *letter != Some(Letter::X) {
+                continue;
+            }
+            for direction in Direction::ALL {
+                let rest = [Letter::M, Letter::A, Letter::S];
+                let found = rest.iter().zip(1..).all(|(expected, steps)| {
+                    letter_at(coord + direction.offset() * steps) == Some(*expected)
+                });
+                if found {
+                    num_found += 1;
+                    errors.push(format!("Found in direction {} starting at {} ending at {}", direction, coord, coord + direction.offset() * 3));
                 }
             }
         }
     }
+    stopwatch.lap(Phase::Silver);
 
     let mut num_found_gold = 0;
-    for y in 0..grid.len() {
-        if let Some(row) = grid.get(y) {
-            for x in 0..(*row).len() {
-                let coord = (x as i32, y as i32);
-                if let Some(Letter::A) = find_coord(&grid, coord) {
-                    if let Some(down_right) = find_coord(&grid, add_coord(coord, (1, 1))) {
-                        match down_right {
-                            Letter::M => {
-                                if let Some(Letter::S) = find_coord(&grid, add_coord(coord, (-1, -1))) {
-                                } else {
-                                    continue
-                                }
-                            }
-                            Letter::S => {
-                                if let Some(Letter::M) = find_coord(&grid, add_coord(coord, (-1, -1))) {
-                                } else {
-                                    continue
-                                }
-                            }
-                            _ => {
-                                continue
-                            }
-                        }
-                    } else {
-                        continue
-                    }
-                    if let Some(down_right) = find_coord(&grid, add_coord(coord, (1, -1))) {
-                        match down_right {
-                            Letter::M => {
-                                if let Some(Letter::S) = find_coord(&grid, add_coord(coord, (-1, 1))) {
-                                } else {
-                                    continue
-                                }
-                            }
-                            Letter::S => {
-                                if let Some(Letter::M) = find_coord(&grid, add_coord(coord, (-1, 1))) {
-                                } else {
-                                    continue
-                                }
-                            }
-                            _ => {
-                                continue
-                            }
-                        }
-                    } else {
-                        continue
-                    }
-                    num_found_gold += 1;
-                }
+    if part.gold() {
+        // Both diagonals through the A have to read MAS one way or the other
+        let is_mas = |coord: Coord, direction: Direction| {
+            let ends = (letter_at(coord + direction.offset()), letter_at(coord + direction.opposite().offset()));
+            matches!(ends, (Some(Letter::M), Some(Letter::S)) | (Some(Letter::S), Some(Letter::M)))
+        };
+        for (coord, letter) in grid.iter() {
+            if *letter == Some(Letter::A) && is_mas(coord, Direction::DownRight) && is_mas(coord, Direction::UpRight) {
+                num_found_gold += 1;
             }
         }
     }
+    stopwatch.lap(Phase::Gold);
 
     DayOutput {
         silver_output: format!("{}", num_found),
         gold_output: format!("{}", num_found_gold),
-        diagnostic: Diagnostic::simple(format!("errors: {:?}, grid: {:?}", errors, grid.get(1))),
+        diagnostic: Diagnostic::simple(format!("errors: {:?}, grid: {:?}", errors, grid.row(1))).with_entries(entries),
     }
 }
-
-fn find_coord(grid: &Vec<Vec<Letter>>, coord: (i32, i32)) -> Option<Letter> {
-    let (x, y): (Option<usize>, Option<usize>) = (coord.0.try_into().ok(), coord.1.try_into().ok());
-    if let (Some(x), Some(y)) = (x, y) {
-        grid.get(y).map(|row| {
-            row.get(x).map(|letter| letter.clone())
-        }).unwrap_or(None)
-    } else {
-        None
-    }
-}
-
-fn add_coord(first: (i32, i32), second: (i32, i32)) -> (i32, i32) {
-    (first.0 + second.0, first.1 + second.1)
-}
\ No newline at end of file
@@ -1,6 +1,23 @@
-use crate::app::{DayOutput, Diagnostic};
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab, TabContent};
+use crate::graph::Graph;
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub const TITLE: &str = "Print Queue";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day5.txt"),
+        silver: Some("143"),
+        gold: Some("123"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let mut split = input.split("\n\n");
     if let (Some(input_pairs), Some(input_updates)) = (split.next(), split.next()) {
         // let re_pairs = Regex::new(r"(\d*)|(\d*)").unwrap();
@@ -24,6 +41,7 @@ pub fn puzzle(input: &str) -> DayOutput {
                     .collect::<Vec<u64>>()
             })
             .collect::<Vec<Vec<u64>>>();
+        stopwatch.lap(Phase::Parse);
 
 
         let mut sum_of_correct_middle_pages = 0;
@@ -41,11 +59,12 @@ pub fn puzzle(input: &str) -> DayOutput {
                     }
                 }
             }
+            stopwatch.lap(Phase::Silver);
             if ok {
                 if let Some(page) = update.get( update.len() / 2 ) {
                     sum_of_correct_middle_pages += page;
                 }
-            } else {
+            } else if part.gold() {
                 let mut update = update.clone();
                 let mut rule_pairs = rule_pairs.clone();
 
@@ -77,12 +96,13 @@ pub fn puzzle(input: &str) -> DayOutput {
                     sum_of_incorrect_middle_pages += page;
                 }
             }
+            stopwatch.lap(Phase::Gold);
         }
 
         DayOutput {
             silver_output: format!("{}", sum_of_correct_middle_pages),
             gold_output: format!("{}", sum_of_incorrect_middle_pages),
-            diagnostic: Diagnostic::simple(format!("{:?}", errors)),
+            diagnostic: Diagnostic::with_tabs(vec![rules_tab(&rule_pairs)], format!("{:?}", errors)),
         }
     } else {
         DayOutput {
@@ -93,6 +113,19 @@ pub fn puzzle(input: &str) -> DayOutput {
     }
 }
 
+/// Every rule as an edge from the page that goes first to the page that goes after it.
+fn rules_tab(rule_pairs: &[(u64, u64)]) -> Tab {
+    let mut rules = Graph::new(true);
+    for (first, second) in rule_pairs.iter() {
+        rules.edge(&first.to_string(), &second.to_string(), String::new());
+    }
+    Tab {
+        title: "Ordering rules".to_string(),
+        strings: vec![],
+        content: TabContent::Graph(rules),
+    }
+}
+
 pub fn double_parse(first: Option<&str>, second: Option<&str>) -> Option<(u64, u64)> {
     match (
         first.map(|item| item.parse::<u64>()),
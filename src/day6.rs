@@ -1,8 +1,23 @@
 use std::collections::HashSet;
-use std::ops::Deref;
-use crate::app::{DayOutput, Diagnostic, GridCell, Tab};
+use crate::app::{DayOutput, Diagnostic, DiagnosticEntry, Example, GridCell, Part, Tab, TabContent};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 use crate::grid::{Coord, Grid};
 
+pub const TITLE: &str = "Guard Gallivant";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day6.txt"),
+        silver: Some("41"),
+        gold: Some("6"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
 #[derive(Clone, Debug)]
 pub enum Letter {
     Dot,
@@ -10,54 +25,64 @@ pub enum Letter {
     Guard,
 }
 
-pub fn puzzle(input: &str) -> DayOutput {
-    let mut errors: Vec<String> = Vec::new();
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let mut starting_position = None;
     let mut tabs = Vec::new();
-    let mut grid = Grid::from_with_index_filtered(input, |character, x, y| {
+    let mut entries = Vec::new();
+    let mut grid = Grid::parse_with_index_filtered(input, |character, x, y| {
         match character {
             '.' => Some(Letter::Dot),
             '#' => Some(Letter::Hash),
             '^' => {
-                starting_position = Some(Coord::new(x as i32, y as i32));
+                starting_position = Some(Coord::new(x, y));
                 Some(Letter::Guard)
             }
             c => {
-                errors.push(format!("Found invalid character in input: {}", c));
+                entries.push(DiagnosticEntry::error(format!("Found invalid character '{}'", c)).at(y as usize, x as usize, 1));
                 None
             }
         }
+    }).unwrap_or_else(|error| {
+        entries.push(DiagnosticEntry::error(error));
+        // There is no grid for the guard to walk
+        starting_position = None;
+        Grid::default()
     });
+    stopwatch.lap(Phase::Parse);
 
     let (ordinary_visited, blockage_locations) = if let Some(starting_position) = starting_position {
         let starting_dir = Coord::new(0, -1);
         let (_looped, ordinary_visited_set) = find_visited(&grid, starting_position, starting_dir);
         let mut ordinary_visited = ordinary_visited_set.into_iter().collect::<Vec<_>>();
         ordinary_visited.sort();
+        stopwatch.lap(Phase::Silver);
 
         let mut blockage_locations: HashSet<Coord> = HashSet::new();
-        for (blockage_index, blockage_location) in ordinary_visited.iter().enumerate() {
-            let blockage_location: Coord = blockage_location.clone();
-            if let Some(handle) = grid.get_mut(blockage_location) {
-                let original = handle.clone();
-                *handle = Letter::Hash;
+        if part.gold() {
+            for (blockage_index, blockage_location) in ordinary_visited.iter().enumerate() {
+                let blockage_location: Coord = *blockage_location;
+                if let Some(handle) = grid.get_mut(blockage_location) {
+                    let original = handle.clone();
+                    *handle = Letter::Hash;
 
-                let (looped, visited) = find_visited(&grid, starting_position, starting_dir);
+                    let (looped, visited) = find_visited(&grid, starting_position, starting_dir);
 
-                if looped {
-                    blockage_locations.insert(blockage_location);
-                    if tabs.len() < 100 {
-                        add_tab(&mut tabs, &grid, &visited, format!("B{}", blockage_index));
+                    if looped {
+                        blockage_locations.insert(blockage_location);
+                        if tabs.len() < 100 {
+                            add_tab(&mut tabs, &grid, &visited, format!("B{}", blockage_index));
+                        }
                     }
-                }
 
-                if let Some(handle) = grid.get_mut(blockage_location) {
-                    *handle = original;
-                } else {
-                    errors.push(format!("Couldn't reset coord {:?}", blockage_location));
+                    if let Some(handle) = grid.get_mut(blockage_location) {
+                        *handle = original;
+                    } else {
+                        entries.push(DiagnosticEntry::error(format!("Couldn't reset coord {:?}", blockage_location)));
+                    }
                 }
             }
         }
+        stopwatch.lap(Phase::Gold);
         (ordinary_visited, blockage_locations)
     } else {
         (Vec::new(), HashSet::new())
@@ -67,7 +92,7 @@ pub fn puzzle(input: &str) -> DayOutput {
     DayOutput {
         silver_output: format!("{}", ordinary_visited.len()),
         gold_output: format!("{}", blockage_locations.len()),
-        diagnostic: Diagnostic::with_tabs(tabs, format!("errors: {:?}, starting_position: {:?}", errors, starting_position)),
+        diagnostic: Diagnostic::with_tabs(tabs, format!("starting_position: {:?}", starting_position)).with_entries(entries),
     }
 }
 
@@ -94,7 +119,7 @@ fn add_tab(tabs: &mut Vec<Tab>, grid: &Grid<Letter>, visited: &HashSet<Coord>, t
     let tab = Tab {
         title,
         strings: vec![],
-        grid,
+        content: TabContent::Grid(grid),
     };
     tabs.push(tab);
 }
@@ -102,8 +127,8 @@ fn add_tab(tabs: &mut Vec<Tab>, grid: &Grid<Letter>, visited: &HashSet<Coord>, t
 fn find_visited(grid: &Grid<Letter>, starting_position: Coord, starting_dir: Coord) -> (bool, HashSet<Coord>) {
     let mut visited_locations = HashSet::new();
     let mut visited_location_directions = HashSet::new();
-    let mut current_position = starting_position.clone();
-    let mut current_dir = starting_dir.clone();
+    let mut current_position = starting_position;
+    let mut current_dir = starting_dir;
     let looped = loop {
         let location_direction = (current_position, current_dir);
         if visited_location_directions.contains(&location_direction) {
@@ -111,7 +136,7 @@ fn find_visited(grid: &Grid<Letter>, starting_position: Coord, starting_dir: Coo
         }
         visited_location_directions.insert(location_direction);
         visited_locations.insert(current_position);
-        let next_position = current_position.add(&current_dir);
+        let next_position = current_position + current_dir;
         let next_tile = grid.get(next_position);
         match next_tile {
             None => {
@@ -125,7 +150,7 @@ fn find_visited(grid: &Grid<Letter>, starting_position: Coord, starting_dir: Coo
                         current_position = next_position;
                     }
                     Letter::Hash => {
-                        current_dir = Coord::new(-current_dir.deref().1, current_dir.deref().0);
+                        current_dir = current_dir.rotate_right();
                     }
                 }
             }
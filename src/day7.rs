@@ -1,5 +1,22 @@
 use std::fmt::{Display, Formatter};
-use crate::app::{DayOutput, Diagnostic, Tab};
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab, TabContent};
+use crate::table::{ColumnKind, Table};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
+
+pub const TITLE: &str = "Bridge Repair";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day7.txt"),
+        silver: Some("3749"),
+        gold: Some("11387"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
 
 enum Operator {
     Add,
@@ -7,15 +24,15 @@ enum Operator {
     Concat,
 }
 
-pub fn puzzle(input: &str) -> DayOutput {
-    let rows = input.split("\n").into_iter().filter_map(|line| {
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
+    let rows = input.split("\n").filter_map(|line| {
         let mut split = line.split(": ");
         if let (Some(output), Some(rest)) = (split.next(), split.next()) {
-            let values = rest.split(" ").into_iter().filter_map(|text| {
+            let values = rest.split(" ").filter_map(|text| {
                 text.parse::<u64>().ok()
             }).collect::<Vec<u64>>();
             if let Some((first, rest)) = values.split_first() {
-                if let Some(target) = output.parse::<u64>().ok() {
+                if let Ok(target) = output.parse::<u64>() {
                     Some((target, *first, rest.to_vec()))
                 } else {
                     None
@@ -27,28 +44,35 @@ pub fn puzzle(input: &str) -> DayOutput {
             None
         }
     }).collect::<Vec<(u64, u64, Vec<u64>)>>();
+    stopwatch.lap(Phase::Parse);
 
     let mut sum_of_silver = 0;
     let mut sum_of_gold = 0;
     let silver_operator_list = [Operator::Add, Operator::Multiply];
     let gold_operator_list = [Operator::Add, Operator::Multiply, Operator::Concat];
-    let mut silver_solutions = Vec::new();
-    let mut gold_solutions  = Vec::new();
+    let mut silver_solutions = solutions_table();
+    let mut gold_solutions  = solutions_table();
     for (target, first, rest) in rows {
-        let solution = check(first, &rest, target, &silver_operator_list);
-        if let Some(solution) = solution {
-            add_solution(&mut silver_solutions, target, first, solution);
-            sum_of_silver += target;
-        } else {
-            add_lack_of_solution(&mut silver_solutions, target, first, &rest);
+        if part.silver() {
+            let solution = check(first, &rest, target, &silver_operator_list);
+            if let Some(solution) = solution {
+                add_solution(&mut silver_solutions, target, first, solution);
+                sum_of_silver += target;
+            } else {
+                add_lack_of_solution(&mut silver_solutions, target, first, &rest);
+            }
         }
-        let solution = check(first, &rest, target, &gold_operator_list);
-        if let Some(solution) = solution {
-            add_solution(&mut gold_solutions, target, first, solution);
-            sum_of_gold += target;
-        } else {
-            add_lack_of_solution(&mut gold_solutions, target, first, &rest);
+        stopwatch.lap(Phase::Silver);
+        if part.gold() {
+            let solution = check(first, &rest, target, &gold_operator_list);
+            if let Some(solution) = solution {
+                add_solution(&mut gold_solutions, target, first, solution);
+                sum_of_gold += target;
+            } else {
+                add_lack_of_solution(&mut gold_solutions, target, first, &rest);
+            }
         }
+        stopwatch.lap(Phase::Gold);
     }
 
     let mut tabs = Vec::new();
@@ -58,7 +82,7 @@ pub fn puzzle(input: &str) -> DayOutput {
     DayOutput {
         silver_output: format!("{}", sum_of_silver),
         gold_output: format!("{}", sum_of_gold),
-        diagnostic: Diagnostic::with_tabs(tabs, format!("")),
+        diagnostic: Diagnostic::with_tabs(tabs, String::new()),
     }
 }
 
@@ -76,12 +100,9 @@ fn check<'a>(accumulator: u64, values: &[u64], target: u64, operator_list: &'a [
             for operator in operator_list {
                 if let Some(accumulator) = operator.apply(accumulator, *first) {
                     let solution = check(accumulator, rest, target, operator_list);
-                    match solution {
-                        Some(mut solution) => {
-                            solution.push((operator, *first));
-                            return Some(solution)
-                        }
-                        None => {}
+                    if let Some(mut solution) = solution {
+                        solution.push((operator, *first));
+                        return Some(solution)
                     }
                 }
             }
@@ -95,7 +116,7 @@ impl Operator {
             Operator::Add => {Some(one + other)}
             Operator::Multiply => {Some(one * other)}
             Operator::Concat => {
-                format!("{}{}", one.to_string(), other.to_string()).parse::<u64>().ok()
+                format!("{}{}", one, other).parse::<u64>().ok()
             }
         }
     }
@@ -109,25 +130,33 @@ impl Display for Operator {
         }
     }
 }
-fn add_solution(solutions: &mut Vec<String>, target: u64, first: u64, solution: Vec<(&Operator, u64)>) {
-    let mut string = format!("{}: {}", target, first);
-    for (operator, number) in solution {
+fn solutions_table() -> Table {
+    Table::new(&[
+        ("Target", ColumnKind::Integer),
+        ("Solved", ColumnKind::Text),
+        ("Equation", ColumnKind::Text),
+    ])
+}
+fn add_solution(solutions: &mut Table, target: u64, first: u64, solution: Vec<(&Operator, u64)>) {
+    let mut string = format!("{}", first);
+    // check builds the solution from the last operator back
+    for (operator, number) in solution.into_iter().rev() {
         string = format!("{}{}{}", string, operator, number);
     }
-    solutions.push(string);
+    solutions.push(vec![target.into(), "yes".into(), string.into()]);
 }
-fn add_lack_of_solution(solutions: &mut Vec<String>, target: u64, first: u64, solution: &[u64]) {
-    let mut string = format!("! {}: {}", target, first);
+fn add_lack_of_solution(solutions: &mut Table, target: u64, first: u64, solution: &[u64]) {
+    let mut string = format!("{}", first);
     for number in solution {
         string = format!("{} {}", string, number);
     }
-    solutions.push(string);
+    solutions.push(vec![target.into(), "no".into(), string.into()]);
 }
 
-fn add_tab(tabs: &mut Vec<Tab>, title: &str, solutions: Vec<String>) {
+fn add_tab(tabs: &mut Vec<Tab>, title: &str, solutions: Table) {
     tabs.push(Tab {
         title: title.to_string(),
-        strings: solutions,
-        grid: vec![],
+        strings: vec![],
+        content: TabContent::Table(solutions),
     })
 }
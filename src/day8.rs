@@ -1,31 +1,58 @@
 use std::collections::HashMap;
-use crate::app::{DayOutput, Diagnostic, Tab};
+use crate::app::{DayOutput, Diagnostic, DiagnosticEntry, Example, Part, Tab, TabContent};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 use crate::grid::{Coord, Grid};
 
+pub const TITLE: &str = "Resonant Collinearity";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day8.txt"),
+        silver: Some("14"),
+        gold: Some("34"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
 const ANTIPOLE_CHAR: char = '#';
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let mut antenna_lists: HashMap<char, Vec<Coord>> = HashMap::new();
-    let input_grid = Grid::from_with_index(input, |character, x, y| {
+    let mut entries = Vec::new();
+    let input_grid = Grid::parse_with_index(input, |character, x, y| {
         match character {
             '.' => {}
             antenna => {
-                let entry = antenna_lists.entry(antenna).or_insert(Vec::new());
-                entry.push(Coord::new(x as i32, y as i32));
+                let entry = antenna_lists.entry(antenna).or_default();
+                entry.push(Coord::new(x, y));
             }
         }
         character
+    }).unwrap_or_else(|error| {
+        entries.push(DiagnosticEntry::error(error));
+        Grid::default()
     });
+    stopwatch.lap(Phase::Parse);
 
     let mut grid = input_grid.clone();
     let mut grid_repeating = input_grid.clone();
     for (_, antenna_list) in antenna_lists.iter() {
         for (index, antenna_one) in antenna_list.iter().enumerate() {
             for antenna_two in antenna_list[index + 1..].iter() {
-                add_antipole(&mut grid, antenna_one, antenna_two);
-                add_antipole(&mut grid, antenna_two, antenna_one);
-                add_antipole_repeating(&mut grid_repeating, antenna_one, antenna_two);
-                add_antipole_repeating(&mut grid_repeating, antenna_two, antenna_one);
+                if part.silver() {
+                    add_antipole(&mut grid, antenna_one, antenna_two);
+                    add_antipole(&mut grid, antenna_two, antenna_one);
+                }
+                stopwatch.lap(Phase::Silver);
+                if part.gold() {
+                    add_antipole_repeating(&mut grid_repeating, antenna_one, antenna_two);
+                    add_antipole_repeating(&mut grid_repeating, antenna_two, antenna_one);
+                }
+                stopwatch.lap(Phase::Gold);
             }
         }
     }
@@ -37,40 +64,39 @@ pub fn puzzle(input: &str) -> DayOutput {
         Tab {
             title: "Input".to_string(),
             strings: vec![],
-            grid: input_grid.to_tab_grid(),
+            content: TabContent::Grid(input_grid.to_tab_grid()),
         },
         Tab {
             title: "Output".to_string(),
             strings: vec![],
-            grid: grid.to_tab_grid(),
+            content: TabContent::Grid(grid.to_tab_grid()),
         },
         Tab {
             title: "Output repeating".to_string(),
             strings: vec![],
-            grid: grid_repeating.to_tab_grid(),
+            content: TabContent::Grid(grid_repeating.to_tab_grid()),
         },
     ];
     DayOutput {
         silver_output: format!("{}", sum),
         gold_output: format!("{}", sum_repeating),
-        diagnostic: Diagnostic::with_tabs(tabs, format!("")),
+        diagnostic: Diagnostic::with_tabs(tabs, String::new()).with_entries(entries),
     }
 }
 
 fn add_antipole(grid: &mut Grid<char>, antenna_one: &Coord, antenna_two: &Coord) {
-    let difference = antenna_one.subtract(antenna_two);
-    let antipole = antenna_one.add(&difference);
+    let difference = *antenna_one - *antenna_two;
+    let antipole = *antenna_one + difference;
     if let Some(cell) = grid.get_mut(antipole) {
         *cell = ANTIPOLE_CHAR;
     }
 }
 
 fn add_antipole_repeating(grid: &mut Grid<char>, antenna_one: &Coord, antenna_two: &Coord) {
-    let difference = antenna_one.subtract(antenna_two);
+    let difference = *antenna_one - *antenna_two;
     let mut scalar = 0;
     loop {
-        let current_difference = difference.multiply(scalar);
-        let antipole = antenna_one.add(&current_difference);
+        let antipole = *antenna_one + difference * scalar;
         if let Some(cell) = grid.get_mut(antipole) {
             *cell = ANTIPOLE_CHAR;
         } else {
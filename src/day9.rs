@@ -1,8 +1,24 @@
 use std::collections::HashSet;
-use crate::app::{DayOutput, Diagnostic, Tab};
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab, TabContent};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 use crate::grid::Grid;
 
-pub fn puzzle(input: &str) -> DayOutput {
+pub const TITLE: &str = "Disk Fragmenter";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Example",
+        input: include_str!("../res/examples/day9.txt"),
+        silver: Some("1928"),
+        gold: Some("2858"),
+        parameters: &[],
+    },
+];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+pub fn puzzle(input: &str, part: Part, _parameters: &Parameters, stopwatch: &mut Stopwatch) -> DayOutput {
     let mut tabs = Vec::new();
     let mut grid = Grid::new();
     fn cell_function(cell: &Option<u64>) -> String {
@@ -11,18 +27,30 @@ pub fn puzzle(input: &str) -> DayOutput {
             Some(num) => format!("{}", num),
         }
     }
-    let (initial_disk_spaces, disk_spaces, _errors) = puzzle_silver(input);
-    grid.add_row_from(&initial_disk_spaces, cell_function);
-    grid.add_row_from(&disk_spaces, cell_function);
-    let silver_checksum = calculate_checksum(&disk_spaces);
-    let (initial_disk_spaces, disk_spaces, errors) = puzzle_gold(input);
-    grid.add_row_from(&initial_disk_spaces, cell_function);
-    grid.add_row_from(&disk_spaces, cell_function);
-    let gold_checksum = calculate_checksum(&disk_spaces);
+    let mut silver_checksum = 0;
+    let mut gold_checksum = 0;
+    let mut errors = Vec::new();
+    let mut disk_spaces = Vec::new();
+    if part.silver() {
+        let (initial_disk_spaces, silver_disk_spaces, silver_errors) = puzzle_silver(input);
+        add_rows(&mut grid, &initial_disk_spaces, &silver_disk_spaces, cell_function);
+        silver_checksum = calculate_checksum(&silver_disk_spaces);
+        errors = silver_errors;
+        disk_spaces = silver_disk_spaces;
+    }
+    stopwatch.lap(Phase::Silver);
+    if part.gold() {
+        let (initial_disk_spaces, gold_disk_spaces, gold_errors) = puzzle_gold(input);
+        add_rows(&mut grid, &initial_disk_spaces, &gold_disk_spaces, cell_function);
+        gold_checksum = calculate_checksum(&gold_disk_spaces);
+        errors = gold_errors;
+        disk_spaces = gold_disk_spaces;
+    }
+    stopwatch.lap(Phase::Gold);
     tabs.push(Tab {
         title: "Tab".to_string(),
         strings: vec![],
-        grid: grid.to_tab_grid(),
+        content: TabContent::Grid(grid.to_tab_grid()),
     });
     DayOutput {
         silver_output: format!("{}", silver_checksum),
@@ -31,12 +59,22 @@ pub fn puzzle(input: &str) -> DayOutput {
     }
 }
 
+/// Adds the disk before and after compacting, with free space after the compacted disk so the rows are as wide.
+fn add_rows(grid: &mut Grid<String>, initial: &[Option<u64>], compacted: &[Option<u64>], cell_function: fn(&Option<u64>) -> String) {
+    let width = initial.len().max(compacted.len());
+    for row in [initial, compacted] {
+        let mut row = row.to_vec();
+        row.resize(width, None);
+        grid.add_row_from(&row, cell_function);
+    }
+}
+
 fn puzzle_silver(input: &str) -> (Vec<Option<u64>>, Vec<Option<u64>>, Vec<String>) {
     let mut disk_spaces: Vec<Option<u64>> = Vec::new();
     let mut next_is_file = true;
     let mut errors = Vec::new();
     let mut file_id = 0;
-    for character in input.chars().into_iter() {
+    for character in input.chars() {
         let num_slots = character.to_string().parse::<u64>();
         match num_slots {
             Err(parse_error) => {
@@ -62,10 +100,7 @@ fn puzzle_silver(input: &str) -> (Vec<Option<u64>>, Vec<Option<u64>>, Vec<String
 
     let mut slots_to_move = disk_spaces.iter()
         .enumerate()
-        .filter_map(|(index, id)| match id {
-            None => None,
-            Some(id) => Some((index, *id)),
-        })
+        .filter_map(|(index, id)| id.as_ref().map(|id| (index, *id)))
         .collect::<Vec<(usize, u64)>>();
 
     for (index, disk_space) in disk_spaces.iter_mut().enumerate() {
@@ -97,7 +132,7 @@ fn puzzle_gold(input: &str) -> (Vec<Option<u64>>, Vec<Option<u64>>, Vec<String>)
     let mut next_is_file = true;
     let mut next_file_id = 0;
     let mut errors = Vec::new();
-    for character in input.chars().into_iter() {
+    for character in input.chars() {
         let num_slots = character.to_string().parse::<u64>();
         match num_slots {
             Err(parse_error) => {
@@ -124,8 +159,7 @@ fn puzzle_gold(input: &str) -> (Vec<Option<u64>>, Vec<Option<u64>>, Vec<String>)
 
     let files_to_move = disk_spaces.iter()
         .rev()
-        .filter(|item| item.id.is_some())
-        .map(|item| item.clone())
+        .filter(|item| item.id.is_some()).cloned()
         .collect::<Vec<DiskSpace>>();
 
     // Copy files
@@ -173,7 +207,7 @@ fn split_files(input: Vec<DiskSpace>) -> Vec<Option<u64>> {
     output
 }
 
-fn calculate_checksum(input: &Vec<Option<u64>>) -> u64 {
+fn calculate_checksum(input: &[Option<u64>]) -> u64 {
     input.iter().enumerate().fold(0, |acc, (index, cell)| {
         match cell {
             None => acc,
@@ -1,9 +1,17 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use crate::app::{DayOutput, Diagnostic, Tab};
+use crate::app::{DayOutput, Diagnostic, Example, Part, Tab};
+use crate::parameters::{Parameter, Parameters};
+use crate::timing::{Phase, Stopwatch};
 use indextree::{Arena, NodeEdge, NodeId};
 
-pub fn puzzle(_input: &str) -> DayOutput {
+pub const TITLE: &str = "Template";
+
+pub const EXAMPLES: &[Example] = &[];
+
+pub const PARAMETERS: &[Parameter] = &[];
+
+pub fn puzzle(_input: &str, _part: Part, _parameters: &Parameters, _stopwatch: &mut Stopwatch) -> DayOutput {
     let mut errors: Vec<String> = Vec::new();
     let mut tabs: Vec<Tab> = Vec::new();
     DayOutput {
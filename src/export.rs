@@ -0,0 +1,205 @@
+use gloo::file::{Blob, ObjectUrl};
+use gloo::timers::callback::Timeout;
+use serde::Serialize;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlAnchorElement;
+use crate::app::{Diagnostic, DiagnosticEntry, Tab, TabContent, TabSeries};
+use crate::grid_canvas::grid_png;
+
+/// The ways a tab can be saved to a file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TabExport {
+    Text,
+    GridText,
+    GridPng,
+    TableCsv,
+    TableJson,
+    ChartCsv,
+    ChartJson,
+}
+
+impl TabExport {
+    /// The exports that have something to save for this tab.
+    pub fn for_tab(tab: &Tab) -> Vec<TabExport> {
+        let mut exports = Vec::new();
+        if !tab.strings.is_empty() {
+            exports.push(TabExport::Text);
+        }
+        match &tab.content {
+            TabContent::Grid(grid) if !grid.is_empty() => exports.extend([TabExport::GridText, TabExport::GridPng]),
+            TabContent::Table(_) => exports.extend([TabExport::TableCsv, TabExport::TableJson]),
+            TabContent::Chart(_) => exports.extend([TabExport::ChartCsv, TabExport::ChartJson]),
+            _ => {}
+        }
+        exports
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TabExport::Text => "Text",
+            TabExport::GridText => "Grid text",
+            TabExport::GridPng => "Grid PNG",
+            TabExport::TableCsv => "Table CSV",
+            TabExport::TableJson => "Table JSON",
+            TabExport::ChartCsv => "Chart CSV",
+            TabExport::ChartJson => "Chart JSON",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            TabExport::Text | TabExport::GridText => "txt",
+            TabExport::GridPng => "png",
+            TabExport::TableCsv | TabExport::ChartCsv => "csv",
+            TabExport::TableJson | TabExport::ChartJson => "json",
+        }
+    }
+}
+
+/// Saves a tab of the given day through the browser's downloads.
+pub fn download_tab(tab: &Tab, export: TabExport, day_number: u32) -> Result<(), String> {
+    let suffix = match export {
+        TabExport::GridText | TabExport::GridPng => "-grid",
+        TabExport::TableCsv | TabExport::TableJson => "-table",
+        TabExport::ChartCsv | TabExport::ChartJson => "-chart",
+        TabExport::Text => "",
+    };
+    let file_name = format!("day{}-{}{}.{}", day_number, slug(&tab.title), suffix, export.extension());
+    match (export, &tab.content) {
+        (TabExport::Text, _) => download_text(&file_name, "text/plain", &(tab.strings.join("\n") + "\n")),
+        (TabExport::GridText, TabContent::Grid(_)) => download_text(&file_name, "text/plain", &grid_text(tab)),
+        (TabExport::GridPng, TabContent::Grid(_)) => download_url(&file_name, &grid_png(tab)?),
+        (TabExport::TableCsv, TabContent::Table(table)) => download_text(&file_name, "text/csv", &table.to_csv()),
+        (TabExport::TableJson, TabContent::Table(table)) => download_text(&file_name, "application/json", &to_json(table)?),
+        (TabExport::ChartCsv, TabContent::Chart(chart)) => download_text(&file_name, "text/csv", &chart.to_csv()),
+        (TabExport::ChartJson, TabContent::Chart(chart)) => download_text(&file_name, "application/json", &to_json(chart)?),
+        _ => Err(format!("Tab \"{}\" has nothing to save as {}", tab.title, export.name())),
+    }
+}
+
+/// The document "Download all" saves.
+#[derive(Serialize)]
+struct DiagnosticJson<'a> {
+    message: &'a str,
+    entries: &'a [DiagnosticEntry],
+    tabs: Vec<TabJson<'a>>,
+    /// Indices into `tabs`
+    series: &'a [TabSeries],
+}
+
+/// A tab of `DiagnosticJson`. Lazy tabs that were never opened have `rendered: false` and only their title.
+#[derive(Serialize)]
+struct TabJson<'a> {
+    title: &'a str,
+    rendered: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    strings: Option<&'a [String]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<&'a TabContent>,
+}
+
+/// Saves the message, entries, tabs and series of a run as one JSON document.
+pub fn download_diagnostic(diagnostic: &Diagnostic, day_number: u32) -> Result<(), String> {
+    download_text(&format!("day{}-diagnostic.json", day_number), "application/json", &diagnostic_json(diagnostic)?)
+}
+
+fn diagnostic_json(diagnostic: &Diagnostic) -> Result<String, String> {
+    to_json(&DiagnosticJson {
+        message: diagnostic.message(),
+        entries: diagnostic.entries(),
+        tabs: diagnostic.tab_titles().enumerate().map(|(index, title)| {
+            let tab = diagnostic.tab(index);
+            TabJson {
+                title,
+                rendered: tab.is_some(),
+                strings: tab.map(|tab| tab.strings.as_slice()),
+                content: tab.map(|tab| &tab.content),
+            }
+        }).collect(),
+        series: diagnostic.series(),
+    })
+}
+
+/// One line per grid row with the text of its cells.
+fn grid_text(tab: &Tab) -> String {
+    tab.grid().iter().map(|row| row.iter().map(|cell| cell.text.as_str()).collect::<String>() + "\n").collect()
+}
+
+/// The fields separated by commas, quoting the ones that contain a comma, quote or line break.
+pub fn csv_line<S: AsRef<str>>(fields: impl IntoIterator<Item = S>) -> String {
+    fields.into_iter().map(|field| {
+        let field = field.as_ref();
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }).collect::<Vec<_>>().join(",")
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|error| error.to_string())
+}
+
+/// The title in lowercase, with anything but letters and digits turned into single dashes.
+fn slug(title: &str) -> String {
+    let slug = title.to_lowercase().split(|c: char| !c.is_alphanumeric()).filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-");
+    if slug.is_empty() { "tab".to_string() } else { slug }
+}
+
+fn download_text(file_name: &str, mime_type: &str, contents: &str) -> Result<(), String> {
+    let url = ObjectUrl::from(Blob::new_with_options(contents, Some(mime_type)));
+    download_url(file_name, &url)?;
+    // Revoking the url right away can cancel the download before the browser has read it
+    Timeout::new(60_000, move || drop(url)).forget();
+    Ok(())
+}
+
+/// Clicks a link to the url that tells the browser to save it rather than open it.
+fn download_url(file_name: &str, url: &str) -> Result<(), String> {
+    let anchor = gloo::utils::document().create_element("a")
+        .map_err(|error| format!("{:?}", error))?
+        .dyn_into::<HtmlAnchorElement>()
+        .map_err(|_| "Failed to create a download link".to_string())?;
+    anchor.set_href(url);
+    anchor.set_download(file_name);
+    anchor.click();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::LazyTab;
+
+    #[test]
+    fn csv_line_quotes_fields_that_need_it() {
+        assert_eq!(csv_line(["a", "b c", ""]), "a,b c,");
+        assert_eq!(csv_line(["1,2", "say \"hi\"", "two\nlines"]), "\"1,2\",\"say \"\"hi\"\"\",\"two\nlines\"");
+        assert_eq!(csv_line(Vec::<String>::new()), "");
+    }
+
+    #[test]
+    fn slug_keeps_letters_and_digits() {
+        assert_eq!(slug("Output Grid"), "output-grid");
+        assert_eq!(slug("  S12: after (moves)  "), "s12-after-moves");
+        assert_eq!(slug("Ünïcode ok"), "ünïcode-ok");
+        assert_eq!(slug("?!-- ()"), "tab");
+        assert_eq!(slug(""), "tab");
+    }
+
+    #[test]
+    fn diagnostic_json_marks_lazy_tabs() {
+        let ready = Tab {
+            title: "Ready".to_string(),
+            strings: vec!["line".to_string()],
+            content: TabContent::Strings,
+        };
+        let diagnostic = Diagnostic::with_tabs(vec![ready], "Done".to_string())
+            .with_lazy_tabs(vec![LazyTab::new("Lazy".to_string(), || unreachable!())]);
+        let json: serde_json::Value = serde_json::from_str(&diagnostic_json(&diagnostic).unwrap()).unwrap();
+        assert_eq!(json["message"], "Done");
+        assert_eq!(json["tabs"][0], serde_json::json!({"title": "Ready", "rendered": true, "strings": ["line"], "content": "Strings"}));
+        assert_eq!(json["tabs"][1], serde_json::json!({"title": "Lazy", "rendered": false}));
+    }
+}
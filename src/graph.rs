@@ -0,0 +1,493 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::f64::consts::TAU;
+use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use web_sys::Element;
+use yew::prelude::*;
+use crate::app::{Tab, TabContent};
+use crate::chart::SERIES_COLOURS;
+
+/// Distance between neighbouring nodes in a layout.
+const NODE_SPACING: f64 = 48.0;
+const LAYER_SPACING: f64 = 72.0;
+const NODE_RADIUS: f64 = 8.0;
+const WIDTH: f64 = 960.0;
+const HEIGHT: f64 = 640.0;
+const MIN_ZOOM: f64 = 0.01;
+const MAX_ZOOM: f64 = 8.0;
+/// Edge labels are only drawn for highlighted edges on graphs with more edges than this.
+const MAX_EDGE_LABELS: usize = 100;
+const DEFAULT_NODE_COLOUR: &str = "#9ca3af";
+
+/// Nodes joined by labelled edges, for a graph tab.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Graph {
+    pub directed: bool,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// Names of the node groups, each drawn in its own colour
+    pub groups: Vec<String>,
+    /// Where `lay_out` put every node, so the app doesn't have to compute the layout while it draws
+    #[serde(default)]
+    pub positions: Vec<(f64, f64)>,
+    /// Node index by label, only needed while the day builds the graph
+    #[serde(skip)]
+    lookup: HashMap<String, usize>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub label: String,
+    /// Index into `Graph::groups`
+    pub group: Option<usize>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: usize,
+    pub to: usize,
+    pub label: String,
+}
+
+impl Graph {
+    pub fn new(directed: bool) -> Self {
+        Graph {
+            directed,
+            ..Default::default()
+        }
+    }
+
+    /// The index of the node with this label, adding it if it doesn't exist yet.
+    pub fn node(&mut self, label: &str) -> usize {
+        if let Some(index) = self.lookup.get(label) {
+            return *index;
+        }
+        self.nodes.push(GraphNode {
+            label: label.to_string(),
+            group: None,
+        });
+        self.lookup.insert(label.to_string(), self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    /// Adds an edge between the nodes with these labels, adding the nodes too.
+    pub fn edge(&mut self, from: &str, to: &str, label: String) {
+        let from = self.node(from);
+        let to = self.node(to);
+        self.edges.push(GraphEdge {
+            from,
+            to,
+            label,
+        });
+    }
+
+    /// Puts the nodes with these labels in a new group, skipping labels without a node. A node in several groups keeps the last one.
+    pub fn group<'a>(&mut self, name: String, labels: impl IntoIterator<Item = &'a str>) {
+        self.groups.push(name);
+        let group = self.groups.len() - 1;
+        for label in labels {
+            if let Some(node) = self.lookup.get(label) {
+                self.nodes[*node].group = Some(group);
+            }
+        }
+    }
+
+    /// One line per edge, then one per group, for the command line.
+    pub fn to_plain_text(&self) -> Vec<String> {
+        let arrow = if self.directed { "->" } else { "--" };
+        let edges = self.edges.iter().map(|edge| {
+            let (from, to) = (&self.nodes[edge.from].label, &self.nodes[edge.to].label);
+            if edge.label.is_empty() {
+                format!("{} {} {}", from, arrow, to)
+            } else {
+                format!("{} {} {} ({})", from, arrow, to, edge.label)
+            }
+        });
+        let groups = self.groups.iter().enumerate().map(|(group, name)| {
+            let members = self.nodes.iter().filter(|node| node.group == Some(group)).map(|node| node.label.as_str()).collect::<Vec<_>>();
+            format!("{}: {}", name, members.join(", "))
+        });
+        edges.chain(groups).collect()
+    }
+
+    /// Nodes connected to each node, ignoring the direction of edges.
+    fn neighbours(&self) -> Vec<HashSet<usize>> {
+        let mut neighbours = vec![HashSet::new(); self.nodes.len()];
+        for edge in self.edges.iter() {
+            neighbours[edge.from].insert(edge.to);
+            neighbours[edge.to].insert(edge.from);
+        }
+        neighbours
+    }
+
+    /// Positions for every node: layers from top to bottom for acyclic directed graphs, a force-directed layout otherwise.
+    pub fn layout(&self) -> Vec<(f64, f64)> {
+        self.directed.then(|| self.layered_layout()).flatten().unwrap_or_else(|| self.force_layout())
+    }
+
+    /// Stores the layout in `positions`, unless it is already there. The worker calls this before sending a tab to the app.
+    pub fn lay_out(&mut self) {
+        if self.positions.len() != self.nodes.len() {
+            self.positions = self.layout();
+        }
+    }
+
+    /// Puts every node one layer below its lowest predecessor. Gives up on cycles.
+    fn layered_layout(&self) -> Option<Vec<(f64, f64)>> {
+        let count = self.nodes.len();
+        let mut predecessors = vec![Vec::new(); count];
+        let mut successors = vec![Vec::new(); count];
+        for edge in self.edges.iter() {
+            predecessors[edge.to].push(edge.from);
+            successors[edge.from].push(edge.to);
+        }
+        let mut incoming = predecessors.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut queue = (0..count).filter(|node| incoming[*node] == 0).collect::<VecDeque<_>>();
+        let mut layer = vec![0; count];
+        let mut sorted = 0;
+        while let Some(node) = queue.pop_front() {
+            sorted += 1;
+            for successor in successors[node].iter() {
+                layer[*successor] = layer[*successor].max(layer[node] + 1);
+                incoming[*successor] -= 1;
+                if incoming[*successor] == 0 {
+                    queue.push_back(*successor);
+                }
+            }
+        }
+        if sorted < count {
+            return None;
+        }
+
+        let mut layers = vec![Vec::new(); layer.iter().max().map_or(0, |max| max + 1)];
+        for (node, node_layer) in layer.iter().enumerate() {
+            layers[*node_layer].push(node);
+        }
+        // Order each layer by the average position of the predecessors, to untangle the edges a bit
+        let mut order = vec![0.0; count];
+        for nodes in layers.iter_mut() {
+            let mut keyed = nodes.iter().map(|node| {
+                let key = if predecessors[*node].is_empty() {
+                    f64::MAX
+                } else {
+                    predecessors[*node].iter().map(|predecessor| order[*predecessor]).sum::<f64>() / predecessors[*node].len() as f64
+                };
+                (key, *node)
+            }).collect::<Vec<_>>();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            *nodes = keyed.into_iter().map(|(_, node)| node).collect();
+            for (index, node) in nodes.iter().enumerate() {
+                order[*node] = index as f64 - (nodes.len() - 1) as f64 / 2.0;
+            }
+        }
+        Some((0..count).map(|node| (order[node] * NODE_SPACING, layer[node] as f64 * LAYER_SPACING)).collect())
+    }
+
+    /// Fruchterman-Reingold: nodes push each other away, edges pull them together, starting from a circle.
+    fn force_layout(&self) -> Vec<(f64, f64)> {
+        let count = self.nodes.len();
+        let radius = NODE_SPACING * count as f64 / TAU;
+        let mut positions = (0..count).map(|node| {
+            let angle = node as f64 / count as f64 * TAU;
+            (radius * angle.cos(), radius * angle.sin())
+        }).collect::<Vec<_>>();
+        let iterations = match count {
+            0..200 => 300,
+            200..1000 => 100,
+            _ => 30,
+        };
+        let k = NODE_SPACING;
+        let mut temperature = radius.max(k);
+        for _ in 0..iterations {
+            let mut displacement = vec![(0.0, 0.0); count];
+            for first in 0..count {
+                for second in (first + 1)..count {
+                    let (dx, dy) = (positions[first].0 - positions[second].0, positions[first].1 - positions[second].1);
+                    let distance = (dx * dx + dy * dy).sqrt().max(0.01);
+                    let force = k * k / distance / distance;
+                    displacement[first].0 += dx * force;
+                    displacement[first].1 += dy * force;
+                    displacement[second].0 -= dx * force;
+                    displacement[second].1 -= dy * force;
+                }
+            }
+            for edge in self.edges.iter().filter(|edge| edge.from != edge.to) {
+                let (dx, dy) = (positions[edge.from].0 - positions[edge.to].0, positions[edge.from].1 - positions[edge.to].1);
+                let distance = (dx * dx + dy * dy).sqrt().max(0.01);
+                let force = distance / k;
+                displacement[edge.from].0 -= dx * force;
+                displacement[edge.from].1 -= dy * force;
+                displacement[edge.to].0 += dx * force;
+                displacement[edge.to].1 += dy * force;
+            }
+            for (position, (dx, dy)) in positions.iter_mut().zip(displacement) {
+                // A little gravity keeps unconnected parts from drifting off
+                let (dx, dy) = (dx - position.0 * 0.05, dy - position.1 * 0.05);
+                let length = (dx * dx + dy * dy).sqrt();
+                if length > 0.0 {
+                    let step = length.min(temperature);
+                    position.0 += dx / length * step;
+                    position.1 += dy / length * step;
+                }
+            }
+            temperature = (temperature * 0.95).max(1.0);
+        }
+        positions
+    }
+}
+
+#[derive(Properties)]
+pub struct GraphViewProps {
+    pub tab: Rc<Tab>,
+}
+
+impl PartialEq for GraphViewProps {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.tab, &other.tab)
+    }
+}
+
+pub enum GraphViewMessage {
+    Select(usize),
+    Hover(Option<usize>),
+    Zoom(f64, f64, f64),
+    Press(f64, f64),
+    Move(f64, f64),
+    Release,
+    Fit,
+}
+
+/// Draws the graph of a tab as SVG. Scroll to zoom, drag to pan, click a node to highlight its neighbours.
+pub struct GraphView {
+    svg: NodeRef,
+    positions: Vec<(f64, f64)>,
+    neighbours: Vec<HashSet<usize>>,
+    selected: Option<usize>,
+    hovered: Option<usize>,
+    zoom: f64,
+    offset: (f64, f64),
+    /// The last mouse position while the left button is held
+    dragging: Option<(f64, f64)>,
+    /// Whether the mouse moved since it was pressed, so the end of a drag doesn't select a node
+    dragged: bool,
+}
+
+impl Component for GraphView {
+    type Message = GraphViewMessage;
+    type Properties = GraphViewProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut graph_view = GraphView {
+            svg: NodeRef::default(),
+            positions: vec![],
+            neighbours: vec![],
+            selected: None,
+            hovered: None,
+            zoom: 1.0,
+            offset: (0.0, 0.0),
+            dragging: None,
+            dragged: false,
+        };
+        graph_view.load(&ctx.props().tab);
+        graph_view
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, message: Self::Message) -> bool {
+        match message {
+            GraphViewMessage::Select(node) => {
+                if !self.dragged {
+                    self.selected = if self.selected == Some(node) { None } else { Some(node) };
+                }
+            }
+            GraphViewMessage::Hover(node) => {
+                self.hovered = node;
+            }
+            GraphViewMessage::Zoom(x, y, delta) => {
+                let zoom = (self.zoom * 1.1f64.powf(-delta.signum())).clamp(MIN_ZOOM, MAX_ZOOM);
+                // Keep the point under the mouse in place
+                self.offset.0 = x - (x - self.offset.0) * zoom / self.zoom;
+                self.offset.1 = y - (y - self.offset.1) * zoom / self.zoom;
+                self.zoom = zoom;
+            }
+            GraphViewMessage::Press(x, y) => {
+                self.dragging = Some((x, y));
+                self.dragged = false;
+            }
+            GraphViewMessage::Move(x, y) => {
+                let Some((last_x, last_y)) = self.dragging else {
+                    return false;
+                };
+                self.offset.0 += x - last_x;
+                self.offset.1 += y - last_y;
+                self.dragging = Some((x, y));
+                self.dragged = true;
+            }
+            GraphViewMessage::Release => {
+                self.dragging = None;
+            }
+            GraphViewMessage::Fit => {
+                self.fit();
+            }
+        }
+        true
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.load(&ctx.props().tab);
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let TabContent::Graph(graph) = &ctx.props().tab.content else {
+            return html! {};
+        };
+        let link = ctx.link();
+        let focus = self.selected.or(self.hovered);
+        let highlighted = |node: usize| match focus {
+            Some(focus) => focus == node || self.neighbours[focus].contains(&node),
+            None => true,
+        };
+        let edge_highlighted = |edge: &GraphEdge| match focus {
+            Some(focus) => edge.from == focus || edge.to == focus,
+            None => true,
+        };
+        let colour = |node: &GraphNode| node.group.map_or(DEFAULT_NODE_COLOUR, |group| SERIES_COLOURS[group % SERIES_COLOURS.len()]);
+        let show_edge_labels = graph.edges.len() <= MAX_EDGE_LABELS;
+
+        let svg = self.svg.clone();
+        let relative = move |event: &MouseEvent| {
+            let rect = svg.cast::<Element>().map(|element| element.get_bounding_client_rect());
+            let (left, top) = rect.map_or((0.0, 0.0), |rect| (rect.left(), rect.top()));
+            (event.client_x() as f64 - left, event.client_y() as f64 - top)
+        };
+        let onwheel = {
+            let relative = relative.clone();
+            link.callback(move |event: WheelEvent| {
+                event.prevent_default();
+                let (x, y) = relative(&event);
+                GraphViewMessage::Zoom(x, y, event.delta_y())
+            })
+        };
+        let onmousedown = {
+            let relative = relative.clone();
+            link.batch_callback(move |event: MouseEvent| {
+                (event.button() == 0).then(|| {
+                    let (x, y) = relative(&event);
+                    GraphViewMessage::Press(x, y)
+                })
+            })
+        };
+        let onmousemove = link.callback(move |event: MouseEvent| {
+            let (x, y) = relative(&event);
+            GraphViewMessage::Move(x, y)
+        });
+        let onmouseup = link.callback(|_| GraphViewMessage::Release);
+        let onmouseleave = link.callback(|_| GraphViewMessage::Release);
+
+        let edges = graph.edges.iter().map(|edge| {
+            let (from, to) = (self.positions[edge.from], self.positions[edge.to]);
+            let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+            let length = (dx * dx + dy * dy).sqrt().max(0.01);
+            // Stop at the edge of the target so the arrow head is visible
+            let end = (to.0 - dx / length * NODE_RADIUS, to.1 - dy / length * NODE_RADIUS);
+            let is_highlighted = edge_highlighted(edge);
+            let title = format!("{} {} {} {}", graph.nodes[edge.from].label, if graph.directed { "->" } else { "--" }, graph.nodes[edge.to].label, edge.label);
+            html! {
+                <g opacity={if is_highlighted { "1" } else { "0.1" }}>
+                    <line x1={from.0.to_string()} y1={from.1.to_string()} x2={end.0.to_string()} y2={end.1.to_string()}
+                        stroke={if focus.is_some() && is_highlighted { "#f3f4f6" } else { "#6b7280" }} stroke-width="1"
+                        marker-end={graph.directed.then_some("url(#graph-arrow)")}>
+                        <title>{title.trim_end().to_string()}</title>
+                    </line>
+                    {if !edge.label.is_empty() && (show_edge_labels || (focus.is_some() && is_highlighted)) {
+                        html! {
+                            <text x={((from.0 + to.0) / 2.0).to_string()} y={((from.1 + to.1) / 2.0).to_string()} font-size="10" text-anchor="middle" fill="#d1d5db">{&edge.label}</text>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                </g>
+            }
+        });
+        let nodes = graph.nodes.iter().enumerate().map(|(index, node)| {
+            let (x, y) = self.positions[index];
+            let title = format!("{}: {} neighbours", node.label, self.neighbours[index].len());
+            html! {
+                <g opacity={if highlighted(index) { "1" } else { "0.15" }} class="cursor-pointer"
+                    onclick={link.callback(move |_| GraphViewMessage::Select(index))}
+                    onmouseenter={link.callback(move |_| GraphViewMessage::Hover(Some(index)))}
+                    onmouseleave={link.callback(|_| GraphViewMessage::Hover(None))}>
+                    <circle cx={x.to_string()} cy={y.to_string()} r={NODE_RADIUS.to_string()} fill={colour(node)}
+                        stroke={if Some(index) == self.selected { "#f3f4f6" } else { "#1f2937" }} stroke-width="2">
+                        <title>{title}</title>
+                    </circle>
+                    <text x={x.to_string()} y={(y - NODE_RADIUS - 3.0).to_string()} font-size="11" text-anchor="middle" fill="#f3f4f6">{&node.label}</text>
+                </g>
+            }
+        });
+
+        html! {
+            <div class="flex flex-col gap-1">
+                <div class="flex flex-row items-center gap-2 text-gray-400">
+                    <button onclick={link.callback(|_| GraphViewMessage::Fit)} class="p-1 border border-gray-400 rounded-md">
+                        {"Fit"}
+                    </button>
+                    <div>{format!("{} nodes, {} edges", graph.nodes.len(), graph.edges.len())}</div>
+                    <div>{self.selected.map(|node| format!("Selected {}", graph.nodes[node].label)).unwrap_or("Click a node to highlight its neighbours".to_string())}</div>
+                </div>
+                <svg ref={self.svg.clone()} width={WIDTH.to_string()} height={HEIGHT.to_string()}
+                    class={classes!("border", "border-gray-700", if self.dragging.is_some() {"cursor-grabbing"} else {"cursor-grab"})}
+                    {onwheel} {onmousedown} {onmousemove} {onmouseup} {onmouseleave}>
+                    <defs>
+                        <marker id="graph-arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto-start-reverse">
+                            <path d="M 0 0 L 10 5 L 0 10 z" fill="#9ca3af" />
+                        </marker>
+                    </defs>
+                    <g transform={format!("translate({} {}) scale({})", self.offset.0, self.offset.1, self.zoom)}>
+                        {for edges}
+                        {for nodes}
+                    </g>
+                </svg>
+                <div class="flex flex-row gap-3">
+                    {for graph.groups.iter().enumerate().map(|(group, name)| html! {
+                        <div class="flex flex-row items-center gap-1">
+                            <div class="w-3 h-3 rounded-full" style={format!("background-color: {}", SERIES_COLOURS[group % SERIES_COLOURS.len()])}></div>
+                            <div>{name}</div>
+                        </div>
+                    })}
+                </div>
+            </div>
+        }
+    }
+}
+
+impl GraphView {
+    fn load(&mut self, tab: &Tab) {
+        let TabContent::Graph(graph) = &tab.content else {
+            return;
+        };
+        self.positions = if graph.positions.len() == graph.nodes.len() { graph.positions.clone() } else { graph.layout() };
+        self.neighbours = graph.neighbours();
+        self.selected = None;
+        self.hovered = None;
+        self.fit();
+    }
+
+    /// Zooms and centers so every node is visible, never zooming in past 1.
+    fn fit(&mut self) {
+        let (min_x, max_x, min_y, max_y) = self.positions.iter().fold(
+            (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY),
+            |(min_x, max_x, min_y, max_y), (x, y)| (min_x.min(*x), max_x.max(*x), min_y.min(*y), max_y.max(*y)),
+        );
+        if min_x > max_x {
+            return;
+        }
+        let margin = NODE_SPACING;
+        let (width, height) = (max_x - min_x + 2.0 * margin, max_y - min_y + 2.0 * margin);
+        self.zoom = (WIDTH / width).min(HEIGHT / height).clamp(MIN_ZOOM, 1.0);
+        self.offset = (
+            WIDTH / 2.0 - (min_x + max_x) / 2.0 * self.zoom,
+            HEIGHT / 2.0 - (min_y + max_y) / 2.0 * self.zoom,
+        );
+    }
+}
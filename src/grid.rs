@@ -1,241 +1,321 @@
 use std::fmt::{Debug, Display, Formatter};
-use std::iter::zip;
-use std::ops::Deref;
+use std::ops::{Add, Deref, Index, IndexMut, Mul, Neg, Sub};
 use yew::Classes;
 use crate::app::GridCell;
 
 
-#[derive(Clone, Debug)]
-pub struct Grid<T> (pub Vec<Vec<T>>);
+/// Cells stored row after row, with every row as wide as the grid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
 
+/// A position or offset in a grid, `x` to the right and `y` downwards.
 #[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
 pub struct Coord(pub (i32, i32));
 
+/// A `Coord` for positions that don't fit in `i32`.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct Coord64(pub (i64, i64));
+
+/// A step to one of the eight cells around a cell. Rows count downwards, so `Up` is `(0, -1)`.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+/// The lines of an input, without the carriage return of Windows line endings.
+fn lines(input: &str) -> impl Iterator<Item = &str> {
+    input.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line))
+}
+
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> Grid<T> {
     pub fn new() -> Self {
-        Self(Vec::new())
+        Grid {
+            cells: Vec::new(),
+            width: 0,
+            height: 0,
+        }
     }
 
     pub fn new_repeat(x: usize, y: usize, item: T) -> Self
         where T: Clone {
-        let grid = (0..y).map(|_| {
-            (0..x).map(|_| {
-                item.clone()
-            }).collect()
-        }).collect();
-        Grid(grid)
+        Grid {
+            cells: vec![item; x * y],
+            width: x,
+            height: y,
+        }
+    }
+
+    /// A grid from rows that are all as wide as the first one. Empty rows at the end are left out, like the line break at the end of an input.
+    pub fn try_from_rows(rows: Vec<Vec<T>>) -> Result<Self, String> {
+        let mut grid = Grid::new();
+        let used_rows = rows.iter().rposition(|row| !row.is_empty()).map_or(0, |last| last + 1);
+        for (y, row) in rows.into_iter().take(used_rows).enumerate() {
+            if y > 0 && row.len() != grid.width {
+                return Err(format!("Row {} has {} cells where the rows above have {}", y + 1, row.len(), grid.width));
+            }
+            grid.push_row(row);
+        }
+        Ok(grid)
+    }
+
+    /// Like `try_from_rows`, but ragged rows are a bug in the day or a broken input, so they panic.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        Self::try_from_rows(rows).unwrap_or_else(|error| panic!("{}", error))
     }
 
-    pub fn from<F>(input: &str, mut cell_function: F) -> Self
+    /// Parses a grid with a cell per character, failing if the lines aren't all as long.
+    pub fn parse<F>(input: &str, mut cell_function: F) -> Result<Self, String>
         where F: FnMut(char) -> T {
-        let grid = input.split("\n").map(|row| {
-            row.chars().map(|character| {
-                cell_function(character)
-            }).collect::<Vec<T>>()
-        }).filter(|a| !a.is_empty()).collect::<Vec<Vec<T>>>();
-        Grid(grid)
+        Self::try_from_rows(lines(input).map(|row| {
+            row.chars().map(&mut cell_function).collect()
+        }).collect())
     }
 
-    pub fn from_filtered<F>(input: &str, mut cell_function: F) -> Self
+    /// Like `parse`, but ragged rows panic.
+    #[allow(dead_code)]
+    pub fn from<F>(input: &str, cell_function: F) -> Self
+        where F: FnMut(char) -> T {
+        Self::parse(input, cell_function).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `parse`, leaving out the characters the function returns `None` for.
+    pub fn parse_filtered<F>(input: &str, mut cell_function: F) -> Result<Self, String>
+        where F: FnMut(char) -> Option<T> {
+        Self::try_from_rows(lines(input).map(|row| {
+            row.chars().filter_map(&mut cell_function).collect()
+        }).collect())
+    }
+
+    /// Like `parse_filtered`, but ragged rows panic.
+    #[allow(dead_code)]
+    pub fn from_filtered<F>(input: &str, cell_function: F) -> Self
         where F: FnMut(char) -> Option<T> {
-        let grid = input.split("\n").map(|row| {
-            row.chars().filter_map(|character| {
-                cell_function(character)
-            }).collect::<Vec<T>>()
-        }).filter(|a| !a.is_empty()).collect::<Vec<Vec<T>>>();
-        Grid(grid)
+        Self::parse_filtered(input, cell_function).unwrap_or_else(|error| panic!("{}", error))
     }
 
-    pub fn from_filtered_flatten<F>(input: &str, mut cell_function: F) -> Self
+    /// Like `parse_filtered`, with any number of cells per character.
+    pub fn parse_filtered_flatten<F>(input: &str, mut cell_function: F) -> Result<Self, String>
         where F: FnMut(char) -> Option<Vec<T>> {
-        let grid = input.split("\n").map(|row| {
-            row.chars().filter_map(|character| {
-                cell_function(character)
-            }).flatten().collect::<Vec<T>>()
-        }).filter(|a| !a.is_empty()).collect::<Vec<Vec<T>>>();
-        Grid(grid)
+        Self::try_from_rows(lines(input).map(|row| {
+            row.chars().filter_map(&mut cell_function).flatten().collect()
+        }).collect())
     }
 
-    pub fn from_with_index<F>(input: &str, mut cell_function: F) -> Self
+    /// Like `parse_filtered_flatten`, but ragged rows panic.
+    #[allow(dead_code)]
+    pub fn from_filtered_flatten<F>(input: &str, cell_function: F) -> Self
+        where F: FnMut(char) -> Option<Vec<T>> {
+        Self::parse_filtered_flatten(input, cell_function).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `parse`, with the x and y of every character.
+    pub fn parse_with_index<F>(input: &str, mut cell_function: F) -> Result<Self, String>
         where F: FnMut(char, i32, i32) -> T {
-        let grid = input.split("\n").enumerate().map(|(y, row)| {
+        Self::try_from_rows(lines(input).enumerate().map(|(y, row)| {
             row.chars().enumerate().map(|(x, character)| {
                 cell_function(character, x as i32, y as i32)
-            }).collect::<Vec<T>>()
-        }).filter(|a| !a.is_empty()).collect::<Vec<Vec<T>>>();
-        Grid(grid)
+            }).collect()
+        }).collect())
     }
 
-    pub fn from_with_index_filtered<F>(input: &str, mut cell_function: F) -> Self
+    /// Like `parse_with_index`, but ragged rows panic.
+    #[allow(dead_code)]
+    pub fn from_with_index<F>(input: &str, cell_function: F) -> Self
+        where F: FnMut(char, i32, i32) -> T {
+        Self::parse_with_index(input, cell_function).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like `parse_filtered`, with the x and y of every character.
+    pub fn parse_with_index_filtered<F>(input: &str, mut cell_function: F) -> Result<Self, String>
         where F: FnMut(char, i32, i32) -> Option<T> {
-        let grid = input.split("\n").enumerate().map(|(y, row)| {
+        Self::try_from_rows(lines(input).enumerate().map(|(y, row)| {
             row.chars().enumerate().filter_map(|(x, character)| {
                 cell_function(character, x as i32, y as i32)
-            }).collect::<Vec<T>>()
-        }).filter(|a| !a.is_empty()).collect::<Vec<Vec<T>>>();
+            }).collect()
+        }).collect())
+    }
+
+    /// Like `parse_with_index_filtered`, but ragged rows panic.
+    #[allow(dead_code)]
+    pub fn from_with_index_filtered<F>(input: &str, cell_function: F) -> Self
+        where F: FnMut(char, i32, i32) -> Option<T> {
+        Self::parse_with_index_filtered(input, cell_function).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    #[allow(dead_code)]
+    pub fn width(&self) -> usize {
+        self.width
+    }
 
-        Grid(grid)
+    #[allow(dead_code)]
+    pub fn height(&self) -> usize {
+        self.height
     }
 
-    pub fn add_row_from<U, F>(&mut self, input: &Vec<U>, cell_function: F)
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Adds a row at the bottom. The first row sets the width, later rows have to match it.
+    fn push_row(&mut self, row: Vec<T>) {
+        assert!(self.height == 0 || row.len() == self.width, "Row {} has {} cells where the rows above have {}", self.height + 1, row.len(), self.width);
+        self.width = row.len();
+        self.height += 1;
+        self.cells.extend(row);
+    }
+
+    pub fn add_row_from<U, F>(&mut self, input: &[U], cell_function: F)
         where F: Fn(&U) -> T {
-        let new_row = input.iter().map(|item: &U| {
-            cell_function(item)
-        }).collect::<Vec<T>>();
-        self.0.push(new_row);
+        self.push_row(input.iter().map(cell_function).collect());
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // Chunks of 0 aren't allowed, and a grid without width has no cells anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    #[allow(dead_code)]
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let cells = if x < self.width { &self.cells[x..] } else { &[] };
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    /// Every cell with its coordinate, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        let width = self.width.max(1);
+        self.cells.iter().enumerate().map(move |(index, cell)| (Coord::from_usize(index % width, index / width).expect("grid is too large for Coord"), cell))
+    }
+
+    /// Where the cell at the coordinate is stored, if it is in the grid.
+    fn index_of(&self, coord: Coord) -> Option<usize> {
+        let (x, y) = coord.into_usize()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
     }
 
     pub(crate) fn find<F>(&self, predicate: F) -> Option<Coord>
         where F: Fn(&T) -> bool {
-        self.0.iter().enumerate().fold(None, |output, (y, row)| {
-            match output {
-                Some(x) => Some(x),
-                None => {
-                    row.iter().enumerate().fold(None, |output, (x, tile)| {
-                        match output {
-                            Some(x) => Some(x),
-                            None => {
-                                if predicate(tile) {
-                                    Some(Coord::new(x as i32, y as i32))
-                                } else {
-                                    None
-                                }
-                            }
-                        }
-                    })
-                }
-            }
-        })
+        self.iter().find(|(_, cell)| predicate(cell)).map(|(coord, _)| coord)
     }
 
     pub fn count<F>(&self, predicate: F) -> usize
         where F: Fn(&T) -> bool {
-        self.0.iter().fold(0, |acc, row| {
-            let subsum = row.iter().fold(0, |acc, cell| {
-                if predicate(cell) {
-                    acc + 1
-                } else {
-                    acc
-                }
-            });
-            acc + subsum
-        })
+        self.cells.iter().filter(|cell| predicate(cell)).count()
     }
 
-    pub(crate) fn swap(&mut self, first: Coord, second: Coord) -> bool
-        where T: Clone {
-        if let Some(first_contents) = self.get(first) {
-            let first_contents = first_contents.clone();
-            if let Some(handle_second) = self.get_mut(second) {
-                let second_contents = handle_second.clone();
-                *handle_second = first_contents;
-                if let Some(handle_first) = self.get_mut(first) {
-                    *handle_first = second_contents;
-                    return true;
-                }
+    pub(crate) fn swap(&mut self, first: Coord, second: Coord) -> bool {
+        match (self.index_of(first), self.index_of(second)) {
+            (Some(first), Some(second)) => {
+                self.cells.swap(first, second);
+                true
             }
+            _ => false,
         }
-        false
     }
+
+    /// Puts the rows of the other grid below these. The grids have to be as wide, unless this one is empty.
     pub(crate) fn append(&mut self, grid: Grid<T>) {
-        self.0.extend(grid.0.into_iter());
+        if grid.height == 0 {
+            return;
+        }
+        assert!(self.height == 0 || grid.width == self.width, "Appending a grid {} wide to one {} wide", grid.width, self.width);
+        self.width = grid.width;
+        self.height += grid.height;
+        self.cells.extend(grid.cells);
     }
 
     pub(crate) fn mush<F>(&mut self, grid: &Grid<T>, merge_func: F)
-        where F: Fn(&mut T, &T), T: Debug {
-        log::info!("A {:?}", self);
-        log::info!("B {:?}", grid);
-        let _ = zip(self.0.iter_mut(), grid.0.iter()).map(|(self_row, grid_row)| {
-            let _ = zip(self_row.iter_mut(), grid_row.iter()).map(|(self_item, other_item)| {
-                merge_func(self_item, other_item);
-            }).count();
-        }).count();
+        where F: Fn(&mut T, &T) {
+        for (self_item, other_item) in self.cells.iter_mut().zip(grid.cells.iter()) {
+            merge_func(self_item, other_item);
+        }
     }
 
     pub fn to_tab_grid(&self) -> Vec<Vec<GridCell>>
         where T: Display {
-        self.0.iter().map(|row| {
-            row.iter().map(|cell| {
-                GridCell {
-                    text: cell.to_string(),
-                    class: Classes::new(),
-                    title: "".to_string(),
-                }
-            }).collect()
-        }).collect()
+        self.to_tab_grid_title_class(|_, _, _| (String::new(), Classes::new()))
     }
-    pub fn to_tab_grid_class<F>(self, class_function: F) -> Vec<Vec<GridCell>>
+
+    pub fn to_tab_grid_class<F>(&self, class_function: F) -> Vec<Vec<GridCell>>
         where T: Display, F: Fn(&T) -> Classes {
-        self.0.into_iter().map(|row| {
-            row.into_iter().map(|cell| {
-                let class = class_function(&cell);
-                GridCell {
-                    text: cell.to_string(),
-                    class,
-                    title: "".to_string(),
-                }
-            }).collect()
-        }).collect()
+        self.to_tab_grid_title_class(|cell, _, _| (String::new(), class_function(cell)))
     }
 
-    pub fn _to_tab_grid_title<F>(self, title_function: F) -> Vec<Vec<GridCell>>
+    pub fn _to_tab_grid_title<F>(&self, title_function: F) -> Vec<Vec<GridCell>>
         where T: Display, F: Fn(&T) -> String {
-        self.0.into_iter().map(|row| {
-            row.into_iter().map(|cell| {
-                let title = title_function(&cell);
-                GridCell {
-                    text: cell.to_string(),
-                    class: Classes::new(),
-                    title,
-                }
-            }).collect()
-        }).collect()
+        self.to_tab_grid_title_class(|cell, _, _| (title_function(cell), Classes::new()))
     }
 
-    pub fn to_tab_grid_title_class<F>(self, title_function: F) -> Vec<Vec<GridCell>>
+    pub fn to_tab_grid_title_class<F>(&self, title_function: F) -> Vec<Vec<GridCell>>
         where T: Display, F: Fn(&T, usize, usize) -> (String, Classes) {
-        self.0.into_iter().enumerate().map(|(y, row)| {
-            row.into_iter().enumerate().map(|(x, cell)| {
-                let (title, class) = title_function(&cell, x, y);
-                GridCell {
-                    text: cell.to_string(),
-                    class,
-                    title,
-                }
-            }).collect()
-        }).collect()
+        self.map_grid(|cell, x, y| {
+            let (title, class) = title_function(cell, x, y);
+            GridCell {
+                text: cell.to_string(),
+                class,
+                title,
+            }
+        })
     }
 
     pub fn get_all_coords(&self) -> Vec<Coord> {
-        self.0.iter().enumerate().map(|(y, row)| {
-            row.iter().enumerate().map(move |(x, _)| {
-                Coord::new(x as i32, y as i32)
-            })
-        }).flatten().collect::<Vec<_>>()
+        self.iter().map(|(coord, _)| coord).collect()
     }
 
     pub fn get(&self, coord: Coord) -> Option<&T> {
-        if let Some((x, y)) = coord.into_usize() {
-            self.0.get(y).map(|row| {
-                row.get(x)
-            }).unwrap_or(None)
-        } else {
-            None
-        }
+        self.index_of(coord).map(|index| &self.cells[index])
     }
 
     pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
-        if let Some((x, y)) = coord.into_usize() {
-            self.0.get_mut(y).map(|row| {
-                row.get_mut(x)
-            }).unwrap_or(None)
-        } else {
-            None
-        }
+        self.index_of(coord).map(|index| &mut self.cells[index])
     }
+
+    /// The cells one step away in the given directions that are in the grid.
+    pub fn neighbours<'a>(&'a self, coord: Coord, directions: &'a [Direction]) -> impl Iterator<Item = (Coord, &'a T)> + 'a {
+        directions.iter().filter_map(move |direction| {
+            let next = coord + direction.offset();
+            self.get(next).map(|cell| (next, cell))
+        })
+    }
+
+    pub fn orthogonal_neighbours(&self, coord: Coord) -> impl Iterator<Item = (Coord, &T)> {
+        self.neighbours(coord, &Direction::ORTHOGONAL)
+    }
+
+    #[allow(dead_code)]
+    pub fn diagonal_neighbours(&self, coord: Coord) -> impl Iterator<Item = (Coord, &T)> {
+        self.neighbours(coord, &Direction::DIAGONAL)
+    }
+
+    #[allow(dead_code)]
+    pub fn all_neighbours(&self, coord: Coord) -> impl Iterator<Item = (Coord, &T)> {
+        self.neighbours(coord, &Direction::ALL)
+    }
+
     pub(crate) fn map_grid<U, F>(&self, mut cell_function: F) -> Vec<Vec<U>>
         where F: FnMut(&T, usize, usize) -> U {
-        self.0.iter().enumerate().map(|(y, row)| {
+        self.rows().enumerate().map(|(y, row)| {
             row.iter().enumerate().map(|(x, cell)| {
                 cell_function(cell, x, y)
             }).collect::<Vec<U>>()
@@ -243,65 +323,267 @@ impl<T> Grid<T> {
     }
 }
 
-impl Coord {
-    pub fn new<T>(x: T, y: T) -> Self
-        where T: Into<i32> {
-        Coord((x.into(), y.into()))
-    }
-    pub fn add(&self, other: &Self) -> Self {
-        Self((
-            self.deref().0 + other.deref().0,
-            self.deref().1 + other.deref().1,
-        ))
-    }
-    pub fn subtract(&self, other: &Self) -> Self {
-        Self((
-            self.deref().0 - other.deref().0,
-            self.deref().1 - other.deref().1,
-        ))
-    }
-    pub fn multiply(&self, scalar: i32) -> Self {
-        Self((
-            self.deref().0 * scalar,
-            self.deref().1 * scalar,
-        ))
-    }
-    pub fn into_usize(self) -> Option<(usize, usize)> {
-        let Coord((x, y)) = self;
-        let (x, y): (Option<usize>, Option<usize>) = (x.try_into().ok(), y.try_into().ok());
-        if let (Some(x), Some(y)) = (x, y) {
-            Some((x, y))
-        } else {
-            None
+/// Panics when the coordinate is outside the grid, use `get` when it might be.
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &Self::Output {
+        self.get(coord).unwrap_or_else(|| panic!("{} is outside the {}x{} grid", coord, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(coord).unwrap_or_else(|| panic!("{} is outside the {}x{} grid", coord, width, height))
+    }
+}
+
+/// The methods and operators shared by `Coord` and `Coord64`.
+macro_rules! coord_impls {
+    ($coord:ident, $int:ty) => {
+        // Each day only needs some of these, for one of the two types
+        #[allow(dead_code)]
+        impl $coord {
+            pub fn new<T>(x: T, y: T) -> Self
+                where T: Into<$int> {
+                Self((x.into(), y.into()))
+            }
+            pub fn x(&self) -> $int {
+                self.0.0
+            }
+            pub fn y(&self) -> $int {
+                self.0.1
+            }
+            /// The number of orthogonal steps between the two.
+            pub fn manhattan(&self, other: &Self) -> u64 {
+                let (x, y) = self.distances(other);
+                x + y
+            }
+            /// The number of steps between the two when diagonal steps are allowed.
+            pub fn chebyshev(&self, other: &Self) -> u64 {
+                let (x, y) = self.distances(other);
+                x.max(y)
+            }
+            fn distances(&self, other: &Self) -> (u64, u64) {
+                (u64::from(self.x().abs_diff(other.x())), u64::from(self.y().abs_diff(other.y())))
+            }
+            /// Wraps around into `0..size.x()` and `0..size.y()`, like walking off one edge of a repeating grid onto the other.
+            pub fn rem_euclid(&self, size: &Self) -> Self {
+                Self((self.x().rem_euclid(size.x()), self.y().rem_euclid(size.y())))
+            }
+            pub fn into_usize(self) -> Option<(usize, usize)> {
+                Some((self.x().try_into().ok()?, self.y().try_into().ok()?))
+            }
+            pub fn from_usize(x: usize, y: usize) -> Option<Self> {
+                Some(Self((x.try_into().ok()?, y.try_into().ok()?)))
+            }
+            pub fn rotate_left(self) -> Self {
+                Self((self.y(), -self.x()))
+            }
+            pub fn rotate_right(self) -> Self {
+                Self((-self.y(), self.x()))
+            }
+        }
+
+        impl Add for $coord {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self((self.x() + other.x(), self.y() + other.y()))
+            }
+        }
+
+        impl Sub for $coord {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self((self.x() - other.x(), self.y() - other.y()))
+            }
+        }
+
+        impl Mul<$int> for $coord {
+            type Output = Self;
+
+            fn mul(self, scalar: $int) -> Self {
+                Self((self.x() * scalar, self.y() * scalar))
+            }
+        }
+
+        impl Neg for $coord {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self((-self.x(), -self.y()))
+            }
+        }
+
+        impl Deref for $coord {
+            type Target = ($int, $int);
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl Display for $coord {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, "({}, {})", self.x(), self.y())
+            }
+        }
+    };
+}
+
+coord_impls!(Coord, i32);
+coord_impls!(Coord64, i64);
+
+impl Direction {
+    /// Clockwise, starting up.
+    pub const ORTHOGONAL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+    #[allow(dead_code)]
+    pub const DIAGONAL: [Direction; 4] = [Direction::UpRight, Direction::DownRight, Direction::DownLeft, Direction::UpLeft];
+    pub const ALL: [Direction; 8] = [
+        Direction::Up, Direction::UpRight, Direction::Right, Direction::DownRight,
+        Direction::Down, Direction::DownLeft, Direction::Left, Direction::UpLeft,
+    ];
+
+    pub fn is_orthogonal(self) -> bool {
+        Self::ORTHOGONAL.contains(&self)
+    }
+
+    pub fn offset(self) -> Coord {
+        match self {
+            Direction::Up => Coord::new(0, -1),
+            Direction::UpRight => Coord::new(1, -1),
+            Direction::Right => Coord::new(1, 0),
+            Direction::DownRight => Coord::new(1, 1),
+            Direction::Down => Coord::new(0, 1),
+            Direction::DownLeft => Coord::new(-1, 1),
+            Direction::Left => Coord::new(-1, 0),
+            Direction::UpLeft => Coord::new(-1, -1),
         }
     }
-    pub fn rotate_left(self) -> Self {
-        Self((self.0.1, -self.0.0))
+
+    /// The direction of a step to a neighbouring cell, `None` for any other offset.
+    #[allow(dead_code)]
+    pub fn from_offset(offset: Coord) -> Option<Self> {
+        Self::ALL.into_iter().find(|direction| direction.offset() == offset)
     }
+
+    /// `^>v<` like the puzzles draw them, and arrows for the diagonals.
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::UpRight => '↗',
+            Direction::Right => '>',
+            Direction::DownRight => '↘',
+            Direction::Down => 'v',
+            Direction::DownLeft => '↙',
+            Direction::Left => '<',
+            Direction::UpLeft => '↖',
+        }
+    }
+
+    pub fn from_arrow(arrow: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|direction| direction.arrow() == arrow)
+    }
+
+    /// Turns 90 degrees clockwise.
     pub fn rotate_right(self) -> Self {
-        Self((-self.0.1, self.0.0))
+        self.rotate_right_45().rotate_right_45()
+    }
+
+    pub fn rotate_left(self) -> Self {
+        self.rotate_left_45().rotate_left_45()
+    }
+
+    pub fn rotate_right_45(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    pub fn rotate_left_45(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
     }
+}
 
-    pub fn get_orthagonal_dirs() -> Vec<Self> {
-        vec![
-            Self::new(0, 1),
-            Self::new(1, 0),
-            Self::new(0, -1),
-            Self::new(-1, 0),
-        ]
+impl From<Direction> for Coord {
+    fn from(direction: Direction) -> Self {
+        direction.offset()
     }
 }
 
-impl Deref for Coord {
-    type Target = (i32, i32);
+impl From<Coord> for Coord64 {
+    fn from(coord: Coord) -> Self {
+        Coord64::new(coord.x(), coord.y())
+    }
+}
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+impl TryFrom<Coord64> for Coord {
+    type Error = String;
+
+    fn try_from(coord: Coord64) -> Result<Self, Self::Error> {
+        match (coord.x().try_into(), coord.y().try_into()) {
+            (Ok(x), Ok(y)) => Ok(Coord((x, y))),
+            _ => Err(format!("{} doesn't fit in a 32 bit coordinate", coord)),
+        }
     }
 }
 
-impl Display for Coord {
+impl Display for Direction {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("({}, {})", self.deref().0, self.deref().1))
+        write!(f, "{}", self.arrow())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_checks_row_widths() {
+        let grid = Grid::parse("ab\ncd\n", |character| character).unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid.row(1), Some(&['c', 'd'][..]));
+        assert_eq!(grid.column(1).collect::<String>(), "bd");
+        assert_eq!(grid[Coord::new(1, 0)], 'b');
+        assert_eq!(grid.get(Coord::new(2, 0)), None);
+        assert_eq!(Grid::parse("ab\nc\n", |character| character), Err("Row 2 has 1 cells where the rows above have 2".to_string()));
+        assert!(Grid::parse("ab\n\ncd", |character| character).is_err());
+        assert_eq!(Grid::parse("ab\r\ncd\r\n", |character| character), Ok(grid));
+    }
+
+    #[test]
+    fn directions_turn_and_step() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.rotate_right().rotate_left(), direction);
+            assert_eq!(direction.opposite().offset(), -direction.offset());
+            assert_eq!(Direction::from_offset(direction.offset()), Some(direction));
+            assert_eq!(Direction::from_arrow(direction.arrow()), Some(direction));
+        }
+        assert_eq!(Direction::Right.rotate_left().offset(), Coord::new(1, 0).rotate_left());
+        let grid = Grid::parse("abc\ndef\n", |character| character).unwrap();
+        let corner = grid.all_neighbours(Coord::new(0, 0)).map(|(_, cell)| *cell).collect::<String>();
+        assert_eq!(corner, "bed");
+        assert_eq!(grid.orthogonal_neighbours(Coord::new(1, 1)).count(), 3);
+    }
+
+    #[test]
+    fn coord_arithmetic() {
+        let (a, b) = (Coord::new(2, -3), Coord::new(-1, 1));
+        assert_eq!(a + b, Coord::new(1, -2));
+        assert_eq!(a - b, Coord::new(3, -4));
+        assert_eq!(-a * 2, Coord::new(-4, 6));
+        assert_eq!((a.manhattan(&b), a.chebyshev(&b)), (7, 4));
+        assert_eq!(Coord::new(-1, 7).rem_euclid(&Coord::new(5, 5)), Coord::new(4, 2));
+        assert_eq!(Coord::from_usize(3, 4).and_then(Coord::into_usize), Some((3, 4)));
+        assert_eq!(Coord::new(-1, 0).into_usize(), None);
+        let far = Coord64::new(10_000_000_000i64, 1) + Coord64::from(a);
+        assert_eq!(far, Coord64::new(10_000_000_002i64, -2));
+        assert!(Coord::try_from(far).is_err());
+        assert_eq!(Coord::try_from(Coord64::from(a)), Ok(a));
+    }
+}
@@ -0,0 +1,342 @@
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use yew::prelude::*;
+use crate::app::Tab;
+
+/// Size of a cell in pixels at zoom 1, the same as the `w-4 h-4` cells of the DOM renderer.
+const CELL_SIZE: f64 = 16.0;
+const MAX_WIDTH: f64 = 960.0;
+const MAX_HEIGHT: f64 = 720.0;
+const MIN_ZOOM: f64 = 0.05;
+const MAX_ZOOM: f64 = 8.0;
+/// Below this many pixels per cell the text is unreadable, so only the colours are drawn.
+const MIN_TEXT_SIZE: f64 = 6.0;
+const DEFAULT_TEXT_COLOUR: &str = "#f3f4f6";
+/// `bg-gray-900`, the background of the page
+const PAGE_BACKGROUND: &str = "#111827";
+/// Grids with more cells than this are drawn on a canvas when the renderer is automatic.
+const AUTO_CANVAS_CELLS: usize = 2500;
+
+/// How the diagnostics panel shows grids.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GridRenderer {
+    /// Canvas for big grids, DOM elements otherwise
+    #[default]
+    Auto,
+    Canvas,
+    Dom,
+}
+
+impl GridRenderer {
+    pub const ALL: [GridRenderer; 3] = [GridRenderer::Auto, GridRenderer::Canvas, GridRenderer::Dom];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GridRenderer::Auto => "Automatic",
+            GridRenderer::Canvas => "Canvas",
+            GridRenderer::Dom => "DOM elements",
+        }
+    }
+
+    pub fn uses_canvas(self, tab: &Tab) -> bool {
+        match self {
+            GridRenderer::Auto => tab.grid().iter().map(Vec::len).sum::<usize>() > AUTO_CANVAS_CELLS,
+            GridRenderer::Canvas => true,
+            GridRenderer::Dom => false,
+        }
+    }
+}
+
+/// Tailwind colours of the families days use, by class suffix.
+const PALETTE: &[(&str, &str)] = &[
+    ("white", "#ffffff"), ("black", "#000000"),
+    ("slate-50", "#f8fafc"), ("slate-100", "#f1f5f9"), ("slate-200", "#e2e8f0"), ("slate-300", "#cbd5e1"), ("slate-400", "#94a3b8"),
+    ("slate-500", "#64748b"), ("slate-600", "#475569"), ("slate-700", "#334155"), ("slate-800", "#1e293b"), ("slate-900", "#0f172a"),
+    ("gray-50", "#f9fafb"), ("gray-100", "#f3f4f6"), ("gray-200", "#e5e7eb"), ("gray-300", "#d1d5db"), ("gray-400", "#9ca3af"),
+    ("gray-500", "#6b7280"), ("gray-600", "#4b5563"), ("gray-700", "#374151"), ("gray-800", "#1f2937"), ("gray-900", "#111827"),
+    ("red-50", "#fef2f2"), ("red-100", "#fee2e2"), ("red-200", "#fecaca"), ("red-300", "#fca5a5"), ("red-400", "#f87171"),
+    ("red-500", "#ef4444"), ("red-600", "#dc2626"), ("red-700", "#b91c1c"), ("red-800", "#991b1b"), ("red-900", "#7f1d1d"),
+    ("orange-50", "#fff7ed"), ("orange-100", "#ffedd5"), ("orange-200", "#fed7aa"), ("orange-300", "#fdba74"), ("orange-400", "#fb923c"),
+    ("orange-500", "#f97316"), ("orange-600", "#ea580c"), ("orange-700", "#c2410c"), ("orange-800", "#9a3412"), ("orange-900", "#7c2d12"),
+    ("yellow-50", "#fefce8"), ("yellow-100", "#fef9c3"), ("yellow-200", "#fef08a"), ("yellow-300", "#fde047"), ("yellow-400", "#facc15"),
+    ("yellow-500", "#eab308"), ("yellow-600", "#ca8a04"), ("yellow-700", "#a16207"), ("yellow-800", "#854d0e"), ("yellow-900", "#713f12"),
+    ("green-50", "#f0fdf4"), ("green-100", "#dcfce7"), ("green-200", "#bbf7d0"), ("green-300", "#86efac"), ("green-400", "#4ade80"),
+    ("green-500", "#22c55e"), ("green-600", "#16a34a"), ("green-700", "#15803d"), ("green-800", "#166534"), ("green-900", "#14532d"),
+    ("blue-50", "#eff6ff"), ("blue-100", "#dbeafe"), ("blue-200", "#bfdbfe"), ("blue-300", "#93c5fd"), ("blue-400", "#60a5fa"),
+    ("blue-500", "#3b82f6"), ("blue-600", "#2563eb"), ("blue-700", "#1d4ed8"), ("blue-800", "#1e40af"), ("blue-900", "#1e3a8a"),
+    ("purple-50", "#faf5ff"), ("purple-100", "#f3e8ff"), ("purple-200", "#e9d5ff"), ("purple-300", "#d8b4fe"), ("purple-400", "#c084fc"),
+    ("purple-500", "#a855f7"), ("purple-600", "#9333ea"), ("purple-700", "#7e22ce"), ("purple-800", "#6b21a8"), ("purple-900", "#581c87"),
+];
+
+/// The background and text colour of a cell's `bg-*` and `text-*` classes. Unknown classes are ignored.
+pub fn cell_colours(classes: &Classes) -> (Option<&'static str>, Option<&'static str>) {
+    let mut background = None;
+    let mut text = None;
+    for class in classes {
+        if let Some(colour) = class.strip_prefix("bg-").and_then(palette_colour) {
+            background = Some(colour);
+        } else if let Some(colour) = class.strip_prefix("text-").and_then(palette_colour) {
+            text = Some(colour);
+        }
+    }
+    (background, text)
+}
+
+fn palette_colour(name: &str) -> Option<&'static str> {
+    PALETTE.iter().find(|(colour_name, _)| *colour_name == name).map(|(_, colour)| *colour)
+}
+
+#[derive(Properties)]
+pub struct GridCanvasProps {
+    pub tab: Rc<Tab>,
+}
+
+impl PartialEq for GridCanvasProps {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.tab, &other.tab)
+    }
+}
+
+pub enum GridCanvasMessage {
+    Zoom(f64, f64, f64),
+    Press(f64, f64),
+    Move(f64, f64),
+    Release,
+    Leave,
+    Fit,
+}
+
+/// Draws the grid of a tab onto a canvas. Scroll to zoom, drag to pan, hover a cell to see its title.
+pub struct GridCanvas {
+    canvas: NodeRef,
+    zoom: f64,
+    offset: (f64, f64),
+    /// The last mouse position while the left button is held
+    dragging: Option<(f64, f64)>,
+    /// The mouse position over the canvas
+    hover: Option<(f64, f64)>,
+    /// Set whenever the view or the grid changed, so hovering alone doesn't redraw
+    dirty: bool,
+}
+
+impl Component for GridCanvas {
+    type Message = GridCanvasMessage;
+    type Properties = GridCanvasProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut grid_canvas = GridCanvas {
+            canvas: NodeRef::default(),
+            zoom: 1.0,
+            offset: (0.0, 0.0),
+            dragging: None,
+            hover: None,
+            dirty: true,
+        };
+        grid_canvas.fit(&ctx.props().tab);
+        grid_canvas
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, message: Self::Message) -> bool {
+        match message {
+            GridCanvasMessage::Zoom(x, y, delta) => {
+                let zoom = (self.zoom * 1.1f64.powf(-delta.signum())).clamp(MIN_ZOOM, MAX_ZOOM);
+                // Keep the point under the mouse in place
+                self.offset.0 = x - (x - self.offset.0) * zoom / self.zoom;
+                self.offset.1 = y - (y - self.offset.1) * zoom / self.zoom;
+                self.zoom = zoom;
+                self.dirty = true;
+            }
+            GridCanvasMessage::Press(x, y) => {
+                self.dragging = Some((x, y));
+            }
+            GridCanvasMessage::Move(x, y) => {
+                if let Some((last_x, last_y)) = self.dragging {
+                    self.offset.0 += x - last_x;
+                    self.offset.1 += y - last_y;
+                    self.dragging = Some((x, y));
+                    self.dirty = true;
+                }
+                self.hover = Some((x, y));
+            }
+            GridCanvasMessage::Release => {
+                self.dragging = None;
+            }
+            GridCanvasMessage::Leave => {
+                self.dragging = None;
+                self.hover = None;
+            }
+            GridCanvasMessage::Fit => {
+                self.fit(&ctx.props().tab);
+            }
+        }
+        true
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        // Frames of an animation keep the view, a different grid starts fitted again
+        if grid_size(&old_props.tab) != grid_size(&ctx.props().tab) {
+            self.fit(&ctx.props().tab);
+        }
+        self.dirty = true;
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let tab = &ctx.props().tab;
+        let (width, height) = canvas_size(tab);
+        let link = ctx.link();
+        let onwheel = link.callback(|event: WheelEvent| {
+            event.prevent_default();
+            GridCanvasMessage::Zoom(event.offset_x() as f64, event.offset_y() as f64, event.delta_y())
+        });
+        let onmousedown = link.batch_callback(|event: MouseEvent| {
+            (event.button() == 0).then(|| GridCanvasMessage::Press(event.offset_x() as f64, event.offset_y() as f64))
+        });
+        let onmousemove = link.callback(|event: MouseEvent| GridCanvasMessage::Move(event.offset_x() as f64, event.offset_y() as f64));
+        let onmouseup = link.callback(|_| GridCanvasMessage::Release);
+        let onmouseleave = link.callback(|_| GridCanvasMessage::Leave);
+        let tooltip = self.hover.filter(|_| self.dragging.is_none()).and_then(|(x, y)| {
+            let (column, row) = self.cell_at(x, y)?;
+            let cell = tab.grid().get(row)?.get(column)?;
+            (!cell.title.is_empty()).then(|| (x, y, format!("{}, {}: {}", column, row, cell.title)))
+        });
+        html! {
+            <div class="flex flex-col gap-1">
+                <div class="flex flex-row items-center gap-2 text-gray-400">
+                    <button onclick={link.callback(|_| GridCanvasMessage::Fit)} class="p-1 border border-gray-400 rounded-md">
+                        {"Fit"}
+                    </button>
+                    <div>{format!("Zoom {:.0}%", self.zoom * 100.0)}</div>
+                </div>
+                <div class="relative w-fit">
+                    <canvas ref={self.canvas.clone()} width={width.to_string()} height={height.to_string()}
+                        class={if self.dragging.is_some() {"cursor-grabbing"} else {"cursor-grab"}}
+                        {onwheel} {onmousedown} {onmousemove} {onmouseup} {onmouseleave}/>
+                    {if let Some((x, y, title)) = tooltip {
+                        html! {
+                            <div class="absolute pointer-events-none p-1 rounded-md bg-gray-700 whitespace-nowrap" style={format!("left: {}px; top: {}px", x + 12.0, y + 12.0)}>
+                                {title}
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                </div>
+            </div>
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        if self.dirty {
+            self.dirty = false;
+            self.draw(&ctx.props().tab);
+        }
+    }
+}
+
+impl GridCanvas {
+    /// Zooms out until the whole grid fits the canvas, never zooming in past 1.
+    fn fit(&mut self, tab: &Tab) {
+        let (columns, rows) = grid_size(tab);
+        let (width, height) = canvas_size(tab);
+        self.zoom = (width / (columns.max(1) as f64 * CELL_SIZE))
+            .min(height / (rows.max(1) as f64 * CELL_SIZE))
+            .clamp(MIN_ZOOM, 1.0);
+        self.offset = (0.0, 0.0);
+        self.dirty = true;
+    }
+
+    fn cell_at(&self, x: f64, y: f64) -> Option<(usize, usize)> {
+        let size = CELL_SIZE * self.zoom;
+        let column = ((x - self.offset.0) / size).floor();
+        let row = ((y - self.offset.1) / size).floor();
+        (column >= 0.0 && row >= 0.0).then_some((column as usize, row as usize))
+    }
+
+    fn draw(&self, tab: &Tab) {
+        let Some(canvas) = self.canvas.cast::<HtmlCanvasElement>() else {
+            return;
+        };
+        let Some(context) = canvas.get_context("2d").ok().flatten()
+            .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok()) else {
+            return;
+        };
+        let (width, height) = (canvas.width() as f64, canvas.height() as f64);
+        context.clear_rect(0.0, 0.0, width, height);
+
+        let size = CELL_SIZE * self.zoom;
+        let draw_text = size >= MIN_TEXT_SIZE;
+        context.set_font(&format!("{}px monospace", (size * 0.75).floor()));
+        context.set_text_align("center");
+        context.set_text_baseline("middle");
+        // Only the cells that are on the canvas
+        let first_row = (-self.offset.1 / size).floor().max(0.0) as usize;
+        let last_row = ((height - self.offset.1) / size).ceil().max(0.0) as usize;
+        let first_column = (-self.offset.0 / size).floor().max(0.0) as usize;
+        let last_column = ((width - self.offset.0) / size).ceil().max(0.0) as usize;
+        for (y, row) in tab.grid().iter().enumerate().take(last_row).skip(first_row) {
+            for (x, cell) in row.iter().enumerate().take(last_column).skip(first_column) {
+                let left = self.offset.0 + x as f64 * size;
+                let top = self.offset.1 + y as f64 * size;
+                let (background, text) = cell_colours(&cell.class);
+                if let Some(background) = background {
+                    context.set_fill_style_str(background);
+                    context.fill_rect(left, top, size, size);
+                }
+                if draw_text && !cell.text.is_empty() {
+                    context.set_fill_style_str(text.unwrap_or(DEFAULT_TEXT_COLOUR));
+                    let _ = context.fill_text(&cell.text, left + size / 2.0, top + size / 2.0);
+                }
+            }
+        }
+    }
+}
+
+/// The whole grid at zoom 1 as a PNG data url, on the background of the page.
+pub fn grid_png(tab: &Tab) -> Result<String, String> {
+    let canvas = gloo::utils::document().create_element("canvas")
+        .map_err(|error| format!("{:?}", error))?
+        .dyn_into::<HtmlCanvasElement>()
+        .map_err(|_| "Failed to create a canvas".to_string())?;
+    let (columns, rows) = grid_size(tab);
+    let (width, height) = (columns as f64 * CELL_SIZE, rows as f64 * CELL_SIZE);
+    canvas.set_width(width as u32);
+    canvas.set_height(height as u32);
+    let context = canvas.get_context("2d").ok().flatten()
+        .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok())
+        .ok_or_else(|| "Failed to draw on a canvas".to_string())?;
+    context.set_fill_style_str(PAGE_BACKGROUND);
+    context.fill_rect(0.0, 0.0, width, height);
+    context.set_font(&format!("{}px monospace", (CELL_SIZE * 0.75).floor()));
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    for (y, row) in tab.grid().iter().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            let (left, top) = (x as f64 * CELL_SIZE, y as f64 * CELL_SIZE);
+            let (background, text) = cell_colours(&cell.class);
+            if let Some(background) = background {
+                context.set_fill_style_str(background);
+                context.fill_rect(left, top, CELL_SIZE, CELL_SIZE);
+            }
+            if !cell.text.is_empty() {
+                context.set_fill_style_str(text.unwrap_or(DEFAULT_TEXT_COLOUR));
+                let _ = context.fill_text(&cell.text, left + CELL_SIZE / 2.0, top + CELL_SIZE / 2.0);
+            }
+        }
+    }
+    canvas.to_data_url_with_type("image/png").map_err(|error| format!("{:?}", error))
+}
+
+/// Columns and rows, counting the longest row.
+fn grid_size(tab: &Tab) -> (usize, usize) {
+    (tab.grid().iter().map(Vec::len).max().unwrap_or(0), tab.grid().len())
+}
+
+fn canvas_size(tab: &Tab) -> (f64, f64) {
+    let (columns, rows) = grid_size(tab);
+    (
+        (columns as f64 * CELL_SIZE).clamp(CELL_SIZE, MAX_WIDTH),
+        (rows as f64 * CELL_SIZE).clamp(CELL_SIZE, MAX_HEIGHT),
+    )
+}
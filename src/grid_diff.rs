@@ -0,0 +1,106 @@
+use yew::prelude::*;
+use crate::app::{GridCell, Tab, TabContent};
+
+/// The two grid tabs picked for comparison, by tab index.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GridDiff {
+    pub before: Option<usize>,
+    pub after: Option<usize>,
+}
+
+impl GridDiff {
+    pub fn tabs(&self) -> Option<(usize, usize)> {
+        self.before.zip(self.after)
+    }
+}
+
+/// A tab with the grid of `after`, where the cells that differ from `before` in text or colour are highlighted.
+/// Hovering a changed cell shows both versions. Fails if the grids don't have the same dimensions.
+pub fn diff_tab(before: &Tab, after: &Tab) -> Result<Tab, String> {
+    let dimensions = |tab: &Tab| tab.grid().iter().map(Vec::len).collect::<Vec<_>>();
+    if before.grid().is_empty() || after.grid().is_empty() {
+        return Err("Both tabs need a grid to compare".to_string());
+    }
+    if dimensions(before) != dimensions(after) {
+        return Err(format!(
+            "\"{}\" and \"{}\" have grids of different sizes, {}x{} and {}x{}",
+            before.title, after.title,
+            before.grid()[0].len(), before.grid().len(),
+            after.grid()[0].len(), after.grid().len(),
+        ));
+    }
+    let mut changed = 0;
+    let grid = before.grid().iter().zip(after.grid()).map(|(before_row, after_row)| {
+        before_row.iter().zip(after_row).map(|(before_cell, after_cell)| {
+            if before_cell == after_cell {
+                return GridCell {
+                    text: after_cell.text.clone(),
+                    class: classes!("text-gray-500"),
+                    title: after_cell.title.clone(),
+                };
+            }
+            changed += 1;
+            GridCell {
+                text: after_cell.text.clone(),
+                class: classes!("bg-red-700", "text-gray-100"),
+                title: format!("Before: {}\nAfter: {}", describe(before_cell), describe(after_cell)),
+            }
+        }).collect()
+    }).collect();
+    Ok(Tab {
+        title: format!("{} → {}", before.title, after.title),
+        strings: vec![format!("{} cells changed", changed)],
+        content: TabContent::Grid(grid),
+    })
+}
+
+fn describe(cell: &GridCell) -> String {
+    if cell.title.is_empty() {
+        format!("\"{}\"", cell.text)
+    } else {
+        format!("\"{}\" ({})", cell.text, cell.title)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_tab(title: &str, rows: &[&str]) -> Tab {
+        Tab {
+            title: title.to_string(),
+            strings: vec![],
+            content: TabContent::Grid(rows.iter().map(|row| row.chars().map(|character| GridCell {
+                text: character.to_string(),
+                class: classes!("bg-gray-700"),
+                title: String::new(),
+            }).collect()).collect()),
+        }
+    }
+
+    #[test]
+    fn diff_counts_changed_cells() {
+        let diff = diff_tab(&grid_tab("Before", &["ab", "cd"]), &grid_tab("After", &["ab", "xy"])).unwrap();
+        assert_eq!(diff.title, "Before → After");
+        assert_eq!(diff.strings, vec!["2 cells changed".to_string()]);
+        let changed = diff.grid().iter().flatten().filter(|cell| cell.class.contains("bg-red-700")).map(|cell| cell.title.as_str()).collect::<Vec<_>>();
+        assert_eq!(changed, vec!["Before: \"c\"\nAfter: \"x\"", "Before: \"d\"\nAfter: \"y\""]);
+        let same = diff_tab(&grid_tab("Before", &["ab"]), &grid_tab("After", &["ab"])).unwrap();
+        assert_eq!(same.strings, vec!["0 cells changed".to_string()]);
+    }
+
+    #[test]
+    fn diff_needs_grids_of_the_same_size() {
+        assert_eq!(
+            diff_tab(&grid_tab("Before", &["ab", "cd"]), &grid_tab("After", &["abc", "def"])).err(),
+            Some("\"Before\" and \"After\" have grids of different sizes, 2x2 and 3x2".to_string()),
+        );
+        assert!(diff_tab(&grid_tab("Before", &["ab", "cd"]), &grid_tab("After", &["ab"])).is_err());
+        let strings = Tab {
+            title: "Strings".to_string(),
+            strings: vec!["ab".to_string()],
+            content: TabContent::Strings,
+        };
+        assert_eq!(diff_tab(&grid_tab("Before", &["ab"]), &strings).err(), Some("Both tabs need a grid to compare".to_string()));
+    }
+}
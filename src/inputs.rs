@@ -0,0 +1,132 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+/// Where every day used to store its input, before inputs were kept per day.
+const LOCAL_STORAGE_LEGACY_INPUT: &str = "INPUT";
+const DEFAULT_SLOT_NAME: &str = "real";
+
+/// A named input, with the parameters that differ from the defaults of the day when running it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InputSlot {
+    pub name: String,
+    pub input: String,
+    #[serde(default)]
+    pub parameters: Vec<(String, i64)>,
+}
+
+/// The named inputs saved for one day, and which one was used last.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DayInputs {
+    selected: usize,
+    slots: Vec<InputSlot>,
+}
+
+impl Default for DayInputs {
+    fn default() -> Self {
+        DayInputs {
+            selected: 0,
+            slots: vec![InputSlot {
+                name: DEFAULT_SLOT_NAME.to_string(),
+                input: String::new(),
+                parameters: Vec::new(),
+            }],
+        }
+    }
+}
+
+impl DayInputs {
+    /// Loads the inputs of a day. A day without any gets the old shared input, if it is still around.
+    pub fn load(day_number: u32) -> Self {
+        LocalStorage::get::<DayInputs>(Self::key(day_number))
+            .ok()
+            .filter(|inputs| !inputs.slots.is_empty())
+            .map(|mut inputs| {
+                inputs.selected = inputs.selected.min(inputs.slots.len() - 1);
+                inputs
+            })
+            .unwrap_or_else(|| {
+                let mut inputs = DayInputs::default();
+                if let Ok(Some(legacy)) = LocalStorage::raw().get_item(LOCAL_STORAGE_LEGACY_INPUT) {
+                    inputs.slots[0].input = legacy;
+                    inputs.save(day_number);
+                    LocalStorage::delete(LOCAL_STORAGE_LEGACY_INPUT);
+                }
+                inputs
+            })
+    }
+
+    pub fn save(&self, day_number: u32) {
+        if let Err(error) = LocalStorage::set(Self::key(day_number), self) {
+            log::error!("Failed to save inputs of day {}: {}", day_number, error);
+        }
+    }
+
+    fn key(day_number: u32) -> String {
+        format!("INPUTS_{}", day_number)
+    }
+
+    pub fn slots(&self) -> &[InputSlot] {
+        &self.slots
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn current(&self) -> &InputSlot {
+        &self.slots[self.selected]
+    }
+
+    pub fn set_input(&mut self, input: String) {
+        self.slots[self.selected].input = input;
+    }
+
+    pub fn set_parameter(&mut self, name: &str, value: i64) {
+        let parameters = &mut self.slots[self.selected].parameters;
+        match parameters.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, existing)) => *existing = value,
+            None => parameters.push((name.to_string(), value)),
+        }
+    }
+
+    pub fn set_parameters(&mut self, parameters: Vec<(String, i64)>) {
+        self.slots[self.selected].parameters = parameters;
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.slots.len() {
+            self.selected = index;
+        }
+    }
+
+    /// Adds an empty slot with a name that isn't taken yet and selects it.
+    pub fn add(&mut self) {
+        let name = (self.slots.len() + 1..)
+            .map(|number| format!("input {}", number))
+            .find(|name| self.slots.iter().all(|slot| slot.name != *name))
+            .unwrap_or_default();
+        self.slots.push(InputSlot {
+            name,
+            input: String::new(),
+            parameters: Vec::new(),
+        });
+        self.selected = self.slots.len() - 1;
+    }
+
+    pub fn rename(&mut self, name: String) {
+        let name = name.trim();
+        if !name.is_empty() {
+            self.slots[self.selected].name = name.to_string();
+        }
+    }
+
+    /// Removes the selected slot. The last slot is emptied instead, so there is always one to type in.
+    pub fn remove(&mut self) {
+        if self.slots.len() > 1 {
+            self.slots.remove(self.selected);
+            self.selected = self.selected.min(self.slots.len() - 1);
+        } else {
+            *self = DayInputs::default();
+        }
+    }
+}
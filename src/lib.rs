@@ -0,0 +1,66 @@
+extern crate core;
+
+pub mod app;
+#[cfg(not(target_arch = "wasm32"))]
+mod bench;
+pub mod chart;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+mod common;
+mod export;
+pub mod graph;
+mod grid;
+mod grid_canvas;
+mod grid_diff;
+mod inputs;
+mod panics;
+pub mod parameters;
+mod pathfinding;
+mod playback;
+mod search;
+pub mod table;
+pub mod timing;
+pub mod tree;
+pub mod worker;
+
+/// Declares the day modules and builds the registry the app lists in its sidebar, with every day's number and title.
+/// Days aren't discovered on their own: a new day is `day_template.rs` copied to `dayN.rs` plus its line in the list below.
+macro_rules! days {
+    ($($number:literal => $module:ident),* $(,)?) => {
+        $(mod $module;)*
+
+        pub fn get_days() -> Vec<app::Day> {
+            vec![
+                $(app::Day::new($number, $module::TITLE, $module::puzzle, $module::EXAMPLES, $module::PARAMETERS),)*
+            ]
+        }
+    };
+}
+
+days! {
+    1 => day1,
+    2 => day2,
+    3 => day3,
+    4 => day4,
+    5 => day5,
+    6 => day6,
+    7 => day7,
+    8 => day8,
+    9 => day9,
+    10 => day10,
+    11 => day11,
+    12 => day12,
+    13 => day13,
+    14 => day14,
+    15 => day15,
+    16 => day16,
+    17 => day17,
+    18 => day18,
+    19 => day19,
+    20 => day20,
+    21 => day21,
+    22 => day22,
+    23 => day23,
+    24 => day24,
+    25 => day25,
+}
@@ -0,0 +1,52 @@
+use std::panic::PanicHookInfo;
+
+/// The panic message followed by where it happened, e.g. "attempt to subtract with overflow at src/day20.rs:60:40".
+pub fn describe(info: &PanicHookInfo) -> String {
+    let payload = info.payload();
+    let message = payload.downcast_ref::<&str>().copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+    match info.location() {
+        Some(location) => format!("{} at {}:{}:{}", message, location.file(), location.line(), location.column()),
+        None => message.to_string(),
+    }
+}
+
+pub fn day_panicked(day_number: u32, description: &str) -> String {
+    format!("Day {} panicked: {}", day_number, description)
+}
+
+/// Runs `f`, turning a panic into its description.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn catch<T, F: FnOnce() -> T>(f: F) -> Result<T, String> {
+    use std::cell::{Cell, RefCell};
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::Once;
+
+    thread_local! {
+        static CATCHING: Cell<bool> = const { Cell::new(false) };
+        static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+    }
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CATCHING.get() {
+                LAST_PANIC.set(Some(describe(info)));
+            } else {
+                previous(info);
+            }
+        }));
+    });
+
+    let was_catching = CATCHING.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(was_catching);
+    result.map_err(|_| LAST_PANIC.take().unwrap_or_else(|| "Unknown panic".to_string()))
+}
+
+/// Panics abort in the browser, so there is nothing to catch. The worker reports them from its panic hook instead.
+#[cfg(target_arch = "wasm32")]
+pub fn catch<T, F: FnOnce() -> T>(f: F) -> Result<T, String> {
+    Ok(f())
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// A number a day reads at run time instead of hardcoding it, because the examples use other values than the real input.
+#[derive(Clone, Copy, Debug)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default: i64,
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Parameter {
+    /// Checks what a day declares when it is registered: names are unique and every default is in its range.
+    pub fn check_declared(declared: &[Parameter]) -> Result<(), String> {
+        for (index, parameter) in declared.iter().enumerate() {
+            if declared[..index].iter().any(|earlier| earlier.name == parameter.name) {
+                return Err(format!("Parameter '{}' is declared twice", parameter.name));
+            }
+            if parameter.min > parameter.default || parameter.default > parameter.max {
+                return Err(format!("Parameter '{}' has default {} outside of {}..={}", parameter.name, parameter.default, parameter.min, parameter.max));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The value of every parameter a day declares, for one run.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Parameters {
+    values: Vec<ParameterValue>,
+}
+
+/// The declared range travels along with the value, so `get` can check it against the type asked for.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct ParameterValue {
+    name: String,
+    value: i64,
+    min: i64,
+    max: i64,
+}
+
+impl Parameters {
+    /// Every declared parameter at its default.
+    pub fn new(declared: &[Parameter]) -> Self {
+        Parameters {
+            values: declared.iter().map(|parameter| ParameterValue {
+                name: parameter.name.to_string(),
+                value: parameter.default,
+                min: parameter.min,
+                max: parameter.max,
+            }).collect(),
+        }
+    }
+
+    /// The defaults with the given values set on top. Values that aren't declared or are out of range are skipped.
+    pub fn with_overrides<'a, I>(declared: &[Parameter], overrides: I) -> Self
+        where I: IntoIterator<Item=(&'a str, i64)> {
+        let mut parameters = Self::new(declared);
+        for (name, value) in overrides {
+            let _ = parameters.set(name, value);
+        }
+        parameters
+    }
+
+    pub fn set(&mut self, name: &str, value: i64) -> Result<(), String> {
+        let parameter = self.values.iter_mut().find(|parameter| parameter.name == name)
+            .ok_or_else(|| format!("Unknown parameter '{}'", name))?;
+        if value < parameter.min || value > parameter.max {
+            return Err(format!("Parameter '{}' must be between {} and {}", name, parameter.min, parameter.max));
+        }
+        parameter.value = value;
+        Ok(())
+    }
+
+    /// The value of a parameter the day declared.
+    /// Its whole declared range has to fit `T`, so asking for an undeclared name or a type that's too small
+    /// fails on every run, including the example tests, rather than only for some values.
+    pub fn get<T: TryFrom<i64>>(&self, name: &str) -> T {
+        let parameter = self.values.iter().find(|parameter| parameter.name == name)
+            .unwrap_or_else(|| panic!("Parameter '{}' isn't declared", name));
+        let fits = |value: i64| T::try_from(value).ok();
+        match (fits(parameter.min), fits(parameter.max), fits(parameter.value)) {
+            (Some(_), Some(_), Some(value)) => value,
+            _ => panic!("Parameter '{}' is declared as {}..={}, which doesn't fit {}", name, parameter.min, parameter.max, std::any::type_name::<T>()),
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use crate::grid::{Coord, Grid};
+
+/// A shortest path search over nodes like coordinates, or coordinates with a direction.
+/// It keeps every predecessor a node can be reached from at its cheapest cost, so all shortest paths can be followed back.
+/// Step through it to watch it expand, or `run` it to the end.
+#[derive(Clone, Debug)]
+pub struct Search<N> {
+    /// The cheapest cost found so far for every node that was reached
+    costs: HashMap<N, u64>,
+    predecessors: HashMap<N, Vec<N>>,
+    settled: HashSet<N>,
+    /// Priority, cost and node. Entries whose cost has been improved since are skipped when they come up
+    queue: BinaryHeap<Reverse<(u64, u64, N)>>,
+    expansions: usize,
+}
+
+impl<N: Copy + Eq + Hash + Ord> Search<N> {
+    pub fn new(starts: impl IntoIterator<Item = N>) -> Self {
+        let mut search = Search {
+            costs: HashMap::new(),
+            predecessors: HashMap::new(),
+            settled: HashSet::new(),
+            queue: BinaryHeap::new(),
+            expansions: 0,
+        };
+        for start in starts {
+            search.costs.insert(start, 0);
+            search.queue.push(Reverse((0, 0, start)));
+        }
+        search
+    }
+
+    /// Settles the cheapest node that isn't settled yet and adds its successors with the cost of moving there.
+    /// The heuristic makes it A*, it must never overestimate the remaining cost. Returns the node and its cost, or `None` when there is nothing left.
+    pub fn step<I, S, H>(&mut self, mut successors: S, heuristic: H) -> Option<(N, u64)>
+        where I: IntoIterator<Item = (N, u64)>, S: FnMut(N) -> I, H: Fn(N) -> u64 {
+        let (node, cost) = self.pop()?;
+        self.settled.insert(node);
+        self.expansions += 1;
+        for (next, move_cost) in successors(node) {
+            let next_cost = cost + move_cost;
+            match self.costs.get(&next) {
+                // Checked first, or moves that cost nothing would make settled nodes each other's predecessors
+                _ if self.settled.contains(&next) => {}
+                Some(known) if *known < next_cost => {}
+                Some(known) if *known == next_cost => {
+                    self.predecessors.entry(next).or_default().push(node);
+                }
+                _ => {
+                    self.costs.insert(next, next_cost);
+                    self.predecessors.insert(next, vec![node]);
+                    self.queue.push(Reverse((next_cost + heuristic(next), next_cost, next)));
+                }
+            }
+        }
+        Some((node, cost))
+    }
+
+    /// Steps until every node is settled, or until no end can be reached more cheaply than the cheapest end found.
+    /// Ends with the same cost are all settled, so all of their shortest paths are known.
+    /// `on_step` sees the search after every step, with the node that was settled and its cost.
+    pub fn run<I, S, H, E, O>(mut self, mut successors: S, heuristic: H, is_end: E, mut on_step: O) -> Self
+        where I: IntoIterator<Item = (N, u64)>, S: FnMut(N) -> I, H: Fn(N) -> u64, E: Fn(N) -> bool, O: FnMut(&Self, N, u64) {
+        let mut end_cost = None;
+        while let Some(priority) = self.next_priority() {
+            if end_cost.is_some_and(|end_cost| priority > end_cost) {
+                break;
+            }
+            let Some((node, cost)) = self.step(&mut successors, &heuristic) else {
+                break;
+            };
+            if end_cost.is_none() && is_end(node) {
+                end_cost = Some(cost);
+            }
+            on_step(&self, node, cost);
+        }
+        self
+    }
+
+    /// The priority of the node that is settled next, skipping queue entries that are out of date.
+    /// Without a heuristic that is its cost.
+    pub fn next_priority(&mut self) -> Option<u64> {
+        while let Some(Reverse((priority, cost, node))) = self.queue.peek() {
+            if self.settled.contains(node) || self.costs.get(node).is_some_and(|known| *known < *cost) {
+                self.queue.pop();
+            } else {
+                return Some(*priority);
+            }
+        }
+        None
+    }
+
+    fn pop(&mut self) -> Option<(N, u64)> {
+        self.next_priority()?;
+        self.queue.pop().map(|Reverse((_, cost, node))| (node, cost))
+    }
+
+    /// The cheapest cost to every node that was reached, settled or not.
+    pub fn costs(&self) -> &HashMap<N, u64> {
+        &self.costs
+    }
+
+    pub fn cost(&self, node: N) -> Option<u64> {
+        self.costs.get(&node).copied()
+    }
+
+    /// The nodes a node can be reached from at its cheapest cost.
+    pub fn predecessors(&self, node: N) -> &[N] {
+        self.predecessors.get(&node).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn is_settled(&self, node: N) -> bool {
+        self.settled.contains(&node)
+    }
+
+    /// The nodes whose cost is final.
+    pub fn settled(&self) -> impl Iterator<Item = N> + '_ {
+        self.settled.iter().copied()
+    }
+
+    /// The nodes that were reached but aren't settled yet.
+    pub fn frontier(&self) -> impl Iterator<Item = N> + '_ {
+        self.costs.keys().filter(|node| !self.settled.contains(node)).copied()
+    }
+
+    /// How many nodes have been settled.
+    pub fn expansions(&self) -> usize {
+        self.expansions
+    }
+
+    /// The cheapest of the ends that were reached.
+    pub fn cheapest(&self, ends: impl IntoIterator<Item = N>) -> Option<(N, u64)> {
+        ends.into_iter().filter_map(|end| self.cost(end).map(|cost| (end, cost))).min_by_key(|(_, cost)| *cost)
+    }
+
+    /// One of the shortest paths to `end`, from a start to `end`.
+    pub fn path_to(&self, end: N) -> Option<Vec<N>> {
+        self.cost(end)?;
+        let mut path = vec![end];
+        while let Some(previous) = self.predecessors(path[path.len() - 1]).first() {
+            path.push(*previous);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every node on any shortest path to the cheapest of the ends.
+    pub fn on_shortest_paths(&self, ends: impl IntoIterator<Item = N>) -> HashSet<N> {
+        let ends = ends.into_iter().collect::<Vec<_>>();
+        let Some((_, cheapest)) = self.cheapest(ends.iter().copied()) else {
+            return HashSet::new();
+        };
+        let mut remaining = ends.into_iter().filter(|end| self.cost(*end) == Some(cheapest)).collect::<Vec<_>>();
+        let mut on_paths = HashSet::new();
+        while let Some(node) = remaining.pop() {
+            if on_paths.insert(node) {
+                remaining.extend(self.predecessors(node));
+            }
+        }
+        on_paths
+    }
+}
+
+/// Breadth first search through the cells for which `passable` holds, stopping once `end` is reached, if given.
+/// Every move costs 1, so the costs are the number of steps from the start.
+pub fn bfs<T>(grid: &Grid<T>, start: Coord, end: Option<Coord>, passable: impl Fn(&T) -> bool) -> Search<Coord> {
+    let mut search = Search::new([]);
+    if !grid.get(start).is_some_and(&passable) {
+        return search;
+    }
+    search.costs.insert(start, 0);
+    let mut queue = VecDeque::from([start]);
+    while let Some(coord) = queue.pop_front() {
+        let cost = search.costs[&coord];
+        if end.is_some_and(|end| search.costs.get(&end).is_some_and(|end_cost| *end_cost < cost)) {
+            break;
+        }
+        search.settled.insert(coord);
+        search.expansions += 1;
+        for (next, cell) in grid.orthogonal_neighbours(coord) {
+            if !passable(cell) {
+                continue;
+            }
+            match search.costs.get(&next) {
+                Some(known) if *known == cost + 1 => search.predecessors.entry(next).or_default().push(coord),
+                Some(_) => {}
+                None => {
+                    search.costs.insert(next, cost + 1);
+                    search.predecessors.insert(next, vec![coord]);
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    search
+}
+
+/// Dijkstra through the grid, where `cost` gives the cost of moving onto a cell, or `None` if it can't be entered.
+/// Stops once `end` is settled, if given.
+#[allow(dead_code)] // No day moves through a grid with costs yet
+pub fn dijkstra<T>(grid: &Grid<T>, start: Coord, end: Option<Coord>, cost: impl Fn(&T) -> Option<u64>) -> Search<Coord> {
+    let starts = grid.get(start).and_then(&cost).map(|_| start);
+    Search::new(starts).run(|coord| grid_moves(grid, coord, &cost), |_| 0, |coord| Some(coord) == end, |_, _, _| {})
+}
+
+/// A* from `start` to `end` through the grid, with the same costs as `dijkstra`.
+/// The heuristic is the Manhattan distance, so every move has to cost at least 1.
+#[allow(dead_code)] // Like `dijkstra`
+pub fn astar<T>(grid: &Grid<T>, start: Coord, end: Coord, cost: impl Fn(&T) -> Option<u64>) -> Search<Coord> {
+    let starts = grid.get(start).and_then(&cost).map(|_| start);
+    Search::new(starts).run(|coord| grid_moves(grid, coord, &cost), |coord| coord.manhattan(&end), |coord| coord == end, |_, _, _| {})
+}
+
+#[allow(dead_code)] // Used by `dijkstra` and `astar`
+fn grid_moves<T>(grid: &Grid<T>, coord: Coord, cost: &impl Fn(&T) -> Option<u64>) -> Vec<(Coord, u64)> {
+    grid.orthogonal_neighbours(coord).filter_map(|(next, cell)| cost(cell).map(|cost| (next, cost))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+.....
+.##..
+...#.
+.#...
+";
+
+    fn maze() -> Grid<bool> {
+        Grid::parse(MAZE, |character| character == '.').unwrap()
+    }
+
+    #[test]
+    fn bfs_counts_steps() {
+        let search = bfs(&maze(), Coord::new(0, 0), None, |open| *open);
+        assert_eq!(search.cost(Coord::new(4, 3)), Some(7));
+        assert_eq!(search.cost(Coord::new(1, 1)), None);
+        assert_eq!(search.path_to(Coord::new(4, 3)).map(|path| path.len()), Some(8));
+    }
+
+    #[test]
+    fn all_shortest_paths_are_kept() {
+        // Around the wall block either way is as short
+        let search = dijkstra(&maze(), Coord::new(0, 0), Some(Coord::new(4, 3)), |open| open.then_some(1));
+        assert_eq!(search.cost(Coord::new(4, 3)), Some(7));
+        let on_paths = search.on_shortest_paths([Coord::new(4, 3)]);
+        assert!(on_paths.contains(&Coord::new(4, 0)));
+        assert!(on_paths.contains(&Coord::new(0, 2)));
+        assert!(!on_paths.contains(&Coord::new(0, 3)));
+    }
+
+    #[test]
+    fn astar_agrees_with_dijkstra() {
+        let grid = maze();
+        let cost = |open: &bool| open.then_some(2);
+        let (start, end) = (Coord::new(0, 3), Coord::new(4, 0));
+        let dijkstra = dijkstra(&grid, start, Some(end), cost);
+        let astar = astar(&grid, start, end, cost);
+        assert_eq!(astar.cost(end), dijkstra.cost(end));
+        assert!(astar.expansions() <= dijkstra.expansions());
+        assert_eq!(astar.on_shortest_paths([end]), dijkstra.on_shortest_paths([end]));
+    }
+
+    #[test]
+    fn free_moves_end() {
+        let grid = Grid::parse("..\n", |character| character == '.').unwrap();
+        let search = dijkstra(&grid, Coord::new(0, 0), None, |_| Some(0));
+        assert_eq!(search.cost(Coord::new(1, 0)), Some(0));
+        assert_eq!(search.path_to(Coord::new(1, 0)), Some(vec![Coord::new(0, 0), Coord::new(1, 0)]));
+        assert_eq!(search.path_to(Coord::new(0, 0)), Some(vec![Coord::new(0, 0)]));
+    }
+}
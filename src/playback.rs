@@ -0,0 +1,99 @@
+use gloo::timers::callback::Interval;
+use yew::html::Scope;
+use crate::app::{App, AppMessage, TabSeries};
+
+const DEFAULT_FRAMES_PER_SECOND: u32 = 10;
+pub const MAX_FRAMES_PER_SECOND: u32 = 60;
+
+/// Where the animation player is in one of the tab series of a diagnostic.
+pub struct Playback {
+    series: usize,
+    frame: usize,
+    frames_per_second: u32,
+    /// Ticks while playing, dropping it stops the animation
+    interval: Option<Interval>,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Playback {
+            series: 0,
+            frame: 0,
+            frames_per_second: DEFAULT_FRAMES_PER_SECOND,
+            interval: None,
+        }
+    }
+}
+
+impl Playback {
+    /// Goes back to the first frame of the first series, for a new diagnostic. Keeps the speed.
+    pub fn reset(&mut self) {
+        self.series = 0;
+        self.frame = 0;
+        self.interval = None;
+    }
+
+    pub fn series(&self) -> usize {
+        self.series
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn frames_per_second(&self) -> u32 {
+        self.frames_per_second
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.interval.is_some()
+    }
+
+    /// The tab to show for the current frame.
+    pub fn tab(&self, series: &[TabSeries]) -> Option<usize> {
+        series.get(self.series).and_then(|series| series.tabs.get(self.frame)).copied()
+    }
+
+    pub fn select(&mut self, series: usize) {
+        self.series = series;
+        self.frame = 0;
+        self.interval = None;
+    }
+
+    pub fn seek(&mut self, frame: usize) {
+        self.frame = frame;
+    }
+
+    /// Starts playing, from the start if the last frame was reached.
+    pub fn play(&mut self, series: &[TabSeries], link: &Scope<App>) {
+        let frames = series.get(self.series).map(|series| series.tabs.len()).unwrap_or(0);
+        if self.frame + 1 >= frames {
+            self.frame = 0;
+        }
+        let link = link.clone();
+        self.interval = Some(Interval::new(1000 / self.frames_per_second, move || link.send_message(AppMessage::PlaybackTick)));
+    }
+
+    pub fn pause(&mut self) {
+        self.interval = None;
+    }
+
+    pub fn set_speed(&mut self, frames_per_second: u32, series: &[TabSeries], link: &Scope<App>) {
+        self.frames_per_second = frames_per_second.clamp(1, MAX_FRAMES_PER_SECOND);
+        if self.is_playing() {
+            self.play(series, link);
+        }
+    }
+
+    /// Moves to the next frame. Stops and returns false at the end of the series.
+    pub fn advance(&mut self, series: &[TabSeries]) -> bool {
+        let frames = series.get(self.series).map(|series| series.tabs.len()).unwrap_or(0);
+        if self.frame + 1 < frames {
+            self.frame += 1;
+            true
+        } else {
+            self.pause();
+            false
+        }
+    }
+}
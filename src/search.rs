@@ -0,0 +1,56 @@
+use regex::Regex;
+use yew::prelude::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SearchMode {
+    /// Case insensitive text
+    #[default]
+    Substring,
+    Regex,
+}
+
+/// What the user searches the string tabs for. The query stays when switching tabs, so every tab button can show its matches.
+#[derive(Clone, Debug, Default)]
+pub struct Search {
+    pub query: String,
+    pub mode: SearchMode,
+    pub only_matching: bool,
+}
+
+impl Search {
+    /// The compiled query, `None` if there is nothing to search for.
+    pub fn matcher(&self) -> Result<Option<Regex>, String> {
+        if self.query.is_empty() {
+            return Ok(None);
+        }
+        let pattern = match self.mode {
+            SearchMode::Substring => format!("(?i){}", regex::escape(&self.query)),
+            SearchMode::Regex => self.query.clone(),
+        };
+        Regex::new(&pattern).map(Some).map_err(|error| error.to_string())
+    }
+}
+
+/// How many times the matcher matches in all of the lines.
+pub fn count_matches(matcher: &Regex, lines: &[String]) -> usize {
+    lines.iter().map(|line| matcher.find_iter(line).filter(|found| !found.is_empty()).count()).sum()
+}
+
+/// The line with every match marked, or `None` if nothing matches and only matching lines are shown.
+pub fn view_line(line: &str, matcher: Option<&Regex>, only_matching: bool) -> Option<Html> {
+    let Some(matcher) = matcher else {
+        return Some(html! { <div>{line}</div> });
+    };
+    let mut parts = Vec::new();
+    let mut end = 0;
+    for found in matcher.find_iter(line).filter(|found| !found.is_empty()) {
+        parts.push(html! { {&line[end..found.start()]} });
+        parts.push(html! { <mark class="bg-yellow-300 text-gray-900">{found.as_str()}</mark> });
+        end = found.end();
+    }
+    if parts.is_empty() && only_matching {
+        return None;
+    }
+    parts.push(html! { {&line[end..]} });
+    Some(html! { <div>{for parts}</div> })
+}
@@ -0,0 +1,387 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use crate::app::{merge, Tab, TabContent};
+use crate::export::csv_line;
+
+const PAGE_SIZES: [usize; 3] = [25, 100, 500];
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ColumnKind {
+    Integer,
+    Float,
+    Text,
+}
+
+impl ColumnKind {
+    /// Every column can hold `Empty`. Integer columns also take the text that integers too big for an i64 turn into.
+    fn accepts(self, value: &TableValue) -> bool {
+        matches!((self, value),
+            (_, TableValue::Empty)
+            | (ColumnKind::Integer, TableValue::Integer(_) | TableValue::Text(_))
+            | (ColumnKind::Float, TableValue::Float(_))
+            | (ColumnKind::Text, TableValue::Text(_)))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TableColumn {
+    pub name: String,
+    pub kind: ColumnKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TableValue {
+    Empty,
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+/// Rows of typed values for a table tab.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Table {
+    pub columns: Vec<TableColumn>,
+    pub rows: Vec<Vec<TableValue>>,
+}
+
+impl Table {
+    pub fn new(columns: &[(&str, ColumnKind)]) -> Self {
+        Table {
+            columns: columns.iter().map(|(name, kind)| TableColumn {
+                name: name.to_string(),
+                kind: *kind,
+            }).collect(),
+            rows: vec![],
+        }
+    }
+
+    /// Panics if the row doesn't have a value of the right kind for every column.
+    pub fn push(&mut self, row: Vec<TableValue>) {
+        assert_eq!(row.len(), self.columns.len(), "Table row {} has {} values for {} columns", self.rows.len() + 1, row.len(), self.columns.len());
+        for (column, value) in self.columns.iter().zip(row.iter()) {
+            assert!(column.kind.accepts(value), "Table row {} has {:?} in {:?} column \"{}\"", self.rows.len() + 1, value, column.kind, column.name);
+        }
+        self.rows.push(row);
+    }
+
+    /// A header line and a line per row with the values separated by tabs, for the command line.
+    pub fn to_plain_text(&self) -> Vec<String> {
+        let header = self.columns.iter().map(|column| column.name.as_str()).collect::<Vec<_>>().join("\t");
+        let rows = self.rows.iter().map(|row| row.iter().map(|value| value.to_string()).collect::<Vec<_>>().join("\t"));
+        [header].into_iter().chain(rows).collect()
+    }
+
+    /// A header line and a line per row.
+    pub fn to_csv(&self) -> String {
+        let header = csv_line(self.columns.iter().map(|column| column.name.as_str()));
+        let rows = self.rows.iter().map(|row| csv_line(row.iter().map(|value| value.to_string())));
+        [header].into_iter().chain(rows).map(|line| line + "\n").collect()
+    }
+}
+
+impl TableValue {
+    /// Empty values first, then numbers, then text.
+    fn compare(&self, other: &TableValue) -> Ordering {
+        match (self, other) {
+            (TableValue::Integer(first), TableValue::Integer(second)) => first.cmp(second),
+            (TableValue::Text(first), TableValue::Text(second)) => first.cmp(second),
+            (first, second) => match (first.number(), second.number()) {
+                (Some(first), Some(second)) => first.total_cmp(&second),
+                _ => first.rank().cmp(&second.rank()),
+            },
+        }
+    }
+
+    fn number(&self) -> Option<f64> {
+        match self {
+            TableValue::Integer(integer) => Some(*integer as f64),
+            TableValue::Float(float) => Some(*float),
+            _ => None,
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            TableValue::Empty => 0,
+            TableValue::Integer(_) | TableValue::Float(_) => 1,
+            TableValue::Text(_) => 2,
+        }
+    }
+}
+
+impl Display for TableValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableValue::Empty => Ok(()),
+            TableValue::Integer(integer) => write!(f, "{}", integer),
+            TableValue::Float(float) => write!(f, "{}", float),
+            TableValue::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+macro_rules! integer_values {
+    ($($integer:ty),*) => {$(
+        impl From<$integer> for TableValue {
+            /// Integers that don't fit an i64 become their text.
+            fn from(value: $integer) -> Self {
+                i64::try_from(value).map_or_else(|_| TableValue::Text(value.to_string()), TableValue::Integer)
+            }
+        }
+    )*};
+}
+
+integer_values!(i32, i64, u8, u32, u64, usize);
+
+impl From<f64> for TableValue {
+    fn from(value: f64) -> Self {
+        TableValue::Float(value)
+    }
+}
+
+impl From<String> for TableValue {
+    fn from(value: String) -> Self {
+        TableValue::Text(value)
+    }
+}
+
+impl From<&str> for TableValue {
+    fn from(value: &str) -> Self {
+        TableValue::Text(value.to_string())
+    }
+}
+
+impl<T: Into<TableValue>> From<Option<T>> for TableValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(TableValue::Empty, Into::into)
+    }
+}
+
+#[derive(Properties)]
+pub struct TableViewProps {
+    pub tab: Rc<Tab>,
+}
+
+impl PartialEq for TableViewProps {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.tab, &other.tab)
+    }
+}
+
+pub enum TableViewMessage {
+    Sort(usize),
+    Filter(String),
+    Page(usize),
+    PageSize(usize),
+}
+
+/// Shows the table of a tab a page at a time. Click a header to sort, type to filter the rows.
+pub struct TableView {
+    /// Column and whether it's ascending
+    sort: Option<(usize, bool)>,
+    filter: String,
+    page: usize,
+    page_size: usize,
+    /// Indices of the rows that pass the filter, in sorted order
+    visible: Vec<usize>,
+}
+
+impl Component for TableView {
+    type Message = TableViewMessage;
+    type Properties = TableViewProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut table_view = TableView {
+            sort: None,
+            filter: String::new(),
+            page: 0,
+            page_size: PAGE_SIZES[0],
+            visible: vec![],
+        };
+        table_view.refresh(&ctx.props().tab);
+        table_view
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, message: Self::Message) -> bool {
+        match message {
+            TableViewMessage::Sort(column) => {
+                // Ascending, descending, then back to the order of the day
+                self.sort = match self.sort {
+                    Some((sorted, true)) if sorted == column => Some((column, false)),
+                    Some((sorted, false)) if sorted == column => None,
+                    _ => Some((column, true)),
+                };
+                self.refresh(&ctx.props().tab);
+            }
+            TableViewMessage::Filter(filter) => {
+                self.filter = filter;
+                self.page = 0;
+                self.refresh(&ctx.props().tab);
+            }
+            TableViewMessage::Page(page) => {
+                self.page = page;
+            }
+            TableViewMessage::PageSize(page_size) => {
+                self.page = self.page * self.page_size / page_size;
+                self.page_size = page_size;
+            }
+        }
+        true
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.refresh(&ctx.props().tab);
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let TabContent::Table(table) = &ctx.props().tab.content else {
+            return html! {};
+        };
+        let link = ctx.link();
+        let pages = self.visible.len().div_ceil(self.page_size).max(1);
+        let page = self.page.min(pages - 1);
+        let first = page * self.page_size;
+        let shown = &self.visible[first.min(self.visible.len())..(first + self.page_size).min(self.visible.len())];
+        let numeric = |column: usize| table.columns.get(column).is_some_and(|column| column.kind != ColumnKind::Text);
+        html! {
+            <div class="flex flex-col gap-2">
+                <div class="flex flex-row items-center gap-2">
+                    <input type="search" placeholder="Filter rows" value={self.filter.clone()} class="p-1 rounded-md bg-gray-700"
+                        oninput={link.callback(|event: InputEvent| TableViewMessage::Filter(event.target_unchecked_into::<HtmlInputElement>().value()))} />
+                    <div class="text-gray-400">
+                        {if self.visible.len() == table.rows.len() {
+                            format!("{} rows", table.rows.len())
+                        } else {
+                            format!("{} of {} rows", self.visible.len(), table.rows.len())
+                        }}
+                    </div>
+                </div>
+                <table class="w-fit font-mono">
+                    <thead>
+                        <tr class="border-b border-gray-400">
+                            {for table.columns.iter().enumerate().map(|(index, column)| html! {
+                                <th onclick={link.callback(move |_| TableViewMessage::Sort(index))}
+                                    class={classes!("px-2", "cursor-pointer", "select-none", if numeric(index) {"text-right"} else {"text-left"})}>
+                                    {&column.name}
+                                    {match self.sort {
+                                        Some((sorted, true)) if sorted == index => " ▲",
+                                        Some((sorted, false)) if sorted == index => " ▼",
+                                        _ => "",
+                                    }}
+                                </th>
+                            })}
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {for shown.iter().map(|row| html! {
+                            <tr class="hover:bg-gray-700">
+                                {for table.rows[*row].iter().enumerate().map(|(index, value)| html! {
+                                    <td class={classes!("px-2", if numeric(index) {"text-right"} else {"text-left"})}>{value.to_string()}</td>
+                                })}
+                            </tr>
+                        })}
+                    </tbody>
+                </table>
+                <div class="flex flex-row items-center gap-2">
+                    <button onclick={link.callback(move |_| TableViewMessage::Page(page.saturating_sub(1)))} disabled={page == 0}
+                        class="p-1 border border-gray-400 rounded-md disabled:text-gray-500">
+                        {"Previous page"}
+                    </button>
+                    <div>{format!("Page {} / {}", page + 1, pages)}</div>
+                    <button onclick={link.callback(move |_| TableViewMessage::Page(page + 1))} disabled={page + 1 >= pages}
+                        class="p-1 border border-gray-400 rounded-md disabled:text-gray-500">
+                        {"Next page"}
+                    </button>
+                    <div>{"Rows per page:"}</div>
+                    {for PAGE_SIZES.into_iter().map(|page_size| html! {
+                        <button onclick={link.callback(move |_| TableViewMessage::PageSize(page_size))} class={merge("p-1 border border-gray-400 rounded-md", &if self.page_size == page_size {classes!("bg-slate-700")} else {classes!("")})}>
+                            {page_size}
+                        </button>
+                    })}
+                </div>
+            </div>
+        }
+    }
+}
+
+impl TableView {
+    /// Applies the filter and the sort to the rows of the tab.
+    fn refresh(&mut self, tab: &Tab) {
+        let TabContent::Table(table) = &tab.content else {
+            self.visible = vec![];
+            return;
+        };
+        self.visible = matching_rows(table, &self.filter);
+        if let Some((column, ascending)) = self.sort {
+            self.visible.sort_by(|first, second| {
+                let ordering = match (table.rows[*first].get(column), table.rows[*second].get(column)) {
+                    (Some(first), Some(second)) => first.compare(second),
+                    (first, second) => first.is_some().cmp(&second.is_some()),
+                };
+                if ascending { ordering } else { ordering.reverse() }
+            });
+        }
+    }
+}
+
+/// Indices of the rows with a value that contains the filter, ignoring case.
+fn matching_rows(table: &Table, filter: &str) -> Vec<usize> {
+    let filter = filter.to_lowercase();
+    (0..table.rows.len()).filter(|row| {
+        filter.is_empty() || table.rows[*row].iter().any(|value| value.to_string().to_lowercase().contains(&filter))
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_sort_empty_then_numbers_then_text() {
+        let mut values = vec![
+            TableValue::from("b"),
+            TableValue::from(2.5),
+            TableValue::Empty,
+            TableValue::from("a"),
+            TableValue::from(3),
+            TableValue::from(-1),
+            TableValue::from(2u64),
+        ];
+        values.sort_by(TableValue::compare);
+        assert_eq!(values, vec![
+            TableValue::Empty,
+            TableValue::Integer(-1),
+            TableValue::Integer(2),
+            TableValue::Float(2.5),
+            TableValue::Integer(3),
+            TableValue::Text("a".to_string()),
+            TableValue::Text("b".to_string()),
+        ]);
+        assert_eq!(TableValue::from(u64::MAX), TableValue::Text(u64::MAX.to_string()));
+    }
+
+    #[test]
+    fn filter_ignores_case() {
+        let mut table = Table::new(&[("Name", ColumnKind::Text), ("Count", ColumnKind::Integer)]);
+        table.push(vec!["Alpha".into(), 10.into()]);
+        table.push(vec!["beta".into(), TableValue::Empty]);
+        table.push(vec!["GAMMA".into(), 110.into()]);
+        assert_eq!(matching_rows(&table, ""), vec![0, 1, 2]);
+        assert_eq!(matching_rows(&table, "a"), vec![0, 1, 2]);
+        assert_eq!(matching_rows(&table, "BeT"), vec![1]);
+        assert_eq!(matching_rows(&table, "10"), vec![0, 2]);
+        assert_eq!(matching_rows(&table, "delta"), Vec::<usize>::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "Table row 1 has Float(1.5) in Integer column \"Count\"")]
+    fn push_checks_column_kinds() {
+        let mut table = Table::new(&[("Count", ColumnKind::Integer)]);
+        table.push(vec![1.5.into()]);
+    }
+}
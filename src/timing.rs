@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+/// The phases a puzzle reports its time under.
+/// Work that both parts share is charged to silver, so gold only shows what it costs on top of that.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phase {
+    Parse,
+    Silver,
+    Gold,
+}
+
+/// Wall time of one run in milliseconds, split by phase.
+/// `total` also covers what happens outside the phases, like building diagnostic tabs.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Timings {
+    pub parse: f64,
+    pub silver: f64,
+    pub gold: f64,
+    pub total: f64,
+}
+
+impl Timings {
+    pub fn phases(&self) -> [(&'static str, f64); 4] {
+        [
+            ("parse", self.parse),
+            ("silver", self.silver),
+            ("gold", self.gold),
+            ("total", self.total),
+        ]
+    }
+}
+
+pub struct Stopwatch {
+    start: f64,
+    last: f64,
+    timings: Timings,
+}
+
+impl Stopwatch {
+    pub fn start() -> Self {
+        let start = now();
+        Stopwatch {
+            start,
+            last: start,
+            timings: Timings::default(),
+        }
+    }
+
+    /// Adds the time since the previous lap to `phase`. Can be called repeatedly, e.g. once per loop iteration.
+    pub fn lap(&mut self, phase: Phase) {
+        let now = now();
+        let elapsed = now - self.last;
+        self.last = now;
+        match phase {
+            Phase::Parse => self.timings.parse += elapsed,
+            Phase::Silver => self.timings.silver += elapsed,
+            Phase::Gold => self.timings.gold += elapsed,
+        }
+    }
+
+    pub fn finish(mut self) -> Timings {
+        self.timings.total = now() - self.start;
+        self.timings
+    }
+}
+
+pub fn format_duration(milliseconds: f64) -> String {
+    if milliseconds < 1.0 {
+        format!("{:.0} µs", milliseconds * 1000.0)
+    } else if milliseconds < 1000.0 {
+        format!("{:.2} ms", milliseconds)
+    } else {
+        format!("{:.2} s", milliseconds / 1000.0)
+    }
+}
+
+/// `Instant` panics in the browser, so there we read `performance.now()` from whichever global scope we run in.
+#[cfg(target_arch = "wasm32")]
+fn now() -> f64 {
+    use wasm_bindgen::JsCast;
+    js_sys::Reflect::get(&js_sys::global(), &"performance".into())
+        .ok()
+        .and_then(|performance| performance.dyn_into::<web_sys::Performance>().ok())
+        .map(|performance| performance.now())
+        .unwrap_or_else(js_sys::Date::now)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
@@ -0,0 +1,220 @@
+use std::rc::Rc;
+use indextree::{Arena, NodeId};
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+use crate::app::{Tab, TabContent};
+
+/// Labelled nodes with children, for a tree tab. Parents are always added before their children.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Tree {
+    pub nodes: Vec<TreeNode>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TreeNode {
+    pub label: String,
+    /// A number the day attaches to the node, like how many solutions were found below it
+    pub count: Option<u64>,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+impl Tree {
+    pub fn new() -> Self {
+        Tree::default()
+    }
+
+    /// Adds a node below `parent`, or a new root without one.
+    pub fn add(&mut self, parent: Option<usize>, label: String) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(TreeNode {
+            label,
+            count: None,
+            parent,
+            children: vec![],
+        });
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(index);
+        }
+        index
+    }
+
+    pub fn set_count(&mut self, node: usize, count: u64) {
+        self.nodes[node].count = Some(count);
+    }
+
+    /// Copies the tree below `root`, labelling every node with `label`.
+    pub fn from_arena<T, F: Fn(&T) -> String>(arena: &Arena<T>, root: NodeId, label: F) -> Self {
+        let mut tree = Tree::new();
+        let mut stack = vec![(root, None)];
+        while let Some((node_id, parent)) = stack.pop() {
+            let Some(node) = arena.get(node_id) else {
+                continue;
+            };
+            let index = tree.add(parent, label(node.get()));
+            // Reversed, so the children come off the stack in order
+            let children = node_id.children(arena).collect::<Vec<_>>();
+            stack.extend(children.into_iter().rev().map(|child| (child, Some(index))));
+        }
+        tree
+    }
+
+    pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+        self.nodes.iter().enumerate().filter(|(_, node)| node.parent.is_none()).map(|(index, _)| index)
+    }
+
+    /// How many nodes are below each node.
+    pub fn descendants(&self) -> Vec<usize> {
+        let mut descendants = vec![0; self.nodes.len()];
+        // Children come after their parents, so going backwards finishes every child before its parent
+        for (index, node) in self.nodes.iter().enumerate().rev() {
+            if let Some(parent) = node.parent {
+                descendants[parent] += descendants[index] + 1;
+            }
+        }
+        descendants
+    }
+
+    /// The nodes in outline order with their depth, skipping the children of nodes for which `expanded` is false.
+    fn outline(&self, expanded: impl Fn(usize) -> bool) -> Vec<(usize, usize)> {
+        let mut outline = Vec::new();
+        let mut stack = self.roots().map(|root| (root, 0)).collect::<Vec<_>>();
+        stack.reverse();
+        while let Some((node, depth)) = stack.pop() {
+            outline.push((node, depth));
+            if expanded(node) {
+                stack.extend(self.nodes[node].children.iter().rev().map(|child| (*child, depth + 1)));
+            }
+        }
+        outline
+    }
+
+    /// One indented line per node, for the command line.
+    pub fn to_plain_text(&self) -> Vec<String> {
+        self.outline(|_| true).into_iter().map(|(node, depth)| {
+            let node = &self.nodes[node];
+            match node.count {
+                Some(count) => format!("{}{} ({})", "  ".repeat(depth), node.label, count),
+                None => format!("{}{}", "  ".repeat(depth), node.label),
+            }
+        }).collect()
+    }
+}
+
+#[derive(Properties)]
+pub struct TreeViewProps {
+    pub tab: Rc<Tab>,
+}
+
+impl PartialEq for TreeViewProps {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.tab, &other.tab)
+    }
+}
+
+pub enum TreeViewMessage {
+    Toggle(usize),
+    ExpandAll,
+    CollapseAll,
+}
+
+/// Shows the tree of a tab as an outline, starting with only the roots open.
+pub struct TreeView {
+    expanded: Vec<bool>,
+    descendants: Vec<usize>,
+}
+
+impl Component for TreeView {
+    type Message = TreeViewMessage;
+    type Properties = TreeViewProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut tree_view = TreeView {
+            expanded: vec![],
+            descendants: vec![],
+        };
+        tree_view.load(&ctx.props().tab);
+        tree_view
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, message: Self::Message) -> bool {
+        match message {
+            TreeViewMessage::Toggle(node) => {
+                if let Some(expanded) = self.expanded.get_mut(node) {
+                    *expanded = !*expanded;
+                }
+            }
+            TreeViewMessage::ExpandAll => {
+                self.expanded.fill(true);
+            }
+            TreeViewMessage::CollapseAll => {
+                self.expanded.fill(false);
+            }
+        }
+        true
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.load(&ctx.props().tab);
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let TabContent::Tree(tree) = &ctx.props().tab.content else {
+            return html! {};
+        };
+        let link = ctx.link();
+        let outline = tree.outline(|node| self.expanded[node]);
+        html! {
+            <div class="flex flex-col gap-2">
+                <div class="flex flex-row items-center gap-2">
+                    <button onclick={link.callback(|_| TreeViewMessage::ExpandAll)} class="p-1 border border-gray-400 rounded-md">
+                        {"Expand all"}
+                    </button>
+                    <button onclick={link.callback(|_| TreeViewMessage::CollapseAll)} class="p-1 border border-gray-400 rounded-md">
+                        {"Collapse all"}
+                    </button>
+                    <div class="text-gray-400">{format!("{} nodes", tree.nodes.len())}</div>
+                </div>
+                <div class="font-mono">
+                    {for outline.into_iter().map(|(index, depth)| {
+                        let node = &tree.nodes[index];
+                        let has_children = !node.children.is_empty();
+                        html! {
+                            <div class="flex flex-row items-center gap-2 hover:bg-gray-700" style={format!("padding-left: {}rem", depth)}>
+                                {if has_children {
+                                    html! {
+                                        <button onclick={link.callback(move |_| TreeViewMessage::Toggle(index))} class="w-4">
+                                            {if self.expanded[index] {"▾"} else {"▸"}}
+                                        </button>
+                                    }
+                                } else {
+                                    html! { <div class="w-4"></div> }
+                                }}
+                                <div>{&node.label}</div>
+                                {for node.count.map(|count| html! {
+                                    <div class="px-1 rounded-md bg-slate-600">{count}</div>
+                                })}
+                                {if has_children && !self.expanded[index] {
+                                    html! { <div class="text-gray-400">{format!("{} below", self.descendants[index])}</div> }
+                                } else {
+                                    html! {}
+                                }}
+                            </div>
+                        }
+                    })}
+                </div>
+            </div>
+        }
+    }
+}
+
+impl TreeView {
+    fn load(&mut self, tab: &Tab) {
+        let TabContent::Tree(tree) = &tab.content else {
+            return;
+        };
+        self.expanded = tree.nodes.iter().map(|node| node.parent.is_none()).collect();
+        self.descendants = tree.descendants();
+    }
+}
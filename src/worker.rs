@@ -0,0 +1,160 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{DedicatedWorkerGlobalScope, ErrorEvent, MessageEvent, Worker};
+use yew::Callback;
+use crate::app::{Day, DayOutput, Diagnostic, Part, Tab, TabContent};
+use crate::panics;
+use crate::parameters::Parameters;
+use crate::timing::Timings;
+
+/// The loader trunk generates for the `worker` binary, see the worker link in `index.html`.
+const WORKER_LOADER: &str = "./worker_loader.js";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PuzzleRequest {
+    pub day_number: u32,
+    pub input: String,
+    pub part: Part,
+    pub parameters: Parameters,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum WorkerRequest {
+    Run(PuzzleRequest),
+    /// Builds a lazy tab of the last run.
+    RenderTab(usize),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum WorkerResponse {
+    Finished(PuzzleResponse),
+    TabRendered(usize, Tab),
+}
+
+/// `crashed` means the worker can't be used anymore and has to be replaced before the next run.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PuzzleResponse {
+    pub output: DayOutput,
+    pub timings: Timings,
+    pub crashed: bool,
+}
+
+impl PuzzleResponse {
+    fn crashed(message: String) -> Self {
+        PuzzleResponse {
+            output: DayOutput::error(message),
+            timings: Timings::default(),
+            crashed: true,
+        }
+    }
+}
+
+/// The day the worker is running, so the panic hook can say which one panicked.
+static RUNNING_DAY: AtomicU32 = AtomicU32::new(0);
+
+/// A web worker that runs puzzles off the UI thread.
+/// Dropping it terminates the worker, which is the only way to stop a puzzle that is stuck in a loop.
+pub struct PuzzleWorker {
+    worker: Worker,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_error: Closure<dyn FnMut(ErrorEvent)>,
+}
+
+impl PuzzleWorker {
+    pub fn spawn(on_output: Callback<WorkerResponse>) -> Result<Self, String> {
+        let worker = Worker::new(WORKER_LOADER).map_err(|error| format!("Failed to start puzzle worker: {:?}", error))?;
+        let on_error_output = on_output.clone();
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            let response = serde_wasm_bindgen::from_value(event.data()).unwrap_or_else(|error| WorkerResponse::Finished(PuzzleResponse {
+                output: DayOutput::error(format!("Failed to read puzzle output: {}", error)),
+                timings: Timings::default(),
+                crashed: false,
+            }));
+            on_output.emit(response);
+        });
+        // Panics are reported by the hook in `register`, this catches whatever else brings the worker down
+        let on_error = Closure::<dyn FnMut(ErrorEvent)>::new(move |event: ErrorEvent| {
+            event.prevent_default();
+            on_error_output.emit(WorkerResponse::Finished(PuzzleResponse::crashed(format!("The puzzle worker crashed: {}", event.message()))));
+        });
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        worker.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        Ok(PuzzleWorker {
+            worker,
+            _on_message: on_message,
+            _on_error: on_error,
+        })
+    }
+
+    pub fn send(&self, request: &WorkerRequest) -> Result<(), String> {
+        let message = serde_wasm_bindgen::to_value(request).map_err(|error| format!("Failed to send puzzle input: {}", error))?;
+        self.worker.post_message(&message).map_err(|error| format!("Failed to send puzzle input: {:?}", error))
+    }
+}
+
+impl Drop for PuzzleWorker {
+    fn drop(&mut self) {
+        // Events that are already queued must not reach the closures dropped with this struct
+        self.worker.set_onmessage(None);
+        self.worker.set_onerror(None);
+        self.worker.terminate();
+    }
+}
+
+/// Entry point of the worker binary. Answers every request by running the day and posting its output back.
+pub fn register(days: Vec<Day>) {
+    // A panic aborts the worker, so the hook sends the error output before that happens
+    std::panic::set_hook(Box::new(|info| {
+        console_error_panic_hook::hook(info);
+        let message = panics::day_panicked(RUNNING_DAY.load(Ordering::Relaxed), &panics::describe(info));
+        respond(&WorkerResponse::Finished(PuzzleResponse::crashed(message)));
+    }));
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    // Keeps the render functions of the lazy tabs, which can't be sent to the app
+    let mut last_diagnostic = Diagnostic::default();
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        let (mut output, timings) = match serde_wasm_bindgen::from_value::<WorkerRequest>(event.data()) {
+            Ok(WorkerRequest::Run(request)) => {
+                if let Some(day) = days.iter().find(|day| day.number == request.day_number) {
+                    RUNNING_DAY.store(day.number, Ordering::Relaxed);
+                    day.run(&request.input, request.part, &request.parameters)
+                } else {
+                    (DayOutput {
+                        silver_output: "Day not found".to_string(),
+                        gold_output: "Day not found".to_string(),
+                        diagnostic: Default::default(),
+                    }, Timings::default())
+                }
+            }
+            Ok(WorkerRequest::RenderTab(index)) => {
+                let mut tab = last_diagnostic.render_tab(index).unwrap_or_else(|| Tab {
+                    title: String::new(),
+                    strings: vec!["This tab isn't available anymore, run the puzzle again".to_string()],
+                    content: TabContent::Strings,
+                });
+                tab.lay_out_graph();
+                respond(&WorkerResponse::TabRendered(index, tab));
+                return;
+            }
+            Err(error) => (DayOutput::error(format!("Failed to read puzzle request: {}", error)), Timings::default()),
+        };
+        // The layout of a big graph takes a while, which is better spent here than on the UI thread
+        output.diagnostic.lay_out_graphs();
+        last_diagnostic = output.diagnostic.clone();
+        respond(&WorkerResponse::Finished(PuzzleResponse { output, timings, crashed: false }));
+    });
+    scope.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    on_message.forget();
+}
+
+fn respond(response: &WorkerResponse) {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    match serde_wasm_bindgen::to_value(response) {
+        Ok(message) => {
+            let _ = scope.post_message(&message);
+        }
+        Err(error) => log::error!("Failed to send puzzle output: {}", error),
+    }
+}
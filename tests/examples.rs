@@ -0,0 +1,29 @@
+use advent_of_code_2024::get_days;
+
+/// Runs every day against the examples stored next to it and checks the answers.
+/// Run with `cargo test -- --nocapture` to see every part that was checked.
+#[test]
+fn examples_give_known_answers() {
+    let mut failures = Vec::new();
+    for day in get_days() {
+        for example in day.examples {
+            let Some(part) = example.part() else {
+                continue;
+            };
+            let (output, _) = day.run(example.input, part, &example.parameters(&day));
+            for (part_name, expected, actual) in [
+                ("silver", example.silver, &output.silver_output),
+                ("gold", example.gold, &output.gold_output),
+            ] {
+                if let Some(expected) = expected {
+                    let passed = expected == actual;
+                    println!("{} {}, {}: {}", day.text(), example.name, part_name, if passed { "pass" } else { "FAIL" });
+                    if !passed {
+                        failures.push(format!("{} {}, {}: expected {}, got {}", day.text(), example.name, part_name, expected, actual));
+                    }
+                }
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{} example answers were wrong:\n{}", failures.len(), failures.join("\n"));
+}
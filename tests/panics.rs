@@ -0,0 +1,18 @@
+use advent_of_code_2024::app::{Day, Part};
+use advent_of_code_2024::get_days;
+
+/// A panicking day turns into an error output, and other days still run afterwards.
+#[test]
+fn panics_become_error_output() {
+    let panicking = Day::new(0, "Panics", |_, _, _, _| panic!("on purpose"), &[], &[]);
+    let (output, _) = panicking.run("", Part::Both, &panicking.default_parameters());
+    let entries = output.diagnostic.entries();
+    assert_eq!(entries.len(), 1);
+    assert!(entries[0].message.starts_with("Day 0 panicked: on purpose at tests/panics.rs"), "{}", entries[0].message);
+    assert!(panicking.try_run("", Part::Both, &panicking.default_parameters()).0.is_err());
+
+    let day = &get_days()[0];
+    let example = &day.examples[0];
+    let (output, _) = day.run(example.input, Part::Both, &example.parameters(day));
+    assert_eq!(Some(output.silver_output.as_str()), example.silver);
+}